@@ -28,6 +28,9 @@ use std::path::Path;
 
 use rayon::prelude::*;
 
+use pbbs::common::graph_bin::{is_binary, MappedGraph};
+use pbbs::common::graph_import::{import, ImportFormat, Imported};
+
 
 #[derive(Default, Clone, Copy)]
 pub struct Edge {
@@ -55,6 +58,12 @@ impl Graph {
     }
 
     pub fn from_file<P: AsRef<Path>>(path: P) -> Self {
+        if is_binary(&path) {
+            return Self::from_binary(&MappedGraph::open(path).unwrap());
+        }
+        if let Some(format) = ImportFormat::guess(&path) {
+            return Self::from_import(&import(path, format, false).unwrap());
+        }
         let contents = std::fs::read_to_string(path).unwrap();
 
         let contents = contents.splitn(4, '\n').collect::<Vec<_>>();
@@ -86,4 +95,32 @@ impl Graph {
 
         Self { nodes, edges, n: num_nodes, m: num_edges }
     }
+
+    /// Copies a mapped binary graph (see `pbbs convert`) into the `usize`
+    /// offsets and edge structs that the benchmarks index, so the mapping is
+    /// not kept. `open` checked that the offsets are at most m, so they fit.
+    pub fn from_binary(g: &MappedGraph) -> Self {
+        let nodes = g.offsets().par_iter().map(|&o| o as usize).collect();
+        if g.weighted {
+            eprintln!("Warning: graph is weighted, ignoring weights");
+        }
+        let edges = g.edges()
+            .par_iter()
+            .map(|&t| Edge { target: t as usize })
+            .collect();
+
+        Self { nodes, edges, n: g.n, m: g.m }
+    }
+
+    /// Builds the graph from a SNAP, Matrix Market or DIMACS file (see
+    /// `pbbs convert`).
+    pub fn from_import(g: &Imported) -> Self {
+        let nodes = g.offsets();
+        let edges = g.edges
+            .par_iter()
+            .map(|e| Edge { target: e.1 as usize })
+            .collect();
+
+        Self { nodes, edges, n: g.n, m: g.num_edges() }
+    }
 }
@@ -1,4 +1,8 @@
 use std::time::{ Instant, Duration };
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::Mutex;
+
+use crate::memory::{AllocStats, Watermark};
 // ============================================================================
 // This code is part of RPB.
 // ----------------------------------------------------------------------------
@@ -26,17 +30,100 @@ use std::time::{ Instant, Duration };
 // ============================================================================
 
 
+/// How much [`Timer`]s and `verbose_println!` print.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Verbosity {
+    /// Nothing.
+    Quiet = 0,
+    /// Timer reports (the default).
+    Normal = 1,
+    /// Timer reports and `verbose_println!`s.
+    Verbose = 2,
+}
+
+const UNSET: u8 = u8::MAX;
+static VERBOSITY: AtomicU8 = AtomicU8::new(UNSET);
+
+/// Sets the verbosity of the whole process.
+pub fn set_verbosity(v: Verbosity) {
+    VERBOSITY.store(v as u8, Ordering::Relaxed);
+}
+
+/// Returns the verbosity set by [`set_verbosity`]. Until it is called, the
+/// `PBBS_VERBOSITY` environment variable (`quiet`, `normal` or `verbose`)
+/// decides, and `Normal` if it is not set.
+pub fn verbosity() -> Verbosity {
+    match VERBOSITY.load(Ordering::Relaxed) {
+        0 => Verbosity::Quiet,
+        1 => Verbosity::Normal,
+        2 => Verbosity::Verbose,
+        _ => {
+            let v = match std::env::var("PBBS_VERBOSITY").as_deref() {
+                Ok("quiet") => Verbosity::Quiet,
+                Ok("verbose") => Verbosity::Verbose,
+                _ => Verbosity::Normal,
+            };
+            set_verbosity(v);
+            v
+        }
+    }
+}
+
+/// A phase reported by [`Timer::next`], e.g. `wc`'s `histogram by key`.
+#[derive(Clone, Debug)]
+pub struct Phase {
+    /// `timer:phase`, as printed.
+    pub name: String,
+    pub time: Duration,
+    /// What the phase allocated; `None` without the counting allocator.
+    pub alloc: Option<AllocStats>,
+}
+
+/// A process wide collector of the phases that every [`Timer`] reports, so
+/// that a harness can aggregate them without changing the algorithms.
+pub mod phases {
+    use super::*;
+
+    static ENABLED: AtomicBool = AtomicBool::new(false);
+    static PHASES: Mutex<Vec<Phase>> = Mutex::new(Vec::new());
+
+    /// Starts collecting phases.
+    pub fn enable() { ENABLED.store(true, Ordering::Relaxed); }
+
+    /// Stops collecting phases; the collected ones are kept.
+    pub fn disable() { ENABLED.store(false, Ordering::Relaxed); }
+
+    pub fn is_enabled() -> bool { ENABLED.load(Ordering::Relaxed) }
+
+    /// Returns the phases collected so far, in the order they were reported,
+    /// and clears the collector.
+    pub fn take() -> Vec<Phase> {
+        std::mem::take(&mut *PHASES.lock().unwrap())
+    }
+
+    pub(crate) fn record(name: String, time: Duration, alloc: Option<AllocStats>) {
+        PHASES.lock().unwrap().push(Phase { name, time, alloc });
+    }
+}
+
 /// A timer that can be used to time regions of code.
+///
+/// While [`phases`] is collecting, a timer that was never started still
+/// measures and records its phases, but does not print them.
 pub struct Timer<'a> {
     total_so_far: Duration,
     last: Instant,
     on: bool,
+    collect: bool,
+    /// What the current phase allocated so far.
+    mark: Option<Watermark>,
     name: &'a str,
 }
 
 impl<'a> Timer<'a> {
     /// prints `d` in seconds
     pub fn report(&self, d: Duration, name: &str) {
+        if verbosity() < Verbosity::Normal { return; }
         if name.is_empty() {
             println!("{}:\t{:.6}", self.name, d.as_secs_f64());
         } else {
@@ -50,6 +137,8 @@ impl<'a> Timer<'a> {
             total_so_far: Duration::ZERO,
             last: Instant::now(),
             on: false,
+            collect: phases::is_enabled(),
+            mark: if phases::is_enabled() { Watermark::start() } else { None },
             name,
         }
     }
@@ -57,6 +146,10 @@ impl<'a> Timer<'a> {
     /// Starts the timer.
     pub fn start(&mut self) {
         self.on = true;
+        if phases::is_enabled() {
+            if let Some(m) = self.mark.take() { m.stop(); }
+            self.mark = Watermark::start();
+        }
         self.last = Instant::now();
     }
 
@@ -96,11 +189,21 @@ impl<'a> Timer<'a> {
     }
 
     /// Prints the time since the last `start` or `next`.
+    /// Also records it in [`phases`] if collecting, with what it allocated.
     pub fn next(&mut self, name: &'a str) {
-        let nt = self.next_time();
+        if !self.on && !self.collect { return; }
+        let t = Instant::now();
+        let nt = t - self.last;
+        self.last = t;
         if self.on {
+            self.total_so_far += nt;
             self.report(nt, name);
         }
+        if phases::is_enabled() {
+            let alloc = self.mark.take().map(Watermark::stop);
+            phases::record(format!("{}:{}", self.name, name), nt, alloc);
+            self.mark = Watermark::start();
+        }
     }
 
     /// Prints the total time when timer was on since the last `new` or `reset`.
@@ -109,3 +212,9 @@ impl<'a> Timer<'a> {
         self.report(tt, "total");
     }
 }
+
+impl Drop for Timer<'_> {
+    fn drop(&mut self) {
+        if let Some(m) = self.mark.take() { m.stop(); }
+    }
+}
@@ -46,4 +46,4 @@ mod sample_sort;
 pub use sample_sort::*;
 
 mod get_time;
-pub use get_time::Timer;
+pub use get_time::{Timer, Phase, phases, Verbosity, set_verbosity, verbosity};
@@ -32,7 +32,8 @@ pub mod random;
 pub mod utilities;
 pub mod primitives;
 pub mod hash_table;
+pub mod memory;
 
-pub use internal::Timer;
+pub use internal::{Timer, Phase, phases, Verbosity, set_verbosity, verbosity};
 
 type DefInt = u32;
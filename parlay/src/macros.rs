@@ -54,7 +54,9 @@ macro_rules! make_mut {
 macro_rules! verbose_println {
     ($($arg:tt)*)
         => {
-            #[cfg(pbbsVerbose)]
-            println!($($arg)*);
+            if cfg!(pbbsVerbose)
+                || $crate::verbosity() == $crate::Verbosity::Verbose {
+                println!($($arg)*);
+            }
         };
 }
@@ -0,0 +1,195 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+
+//! Memory usage of the process: its peak resident set size, from `/proc`,
+//! and, with the `count_alloc` feature, the bytes that went through the
+//! global allocator.
+
+use std::fs;
+
+
+/// The peak resident set size (`VmHWM`) of the process in bytes, since it
+/// started or since the last [`reset_peak_rss`]. `None` without `/proc`.
+pub fn peak_rss() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmHWM:"))?;
+    let kb: u64 = line["VmHWM:".len()..].trim().trim_end_matches("kB").trim().parse().ok()?;
+    Some(kb * 1024)
+}
+
+/// Resets the peak resident set size to the current one. Returns false if
+/// the kernel does not allow it, in which case [`peak_rss`] keeps covering
+/// the whole life of the process.
+pub fn reset_peak_rss() -> bool {
+    fs::write("/proc/self/clear_refs", "5").is_ok()
+}
+
+/// What the code between two [`counters`] allocated.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// The bytes allocated, including the ones freed again.
+    pub allocated: u64,
+    /// The most bytes that were live at once.
+    pub peak_live: u64,
+}
+
+/// A snapshot of the counting allocator.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Counters {
+    /// The bytes allocated since the process started.
+    pub allocated: u64,
+    /// The bytes currently allocated.
+    pub live: u64,
+    /// The most bytes live at once, since the process started or within
+    /// the innermost running [`Watermark`].
+    pub peak: u64,
+}
+
+/// Measures what a region of code allocates, including its own peak of live
+/// bytes. Starting one lowers the allocator's peak to the live bytes and
+/// stopping it raises the peak back to what it was before if that is more,
+/// so regions nest: an enclosing region, or a round of the time loop, still
+/// sees the peak from before an inner one started. Watermarks have to be
+/// stopped in the reverse order they were started.
+#[derive(Debug)]
+pub struct Watermark {
+    start: Counters,
+    #[cfg(feature = "count_alloc")]
+    outer_peak: u64,
+}
+
+impl Watermark {
+    /// `None` without the `count_alloc` feature or when [`CountingAlloc`] is
+    /// not the global allocator.
+    pub fn start() -> Option<Self> {
+        #[cfg(feature = "count_alloc")]
+        {
+            if !counting::installed() { return None; }
+            let outer_peak = counting::lower_peak();
+            Some(Watermark { start: counting::counters(), outer_peak })
+        }
+        #[cfg(not(feature = "count_alloc"))]
+        { None }
+    }
+
+    /// What was allocated since `start`, and the most bytes live at once.
+    pub fn stop(self) -> AllocStats {
+        let now = counters().unwrap_or_default();
+        #[cfg(feature = "count_alloc")]
+        counting::raise_peak(self.outer_peak);
+        AllocStats {
+            allocated: now.allocated - self.start.allocated,
+            peak_live: now.peak.max(self.start.live),
+        }
+    }
+}
+
+#[cfg(feature = "count_alloc")]
+mod counting {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicU64, Ordering::Relaxed};
+
+    use super::Counters;
+
+    static ALLOCATED: AtomicU64 = AtomicU64::new(0);
+    static LIVE: AtomicU64 = AtomicU64::new(0);
+    static PEAK: AtomicU64 = AtomicU64::new(0);
+
+    /// The system allocator, counting the bytes that go through it. The
+    /// counts only cover the programs that make it their global allocator:
+    ///
+    /// ```ignore
+    /// #[global_allocator]
+    /// static GLOBAL: parlay::memory::CountingAlloc = parlay::memory::CountingAlloc;
+    /// ```
+    pub struct CountingAlloc;
+
+    fn grow(n: usize) {
+        ALLOCATED.fetch_add(n as u64, Relaxed);
+        let live = LIVE.fetch_add(n as u64, Relaxed) + n as u64;
+        PEAK.fetch_max(live, Relaxed);
+    }
+
+    fn shrink(n: usize) { LIVE.fetch_sub(n as u64, Relaxed); }
+
+    unsafe impl GlobalAlloc for CountingAlloc {
+        unsafe fn alloc(&self, l: Layout) -> *mut u8 {
+            let p = System.alloc(l);
+            if !p.is_null() { grow(l.size()); }
+            p
+        }
+
+        unsafe fn alloc_zeroed(&self, l: Layout) -> *mut u8 {
+            let p = System.alloc_zeroed(l);
+            if !p.is_null() { grow(l.size()); }
+            p
+        }
+
+        unsafe fn dealloc(&self, p: *mut u8, l: Layout) {
+            System.dealloc(p, l);
+            shrink(l.size());
+        }
+
+        unsafe fn realloc(&self, p: *mut u8, l: Layout, size: usize) -> *mut u8 {
+            let q = System.realloc(p, l, size);
+            if !q.is_null() {
+                if size > l.size() { grow(size - l.size()); }
+                else { shrink(l.size() - size); }
+            }
+            q
+        }
+    }
+
+    /// Whether [`CountingAlloc`] is the global allocator, which has made
+    /// some allocations by the time anyone asks.
+    pub fn installed() -> bool { ALLOCATED.load(Relaxed) > 0 }
+
+    pub fn counters() -> Counters {
+        Counters {
+            allocated: ALLOCATED.load(Relaxed),
+            live: LIVE.load(Relaxed),
+            peak: PEAK.load(Relaxed),
+        }
+    }
+
+    /// Lowers the peak to the live bytes and returns what it was.
+    pub fn lower_peak() -> u64 { PEAK.swap(LIVE.load(Relaxed), Relaxed) }
+
+    pub fn raise_peak(p: u64) { PEAK.fetch_max(p, Relaxed); }
+}
+
+#[cfg(feature = "count_alloc")]
+pub use counting::CountingAlloc;
+
+/// The counting allocator's counters; `None` without the `count_alloc`
+/// feature or when [`CountingAlloc`] is not the global allocator.
+pub fn counters() -> Option<Counters> {
+    #[cfg(feature = "count_alloc")]
+    { counting::installed().then(counting::counters) }
+    #[cfg(not(feature = "count_alloc"))]
+    { None }
+}
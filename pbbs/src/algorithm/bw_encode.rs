@@ -26,10 +26,8 @@ use rayon::prelude::*;
 // ============================================================================
 
 
-mod suffix_array;
-
 use crate::{DefChar, DefInt};
-use suffix_array::suffix_array;
+use super::suffix_array::suffix_array;
 use parlay::maybe_uninit_vec;
 
 
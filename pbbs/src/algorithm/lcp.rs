@@ -30,7 +30,7 @@ use enhanced_rayon::prelude::*;
 use parlay::{Timer, maybe_uninit_vec};
 use parlay::primitives::pack_index;
 use crate::{DefChar, DefInt, DefAtomInt, ORDER};
-use crate::range_min::{RangeMin, AtomU32RangeMin};
+use crate::algorithm::range_min::{RangeMin, AtomU32RangeMin};
 
 #[allow(dead_code)]
 pub fn lcp(s: &[DefChar], sa: &[DefInt]) -> Vec<DefInt> {
@@ -1,4 +1,4 @@
-mod range_min;
+pub mod range_min;
 // ============================================================================
 // This code is part of RPB.
 // ----------------------------------------------------------------------------
@@ -26,6 +26,7 @@ mod range_min;
 // ============================================================================
 
 
-pub(crate) mod lcp;
-pub(crate) mod union_find;
-pub(crate) mod suffix_array;
+pub mod lcp;
+pub mod union_find;
+pub mod suffix_array;
+pub mod bw_encode;
@@ -1,4 +1,3 @@
-#[path="../common/atomics.rs"] mod atomics;
 // ============================================================================
 // This code is part of RPB.
 // ----------------------------------------------------------------------------
@@ -29,7 +28,7 @@
 use std::mem::swap;
 use std::sync::atomic::Ordering;
 
-use atomics::atomic_cas;
+use crate::common::atomics::atomic_cas;
 use crate::{DefIntS, DefAtomIntS};
 
 static ORD: Ordering = Ordering::Relaxed;
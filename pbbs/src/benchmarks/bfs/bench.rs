@@ -0,0 +1,83 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::common::graph::Graph;
+use crate::common::graph_io::read_graph_from_file;
+use crate::common::io::{read_file_to_vec, write_sequence, InputError};
+use crate::runner::{parses, Benchmark, Param, Params};
+use super::{check, det_bfs, dir_opt_bfs, nd_bfs};
+
+
+pub struct Bfs;
+
+impl Benchmark for Bfs {
+    type Input = Graph;
+    type Output = Vec<i32>;
+
+    fn name(&self) -> &'static str { "bfs" }
+    fn about(&self) -> &'static str { "breadth first search" }
+    fn algorithms(&self) -> &'static [&'static str] { &["ndbfs", "dobfs", "detbfs"] }
+
+    fn params(&self) -> &'static [Param] {
+        &[Param {
+            name: "source",
+            default: Some("0"),
+            help: "the source vertex to start BFS from",
+            valid: parses::<usize>,
+        }]
+    }
+
+    fn read_input(&self, fname: &str, ps: &Params) -> Result<Graph, InputError> {
+        let mut g = read_graph_from_file(fname, ps.bad_lines)?;
+        g.add_degrees();
+        Ok(g)
+    }
+
+    fn read_output(&self, fname: &str, _inp: &Self::Input, ps: &Params) -> Result<Vec<i32>, InputError> {
+        read_file_to_vec(fname, Some("sequenceInt"), ps.bad_lines)
+    }
+
+    fn output(&self, _g: &Graph, _ps: &Params) -> Vec<i32> { vec![] }
+
+    fn run(&self, alg: &str, g: &Graph, ps: &Params, out: &mut Vec<i32>) {
+        let f = match alg {
+            "ndbfs" => nd_bfs::bfs,
+            "dobfs" => dir_opt_bfs::bfs,
+            "detbfs" => det_bfs::bfs,
+            _ => unreachable!(),
+        };
+        *out = f(ps.get("source"), g, false);
+    }
+
+    /// Any BFS tree is accepted, not only the one a serial BFS finds.
+    fn check(&self, g: &Graph, out: &Vec<i32>, ps: &Params) -> Option<bool> {
+        Some(check::is_bfs_tree(g, out, ps.get("source")))
+    }
+
+    fn write(&self, out: &Vec<i32>, fname: &str) {
+        write_sequence(out, "sequenceInt", fname).expect("cannot write to output");
+    }
+}
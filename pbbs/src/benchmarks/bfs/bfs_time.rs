@@ -1,21 +1,15 @@
-use core::panic;
-use std::time::Duration;
 use clap::Parser;
 use rayon::prelude::*;
-use std::collections::VecDeque;
 
-#[path ="mod.rs"] mod bfs;
-#[path ="../../misc.rs"] mod misc;
-#[path ="../macros.rs"] mod macros;
-#[path ="../../common/io.rs"] mod io;
-#[path ="../../common/mod.rs"] mod common;
-#[path ="../../common/graph.rs"] mod graph;
-#[path ="../../common/graph_io.rs"] mod graph_io;
 
-use misc::*;
-use graph::Graph;
-use graph_io::read_graph_from_file;
-use io::write_slice_to_file_seq;
+use pbbs::{count_alloc, define_algs, init, report};
+use pbbs::benchmarks::bfs;
+use pbbs::common::graph::Graph;
+use pbbs::common::graph_io::read_graph_from_file;
+use pbbs::common::io::{write_sequence, BadLines};
+use pbbs::common::time_loop::{RunStats, TimeConfig};
+use pbbs::common::report::ReportFormat;
+use pbbs::common::pool::Pinning;
 
 #[derive(Parser, Debug)]
 #[clap(version, about, long_about = None)]
@@ -32,9 +26,17 @@ struct Args {
     #[clap(value_parser, required=true)]
     ifname: String,
 
-    /// the number of rounds to execute the benchmark
-    #[clap(short, long, value_parser, required=false, default_value_t=1)]
-    rounds: usize,
+    #[clap(flatten)]
+    time: TimeConfig,
+
+    /// print a machine-readable record of the run
+    #[clap(long, value_enum, required=false)]
+    report: Option<ReportFormat>,
+
+    /// how to pin worker threads: none, compact, scatter,
+    /// physical-cores-first, or a cpu list such as 0-3,8
+    #[clap(long, default_value_t)]
+    pin: Pinning,
 
     /// The source vertex to start BFS from
     #[clap(short = 's', long, value_parser, required = false, default_value_t = 0)]
@@ -49,31 +51,33 @@ struct Args {
     check: bool,
 }
 
+count_alloc!();
+
 define_algs!(
-    (NDBFS, "ndbfs")
+    (NDBFS, "ndbfs"),
+    (DOBFS, "dobfs"),
+    (DETBFS, "detbfs")
 );
 
 pub fn run(
     alg: Algs,
-    rounds: usize, 
+    tc: &TimeConfig, 
     g: &Graph,
     source: usize,
     verbose: bool
-) -> (Vec<i32>, Duration) {
-    let n = g.num_vertices();
-    let mut parents = vec![-1; n];
-    let parents_ptr = &parents as *const Vec<i32> as usize;
-
+) -> (Vec<i32>, RunStats) {
     let f = match alg {
         Algs::NDBFS => {bfs::nd_bfs::bfs}
+        Algs::DOBFS => {bfs::dir_opt_bfs::bfs}
+        Algs::DETBFS => {bfs::det_bfs::bfs}
     };
 
-    let mean = time_loop(
+    let mut parents = vec![];
+    let stats = time_loop(
         "bfs",
-        rounds,
-        Duration::new(1, 0),
-        || { unsafe { *(parents_ptr as *mut Vec<i32>).as_mut().unwrap() = vec![-1; n]; } },
-        || { f(source, g, verbose); },
+        tc,
+        || {},
+        || { parents = f(source, g, verbose); },
         || {},
     );
 
@@ -82,118 +86,39 @@ pub fn run(
         println!("total visited = {}", visited);
     }
 
-    (parents, mean)
-}
-
-fn check(g: &Graph, parents: &[i32], source: usize) -> bool {
-    let n = g.num_vertices();
-    
-    // Check basic properties
-    if parents.len() != n {
-        println!("Parent array size mismatch");
-        return false;
-    }
-
-    // Check source vertex
-    if parents[source] != source as i32 {
-        println!("Source vertex doesn't point to itself");
-        return false;
-    }
-
-    // Do a sequential BFS to get correct parent array
-    let mut correct_parents = vec![-1; n];
-    let mut queue = VecDeque::new();
-    let mut visited = vec![false; n];
-
-    // Initialize with source
-    queue.push_back(source);
-    visited[source] = true;
-    correct_parents[source] = source as i32;
-
-    // Standard BFS
-    while let Some(v) = queue.pop_front() {
-        let vertex = g.index(v);
-        for &u in vertex.neighbors {
-            let u = u as usize;
-            if !visited[u] {
-                visited[u] = true;
-                queue.push_back(u);
-                correct_parents[u] = v as i32;
-            }
-        }
-    }
-
-    // Compare results
-    let mut is_correct = true;
-    for i in 0..n {
-        if parents[i] != correct_parents[i] {
-            println!("Mismatch at vertex {}: got {}, expected {}", 
-                    i, parents[i], correct_parents[i]);
-            is_correct = false;
-        }
-    }
-
-    // Check that parent relationships form valid paths to source
-    for i in 0..n {
-        if parents[i] != -1 {
-            // Check path from i to source
-            let mut current = i;
-            let mut path_length = 0;
-            let max_path_length = n; // No path should be longer than n
-            
-            while current != source && path_length < max_path_length {
-                let parent = parents[current] as usize;
-                
-                // Verify edge exists in graph
-                let vertex = g.index(parent);
-                if !vertex.neighbors.contains(&(current as u32)) {
-                    println!("Invalid edge in parent relationship: {} -> {}", parent, current);
-                    return false;
-                }
-                
-                current = parent;
-                path_length += 1;
-            }
-            
-            if path_length >= max_path_length {
-                println!("Cycle detected in parent relationships");
-                return false;
-            }
-        }
-    }
-
-    is_correct
+    (parents, stats)
 }
 
 fn main() {
-    init!();
     let args = Args::parse();
+    init!(&args.pin);
 
     // Read graph from file
-    let mut g = read_graph_from_file(&args.ifname);
+    let mut g = read_graph_from_file(&args.ifname, BadLines::Skip)
+        .unwrap_or_else(|e| panic!("{e}"));
     g.add_degrees();
 
     // Run BFS
     let (parents, duration) = run(
         args.algorithm,
-        args.rounds,
+        &args.time,
         &g,
         args.source,
         args.verbose
     );
 
     // Check results if requested
-    if args.check {
-        panic!("Current implementation only implements nd_bfs which does not support results checking");
+    let checked = args.check
+        .then(|| bfs::check::is_bfs_tree(&g, &parents, args.source));
+    if let Some(res) = checked {
+        println!("Result is {}", res);
     }
 
     // Write results if output file specified
     if !args.ofname.is_empty() {
-        let result: Vec<String> = parents.iter()
-            .map(|x| x.to_string())
-            .collect();
-        write_slice_to_file_seq(&result, &args.ofname);
+        write_sequence(&parents, "sequenceInt", &args.ofname).expect("cannot write to output");
     }
 
-    println!("Runtime {:?}", duration);
-}
\ No newline at end of file
+    println!("Runtime {:?}", duration.mean);
+    report!(args, duration, checked);
+}
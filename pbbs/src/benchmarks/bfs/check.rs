@@ -0,0 +1,79 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use rayon::prelude::*;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::common::graph::Graph;
+
+/// Checks that `parents` is a BFS tree of `g` from `source`: the source is
+/// its own parent, exactly the vertices reachable from it have a parent,
+/// every parent has an edge to its child, and every child is one level
+/// below its parent, with levels from a serial BFS.
+pub fn is_bfs_tree(g: &Graph, parents: &[i32], source: usize) -> bool {
+    let n = g.num_vertices();
+    if parents.len() != n || source >= n || parents[source] != source as i32 {
+        return false;
+    }
+
+    let mut level = vec![usize::MAX; n];
+    let mut queue = VecDeque::from([source]);
+    level[source] = 0;
+    while let Some(v) = queue.pop_front() {
+        for &u in g.index(v).neighbors {
+            let u = u as usize;
+            if level[u] == usize::MAX {
+                level[u] = level[v] + 1;
+                queue.push_back(u);
+            }
+        }
+    }
+
+    let levels_ok = (0..n).into_par_iter().all(|v| {
+        match parents[v] {
+            -1 => level[v] == usize::MAX,
+            p if p < 0 || p as usize >= n => false,
+            _ if v == source => true,
+            p => level[v] != usize::MAX && level[p as usize] + 1 == level[v],
+        }
+    });
+    if !levels_ok { return false; }
+
+    // one pass over the edges, rather than a search of each parent's list
+    let has_edge: Vec<AtomicBool> = (0..n)
+        .into_par_iter()
+        .map(|v| AtomicBool::new(v == source || parents[v] == -1))
+        .collect();
+    (0..n).into_par_iter().for_each(|p| {
+        for &v in g.index(p).neighbors {
+            if parents[v as usize] == p as i32 {
+                has_edge[v as usize].store(true, Ordering::Relaxed);
+            }
+        }
+    });
+    has_edge.par_iter().all(|e| e.load(Ordering::Relaxed))
+}
@@ -0,0 +1,102 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use rayon::prelude::*;
+use enhanced_rayon::prelude::*;
+use std::sync::atomic::{AtomicI32, Ordering};
+use parlay::internal::sequence_ops::scan_inplace;
+use parlay::primitives::pack;
+use crate::common::graph::*;
+use crate::common::spec_for::Reservation;
+
+/// Deterministic BFS, as PBBS's deterministicBFS: every unvisited vertex
+/// reached in a round takes as its parent the frontier vertex that comes
+/// first in the frontier, whatever the thread schedule. Each round has two
+/// phases: the frontier vertices write-min their frontier position into the
+/// reservations of their unvisited neighbors, then each frontier vertex
+/// commits the neighbors it holds the reservation of, in adjacency order, so
+/// the next frontier is in the same order on every run. Returns the parent
+/// array, like `nd_bfs::bfs`.
+pub fn bfs(source: usize, g: &Graph, verbose: bool) -> Vec<i32> {
+    let n = g.num_vertices();
+    let parents: Vec<AtomicI32> = (0..n)
+        .into_par_iter()
+        .map(|_| AtomicI32::new(-1))
+        .collect();
+    let reservations: Vec<Reservation> = (0..n)
+        .into_par_iter()
+        .map(|_| Reservation::new())
+        .collect();
+    parents[source].store(source as i32, Ordering::Relaxed);
+
+    let mut frontier = vec![source as i32];
+    let mut total_visited = 0;
+    let mut round = 0;
+
+    while !frontier.is_empty() {
+        total_visited += frontier.len();
+        round += 1;
+
+        // reserve
+        frontier.par_iter().enumerate().for_each(|(i, &v)| {
+            for &ngh in g.index(v as usize).neighbors {
+                if parents[ngh as usize].load(Ordering::Relaxed) == -1 {
+                    reservations[ngh as usize].reserve(i as u32);
+                }
+            }
+        });
+
+        // commit, into each frontier vertex's slots of the next frontier
+        let mut offsets: Vec<usize> = frontier
+            .par_iter()
+            .map(|&v| g.index(v as usize).degree)
+            .collect();
+        let total = scan_inplace(&mut offsets, false, |a, b| a + b);
+        let mut next = vec![-1; total];
+        next.par_ind_chunks_mut(&offsets)
+            .zip(frontier.par_iter())
+            .enumerate()
+            .for_each(|(i, (slots, &v))| {
+                let vertex = g.index(v as usize);
+                for (slot, &ngh) in slots.iter_mut().zip(vertex.neighbors) {
+                    if reservations[ngh as usize].check_reset(i as u32) {
+                        parents[ngh as usize].store(v, Ordering::Relaxed);
+                        *slot = ngh as i32;
+                    }
+                }
+            });
+
+        let flags: Vec<bool> = next.par_iter().map(|&x| x >= 0).collect();
+        pack(&next, &flags, &mut frontier);
+    }
+
+    if verbose {
+        println!("BFS completed in {} rounds", round);
+        println!("Visited {} vertices", total_visited);
+    }
+
+    parents.into_par_iter().map(AtomicI32::into_inner).collect()
+}
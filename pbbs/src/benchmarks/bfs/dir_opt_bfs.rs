@@ -0,0 +1,170 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicI32, Ordering};
+use crate::common::graph::*;
+
+/// Go bottom-up once the frontier's edges are more than 1/ALPHA of the
+/// edges not yet explored.
+const ALPHA: usize = 14;
+/// Go back top-down once a shrinking frontier has fewer than n/BETA vertices.
+const BETA: usize = 24;
+
+enum Frontier {
+    /// The frontier's vertices, for top-down steps.
+    Sparse(Vec<usize>),
+    /// A flag per vertex, for bottom-up steps.
+    Dense(Vec<bool>),
+}
+
+impl Frontier {
+    fn len(&self) -> usize {
+        match self {
+            Frontier::Sparse(f) => f.len(),
+            Frontier::Dense(f) => f.par_iter().filter(|&&b| b).count(),
+        }
+    }
+
+    fn out_edges(&self, g: &Graph) -> usize {
+        match self {
+            Frontier::Sparse(f) => f
+                .par_iter()
+                .map(|&v| g.index(v).neighbors.len())
+                .sum(),
+            Frontier::Dense(f) => f
+                .par_iter()
+                .enumerate()
+                .filter(|(_, &b)| b)
+                .map(|(v, _)| g.index(v).neighbors.len())
+                .sum(),
+        }
+    }
+
+    fn into_sparse(self) -> Vec<usize> {
+        match self {
+            Frontier::Sparse(f) => f,
+            Frontier::Dense(f) => f
+                .par_iter()
+                .enumerate()
+                .filter(|(_, &b)| b)
+                .map(|(v, _)| v)
+                .collect(),
+        }
+    }
+
+    fn into_dense(self, n: usize) -> Vec<bool> {
+        match self {
+            Frontier::Sparse(f) => {
+                let mut d = vec![false; n];
+                f.iter().for_each(|&v| d[v] = true);
+                d
+            }
+            Frontier::Dense(f) => f,
+        }
+    }
+}
+
+/// Claims the unvisited neighbors of the frontier, as `nd_bfs` does.
+fn top_down(g: &Graph, parents: &[AtomicI32], f: &[usize]) -> Frontier {
+    Frontier::Sparse(f
+        .par_iter()
+        .flat_map_iter(|&v| {
+            g.index(v).neighbors.iter().map(|&u| u as usize).filter(move |&u| {
+                parents[u].load(Ordering::Relaxed) == -1 &&
+                parents[u].compare_exchange(
+                    -1, v as i32, Ordering::SeqCst, Ordering::Relaxed
+                ).is_ok()
+            })
+        })
+        .collect())
+}
+
+/// Every unvisited vertex takes its first neighbor in the frontier as its
+/// parent. Each vertex only writes its own parent, so there are no races.
+fn bottom_up(g: &Graph, parents: &[AtomicI32], f: &[bool]) -> Frontier {
+    Frontier::Dense((0..g.num_vertices())
+        .into_par_iter()
+        .map(|v| {
+            if parents[v].load(Ordering::Relaxed) != -1 { return false; }
+            match g.index(v).neighbors.iter().find(|&&u| f[u as usize]) {
+                Some(&u) => {
+                    parents[v].store(u as i32, Ordering::Relaxed);
+                    true
+                }
+                None => false,
+            }
+        })
+        .collect())
+}
+
+/// Direction-optimizing BFS (Beamer et al., SC'12). Top-down steps expand
+/// the frontier's edges; bottom-up steps have the unvisited vertices look
+/// for a parent in the frontier, which touches far fewer edges once the
+/// frontier holds a large part of the graph. Bottom-up steps search a
+/// vertex's own neighbors, so `g` must be symmetric, as the PBBS BFS inputs
+/// are. Returns the parent array, like `nd_bfs::bfs`.
+pub fn bfs(source: usize, g: &Graph, verbose: bool) -> Vec<i32> {
+    let n = g.num_vertices();
+    let parents: Vec<AtomicI32> = (0..n)
+        .into_par_iter()
+        .map(|_| AtomicI32::new(-1))
+        .collect();
+    parents[source].store(source as i32, Ordering::Relaxed);
+
+    let mut frontier = Frontier::Sparse(vec![source]);
+    let mut unexplored = g.edges.len();
+    let (mut size, mut prev_size) = (1, 0);
+    let (mut total_visited, mut round, mut bottom_up_rounds) = (0, 0, 0);
+
+    while size > 0 {
+        total_visited += size;
+        round += 1;
+
+        let edges = frontier.out_edges(g);
+        unexplored = unexplored.saturating_sub(edges);
+        let go_bottom_up = match frontier {
+            Frontier::Sparse(_) => edges > unexplored / ALPHA,
+            Frontier::Dense(_) => size >= n / BETA || size > prev_size,
+        };
+
+        frontier = if go_bottom_up {
+            bottom_up_rounds += 1;
+            bottom_up(g, &parents, &frontier.into_dense(n))
+        } else {
+            top_down(g, &parents, &frontier.into_sparse())
+        };
+        prev_size = size;
+        size = frontier.len();
+    }
+
+    if verbose {
+        println!("BFS completed in {} rounds ({} bottom-up)", round, bottom_up_rounds);
+        println!("Visited {} vertices", total_visited);
+    }
+
+    parents.into_par_iter().map(AtomicI32::into_inner).collect()
+}
@@ -1 +1,5 @@
-pub(crate) mod nd_bfs;
\ No newline at end of file
+pub mod nd_bfs;
+pub mod dir_opt_bfs;
+pub mod det_bfs;
+pub mod check;
+pub mod bench;
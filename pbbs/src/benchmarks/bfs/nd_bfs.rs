@@ -1,67 +1,170 @@
 use rayon::prelude::*;
-use std::sync::atomic::{AtomicBool, Ordering};
-use crate::graph::*;
-use std::sync::{Arc, Mutex};
+use enhanced_rayon::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use parlay::internal::sequence_ops::scan_inplace;
+use parlay::primitives::{pack, pack_index};
+use crate::common::graph::*;
 
-pub fn bfs(source: usize, g: &Graph, verbose: bool) -> (usize, usize) {
-    let n = g.num_vertices();
-    let _m = g.num_edges();
-    
-    let visited: Vec<AtomicBool> = (0..n)
+/// A frontier goes dense once it and its edges are more than 1/DENSE of the
+/// graph's edges, as in Ligra's edgeMap.
+const DENSE: usize = 20;
+
+enum Frontier {
+    /// The frontier's vertices.
+    Sparse(Vec<i32>),
+    /// A flag per vertex, for frontiers with a large part of the graph.
+    Dense(Vec<AtomicBool>),
+}
+
+impl Frontier {
+    fn len(&self) -> usize {
+        match self {
+            Frontier::Sparse(f) => f.len(),
+            Frontier::Dense(f) => f
+                .par_iter()
+                .filter(|b| b.load(Ordering::Relaxed))
+                .count(),
+        }
+    }
+
+    fn out_edges(&self, g: &Graph) -> usize {
+        match self {
+            Frontier::Sparse(f) => f
+                .par_iter()
+                .map(|&v| g.index(v as usize).degree)
+                .sum(),
+            Frontier::Dense(f) => f
+                .par_iter()
+                .enumerate()
+                .filter(|(_, b)| b.load(Ordering::Relaxed))
+                .map(|(v, _)| g.index(v).degree)
+                .sum(),
+        }
+    }
+
+    fn into_sparse(self) -> Vec<i32> {
+        match self {
+            Frontier::Sparse(f) => f,
+            Frontier::Dense(f) => {
+                let flags: Vec<bool> = f
+                    .into_par_iter()
+                    .map(AtomicBool::into_inner)
+                    .collect();
+                let mut sparse = vec![];
+                pack_index(&flags, &mut sparse);
+                sparse
+            }
+        }
+    }
+
+    fn into_dense(self, n: usize) -> Vec<AtomicBool> {
+        match self {
+            Frontier::Sparse(f) => {
+                let d: Vec<AtomicBool> = (0..n)
+                    .into_par_iter()
+                    .map(|_| AtomicBool::new(false))
+                    .collect();
+                f.par_iter().for_each(|&v| d[v as usize].store(true, Ordering::Relaxed));
+                d
+            }
+            Frontier::Dense(f) => f,
+        }
+    }
+}
+
+/// Makes `v` the parent of `ngh` if no other frontier vertex got there first.
+#[inline(always)]
+fn claim(parents: &[AtomicI32], ngh: usize, v: usize) -> bool {
+    parents[ngh].load(Ordering::Relaxed) == -1 &&
+    parents[ngh].compare_exchange(
+        -1, v as i32, Ordering::SeqCst, Ordering::Relaxed
+    ).is_ok()
+}
+
+/// Every frontier vertex writes the neighbors it claims into its own slots,
+/// at its degree prefix offset, and the unclaimed slots are packed out.
+fn sparse_step(g: &Graph, parents: &[AtomicI32], frontier: &[i32]) -> Vec<i32> {
+    let mut offsets: Vec<usize> = frontier
+        .par_iter()
+        .map(|&v| g.index(v as usize).degree)
+        .collect();
+    let total = scan_inplace(&mut offsets, false, |a, b| a + b);
+
+    let mut next = vec![-1; total];
+    next.par_ind_chunks_mut(&offsets)
+        .zip(frontier.par_iter())
+        .for_each(|(slots, &v)| {
+            let vertex = g.index(v as usize);
+            for (slot, &ngh) in slots.iter_mut().zip(vertex.neighbors) {
+                if claim(parents, ngh as usize, v as usize) {
+                    *slot = ngh as i32;
+                }
+            }
+        });
+
+    let flags: Vec<bool> = next.par_iter().map(|&x| x >= 0).collect();
+    let mut frontier_next = vec![];
+    pack(&next, &flags, &mut frontier_next);
+    frontier_next
+}
+
+/// Same as `sparse_step`, but over a flag per vertex, which saves the slots
+/// and the packing when most vertices are in the frontier.
+fn dense_step(g: &Graph, parents: &[AtomicI32], frontier: &[AtomicBool]) -> Vec<AtomicBool> {
+    let next: Vec<AtomicBool> = (0..g.num_vertices())
+        .into_par_iter()
         .map(|_| AtomicBool::new(false))
         .collect();
-    let mut frontier = vec![source];
-    
+    frontier
+        .par_iter()
+        .enumerate()
+        .filter(|(_, b)| b.load(Ordering::Relaxed))
+        .for_each(|(v, _)| {
+            for &ngh in g.index(v).neighbors {
+                if claim(parents, ngh as usize, v) {
+                    next[ngh as usize].store(true, Ordering::Relaxed);
+                }
+            }
+        });
+    next
+}
+
+/// Returns the BFS tree from `source` as a parent array: the source is its own
+/// parent and unreachable vertices have parent -1.
+pub fn bfs(source: usize, g: &Graph, verbose: bool) -> Vec<i32> {
+    let n = g.num_vertices();
+    let m = g.edges.len();
+
+    let parents: Vec<AtomicI32> = (0..n)
+        .into_par_iter()
+        .map(|_| AtomicI32::new(-1))
+        .collect();
+    let mut frontier = Frontier::Sparse(vec![source as i32]);
+
     // Mark source as visited
-    visited[source].store(true, Ordering::SeqCst);
+    parents[source].store(source as i32, Ordering::SeqCst);
     let mut total_visited = 0;
     let mut round = 0;
+    let mut size = 1;
 
     // Continue while frontier is not empty
-    while !frontier.is_empty() {
-        total_visited += frontier.len();
+    while size > 0 {
+        total_visited += size;
         round += 1;
 
-        let mut offsets: Vec<usize> = (0..frontier.len())
-            .into_par_iter()
-            .map(|i| g.index(frontier[i]).degree)
-            .collect();
-
-        let mut total = 0;
-        for offset in &mut offsets {
-            let curr = *offset;
-            *offset = total;
-            total += curr;
-        }
-        let total_size = total;
-
-        let frontier_next = vec![-1; total_size];
-
-        let f_next = Arc::new(Mutex::new(frontier_next));
-
-        frontier
-            .par_iter()
-            .enumerate()
-            .for_each(|(i, &v)| {
-                let vertex = g.index(v);
-                let offset = offsets[i];
-                
-                for (j, &ngh) in vertex.neighbors.iter().enumerate() {
-                    let ngh = ngh as usize;
-                    if !visited[ngh].load(Ordering::Relaxed) &&
-                       !visited[ngh].swap(true, Ordering::SeqCst) {
-                        let mut fnxt = f_next.lock().unwrap();
-                        fnxt[offset + j] = ngh as i32;
-                    }
-                }
-            });
-
-        frontier = f_next.lock().unwrap()
-            .iter()
-            .cloned()
-            .filter(|&x| x >= 0)
-            .map(|x| x as usize)
-            .collect();
+        let dense = size + frontier.out_edges(g) > m / DENSE;
+        frontier = match (frontier, dense) {
+            (Frontier::Sparse(f), false) => {
+                Frontier::Sparse(sparse_step(g, &parents, &f))
+            }
+            (f @ Frontier::Dense(_), false) => {
+                Frontier::Sparse(sparse_step(g, &parents, &f.into_sparse()))
+            }
+            (f, true) => {
+                Frontier::Dense(dense_step(g, &parents, &f.into_dense(n)))
+            }
+        };
+        size = frontier.len();
     }
 
     if verbose {
@@ -69,5 +172,5 @@ pub fn bfs(source: usize, g: &Graph, verbose: bool) -> (usize, usize) {
         println!("Visited {} vertices", total_visited);
     }
 
-    (total_visited, round)
-}
\ No newline at end of file
+    parents.into_par_iter().map(AtomicI32::into_inner).collect()
+}
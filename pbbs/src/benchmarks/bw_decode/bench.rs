@@ -0,0 +1,85 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::DefChar;
+use crate::algorithm::bw_encode::bw_encode;
+use crate::common::io::{chars_from_file, chars_to_file, InputError};
+use crate::runner::{Benchmark, Params};
+use super::list_rank;
+
+
+pub struct BwDecode;
+
+impl Benchmark for BwDecode {
+    /// The original text and its encoding.
+    type Input = (Vec<DefChar>, Vec<DefChar>);
+    type Output = Vec<DefChar>;
+
+    fn name(&self) -> &'static str { "bw" }
+    fn about(&self) -> &'static str { "Burrows-Wheeler decode" }
+    fn algorithms(&self) -> &'static [&'static str] { &["list-rank"] }
+
+    fn read_input(&self, fname: &str, _ps: &Params) -> Result<Self::Input, InputError> {
+        let text = chars_from_file(fname, false).map_err(|e| InputError::io(fname, e))?;
+        let encoded = bw_encode(&text);
+        Ok((text, encoded))
+    }
+
+    fn read_output(&self, fname: &str, _inp: &Self::Input, _ps: &Params) -> Result<Vec<DefChar>, InputError> {
+        chars_from_file(fname, false).map_err(|e| InputError::io(fname, e))
+    }
+
+    fn output(&self, _inp: &Self::Input, _ps: &Params) -> Vec<DefChar> {
+        vec![]
+    }
+
+    fn run(
+        &self,
+        alg: &str,
+        inp: &Self::Input,
+        _ps: &Params,
+        out: &mut Vec<DefChar>
+    ) {
+        let f = match alg {
+            "list-rank" => list_rank::bw_decode,
+            _ => unreachable!(),
+        };
+        *out = f(&inp.1);
+    }
+
+    fn check(
+        &self,
+        inp: &Self::Input,
+        out: &Vec<DefChar>,
+        _ps: &Params
+    ) -> Option<bool> {
+        Some(inp.0 == *out)
+    }
+
+    fn write(&self, out: &Vec<DefChar>, fname: &str) {
+        chars_to_file(out, fname).unwrap();
+    }
+}
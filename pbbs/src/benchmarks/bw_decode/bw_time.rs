@@ -1,4 +1,3 @@
-use std::time::Duration;
 // ============================================================================
 // This code is part of RPB.
 // ----------------------------------------------------------------------------
@@ -26,51 +25,50 @@ use std::time::Duration;
 // ============================================================================
 
 
-#[path ="mod.rs"] mod bw;
-#[path ="../../misc.rs"] mod misc;
-#[path ="../macros.rs"] mod macros;
-#[path ="../../common/io.rs"] mod io;
-#[path ="../../algorithm/bw_encode.rs"] mod bw_encode;
-
-use misc::*;
-use bw_encode::bw_encode;
-use io::{chars_from_file, chars_to_file};
+use pbbs::{count_alloc, define_algs, define_args, init, finalize};
+use pbbs::DefChar;
+use pbbs::benchmarks::bw_decode as bw;
+use pbbs::algorithm::bw_encode::bw_encode;
+use pbbs::common::io::{chars_from_file, chars_to_file};
+use pbbs::common::time_loop::{RunStats, TimeConfig};
 
 define_args!(Algs::ListRank);
 
+count_alloc!();
+
 define_algs!((ListRank, "list-rank"));
 
-pub fn run(alg: Algs, rounds: usize, inp: &[DefChar]) -> (Vec<DefChar>, Duration) {
+pub fn run(alg: Algs, tc: &TimeConfig, inp: &[DefChar]) -> (Vec<DefChar>, RunStats) {
     let f = match alg {
         Algs::ListRank => {bw::list_rank::bw_decode},
     };
 
     let mut r = vec![];
 
-    let mean = time_loop(
+    let stats = time_loop(
         "bw",
-        rounds,
-        Duration::new(1, 0),
+        tc,
         || {},
         || { r = f(&inp); },
         || {}
     );
-    (r, mean)
+    (r, stats)
 }
 
 fn main() {
-    init!();
     let args = Args::parse();
+    init!(&args.pin);
     let arr = chars_from_file(&args.ifname, false).unwrap();
 
     let encoded = bw_encode(&arr);
 
-    let (r, d) = run(args.algorithm, args.rounds, &encoded);
+    let (r, d) = run(args.algorithm, &args.time, &encoded);
 
     finalize!(
         args,
         r,
         d,
-        chars_to_file(&r, args.ofname).unwrap()
+        "bw",
+        f => chars_to_file(&r, &f).unwrap()
     );
 }
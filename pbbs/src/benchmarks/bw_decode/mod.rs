@@ -25,3 +25,4 @@ pub mod list_rank;
 // SOFTWARE.
 // ============================================================================
 
+pub mod bench;
@@ -0,0 +1,103 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use rayon::prelude::*;
+
+use crate::common::io::{read_file_to_vec, write_sequence, InputError};
+use crate::runner::{parses, Benchmark, Param, Params};
+
+
+pub struct ComparisonSort;
+
+fn less(a: i32, b: i32) -> bool { a < b }
+
+impl Benchmark for ComparisonSort {
+    type Input = Vec<i32>;
+    type Output = Vec<i32>;
+
+    fn name(&self) -> &'static str { "sort" }
+    fn about(&self) -> &'static str { "comparison sort" }
+
+    fn algorithms(&self) -> &'static [&'static str] {
+        &["merge", "std", "rayon", "quick", "bucket", "sample"]
+    }
+
+    fn serial(&self) -> Option<&'static str> { Some("std") }
+
+    fn params(&self) -> &'static [Param] {
+        &[Param {
+            name: "stable",
+            default: Some("false"),
+            help: "whether the sort has to be stable",
+            valid: parses::<bool>,
+        }]
+    }
+
+    fn read_input(&self, fname: &str, ps: &Params) -> Result<Vec<i32>, InputError> {
+        read_file_to_vec(fname, Some("sequenceInt"), ps.bad_lines)
+    }
+
+    fn read_output(&self, fname: &str, _inp: &Self::Input, ps: &Params) -> Result<Vec<i32>, InputError> {
+        read_file_to_vec(fname, Some("sequenceInt"), ps.bad_lines)
+    }
+
+    fn output(&self, inp: &Vec<i32>, _ps: &Params) -> Vec<i32> {
+        inp.clone()
+    }
+
+    fn reset(&self, _alg: &str, inp: &Vec<i32>, _ps: &Params, out: &mut Vec<i32>) {
+        out.copy_from_slice(inp);
+    }
+
+    fn run(&self, alg: &str, _inp: &Vec<i32>, ps: &Params, out: &mut Vec<i32>) {
+        let f = match alg {
+            "merge"     => super::merge_sort::comp_sort,
+            "quick"     => super::quick_sort::comp_sort,
+            "bucket"    => super::bucket_sort::comp_sort,
+            "sample"    => super::sample_sort::comp_sort,
+            "std"       => super::std::comp_sort,
+            "rayon"     => super::rayon::comp_sort,
+            _ => unreachable!(),
+        };
+        f(out, less, ps.get("stable"));
+    }
+
+    /// Compares with the input, sorted (sortCheck in PBBS).
+    fn check(
+        &self,
+        inp: &Vec<i32>,
+        out: &Vec<i32>,
+        _ps: &Params
+    ) -> Option<bool> {
+        let mut expected = inp.clone();
+        expected.par_sort_unstable();
+        Some(expected == *out)
+    }
+
+    fn write(&self, out: &Vec<i32>, fname: &str) {
+        write_sequence(out, "sequenceInt", fname).expect("cannot write to output");
+    }
+}
@@ -1,4 +1,4 @@
-pub(crate) fn comp_sort<T, F>(
+pub fn comp_sort<T, F>(
 // ============================================================================
 // This code is part of RPB.
 // ----------------------------------------------------------------------------
@@ -26,12 +26,10 @@
 // ============================================================================
 
 
-#[path ="../macros.rs"] mod macros;
-#[path ="../../common/io.rs"] mod io;
-#[path ="mod.rs"] mod comparison_sort;
-
-use std::time::Duration;
-use io::{read_file_to_vec, write_slice_to_file_seq};
+use pbbs::{count_alloc, define_algs, define_args, init, finalize};
+use pbbs::benchmarks::comparison_sort;
+use pbbs::common::io::{read_file_to_vec, write_sequence, BadLines};
+use pbbs::common::time_loop::{RunStats, TimeConfig};
 
 
 define_args!(
@@ -39,6 +37,8 @@ define_args!(
     (stable, bool, false)
 );
 
+count_alloc!();
+
 define_algs!(
     (STD,       "std"),
     (RAYON,     "rayon"),
@@ -51,11 +51,11 @@ define_algs!(
 
 pub fn run<T, F>(
     alg: Algs,
-    rounds: usize,
+    tc: &TimeConfig,
     stable: bool,
     less: F,
     inp: &[T]
-) -> (Vec<T>, Duration) where
+) -> (Vec<T>, RunStats) where
     T: Copy + Send + Sync + Default,
     F: Fn(T, T) -> bool + Copy + Send + Sync,
 {
@@ -71,33 +71,33 @@ pub fn run<T, F>(
     let mut r = parlay::maybe_uninit_vec![T::default(); inp.len()];
     let r_clone = unsafe { (&mut r[..] as *mut [T]).as_mut().unwrap() };
 
-    let mean = time_loop(
+    let stats = time_loop(
         "sort",
-        rounds,
-        Duration::new(1, 0),
+        tc,
         || { r_clone.copy_from_slice(inp); },
         || { f(&mut r, less, stable) },
         || {}
     );
 
-    (r, mean)
+    (r, stats)
 }
 
 fn main() {
-    init!();
-
     let args = Args::parse();
+    init!(&args.pin);
+
 
     let arr: Vec<i32> = read_file_to_vec(
         &args.ifname,
-        Some { 0: |w: &[&str]| {debug_assert_eq!(w[0], "sequenceInt")} }
-    );
+        Some("sequenceInt"),
+        BadLines::Skip
+    ).unwrap_or_else(|e| panic!("{e}"));
 
     let less = |a: i32, b: i32| a < b;
 
     let (r, d) = run(
         args.algorithm,
-        args.rounds,
+        &args.time,
         args.stable,
         less,
         &arr
@@ -107,6 +107,8 @@ fn main() {
         args,
         r,
         d,
-        write_slice_to_file_seq(&r, args.ofname)
+        "sort",
+        [format!("stable={}", args.stable)],
+        f => write_sequence(&r, "sequenceInt", &f).expect("cannot write to output")
     );
 }
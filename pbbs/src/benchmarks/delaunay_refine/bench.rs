@@ -0,0 +1,101 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::collections::HashMap;
+
+use crate::common::io::InputError;
+use crate::common::geometry::{min_angle_check, Point2d, Tri, Triangles};
+use crate::common::geometry_io::{read_triangles_from_file, write_triangles_to_file};
+use crate::runner::{Benchmark, Params};
+use super::incremental;
+
+type P = Point2d<f64>;
+
+/// The smallest angle [`DelaunayRefine::check`] accepts, a margin under the
+/// 30 degrees the refinement aims for.
+const CHECK_ANGLE: f64 = 26.0;
+
+
+pub struct DelaunayRefine;
+
+impl Benchmark for DelaunayRefine {
+    type Input = Triangles<P>;
+    type Output = Triangles<P>;
+
+    fn name(&self) -> &'static str { "dr" }
+    fn about(&self) -> &'static str { "delaunay refinement" }
+    fn algorithms(&self) -> &'static [&'static str] { &["incremental"] }
+
+    fn read_input(&self, fname: &str, ps: &Params) -> Result<Triangles<P>, InputError> {
+        read_triangles_from_file(fname, 0, ps.bad_lines)
+    }
+
+    fn read_output(&self, fname: &str, _inp: &Triangles<P>, ps: &Params) -> Result<Triangles<P>, InputError> {
+        read_triangles_from_file(fname, 0, ps.bad_lines)
+    }
+
+    fn output(&self, _inp: &Triangles<P>, _ps: &Params) -> Triangles<P> {
+        Triangles { p: vec![], t: vec![] }
+    }
+
+    fn run(
+        &self,
+        alg: &str,
+        tris: &Triangles<P>,
+        _ps: &Params,
+        out: &mut Triangles<P>
+    ) {
+        let f = match alg {
+            "incremental" => incremental::refine,
+            _ => unreachable!(),
+        };
+        f(tris, out);
+    }
+
+    /// Every corner must be a point and every edge must be shared by at most
+    /// two triangles; triangles off the boundary may not be skinny.
+    fn check(&self, _inp: &Triangles<P>, out: &Triangles<P>, _ps: &Params) -> Option<bool> {
+        let n = out.p.len();
+        if out.t.iter().any(|t| t.iter().any(|&c| c < 0 || c as usize >= n)) {
+            return Some(false);
+        }
+        let edge = |t: &Tri, i: usize| (t[i].min(t[(i + 1) % 3]), t[i].max(t[(i + 1) % 3]));
+        let mut edges: HashMap<(i32, i32), u32> = HashMap::new();
+        for t in &out.t {
+            (0..3).for_each(|i| *edges.entry(edge(t, i)).or_default() += 1);
+        }
+        if edges.values().any(|&c| c > 2) { return Some(false); }
+        let corner = |t: &Tri, i: usize| out.p[t[i] as usize];
+        Some(out.t.iter().all(|t| {
+            (0..3).any(|i| edges[&edge(t, i)] == 1)
+                || !min_angle_check(corner(t, 0), corner(t, 1), corner(t, 2), CHECK_ANGLE)
+        }))
+    }
+
+    fn write(&self, out: &Triangles<P>, fname: &str) {
+        write_triangles_to_file(out, fname);
+    }
+}
@@ -26,51 +26,49 @@
 // ============================================================================
 
 
-use std::time::Duration;
-
-#[path ="mod.rs"] mod dr;
-#[path ="../../misc.rs"] mod misc;
-#[path ="../macros.rs"] mod macros;
-#[path ="../../common/mod.rs"] mod common;
-
-use misc::*;
-use dr::incremental;
-use common::geometry::{Triangles, Point2d};
-use common::geometry_io::{read_triangles_from_file, write_triangles_to_file};
+use pbbs::{count_alloc, define_algs, define_args, init, report, write_checked};
+use pbbs::benchmarks::delaunay_refine::incremental;
+use pbbs::common::geometry::{Triangles, Point2d};
+use pbbs::common::geometry_io::{read_triangles_from_file, write_triangles_to_file};
+use pbbs::common::io::BadLines;
+use pbbs::common::time_loop::{RunStats, TimeConfig};
 
 type P = Point2d<f64>;
 
 define_args!(Algs::INCREMENTAL);
 
+count_alloc!();
+
 define_algs!((INCREMENTAL, "incremental"));
 
 pub fn run(
     alg: Algs,
-    rounds: usize,
+    tc: &TimeConfig,
     tris: &Triangles<P>
-) -> (Triangles<P>, Duration) {
+) -> (Triangles<P>, RunStats) {
     let f = match alg {
         Algs::INCREMENTAL => incremental::refine,
     };
 
     let mut r = Triangles { p: vec![], t: vec![] };
-    let mean = time_loop(
+    let stats = time_loop(
         "dr",
-        rounds,
-        Duration::new(1, 0),
+        tc,
         || {},
         || { f(tris, &mut r); },
         || {}
     );
-    (r, mean)
+    (r, stats)
 }
 
 fn main() {
-    init!();
     let args = Args::parse();
-    let tris = read_triangles_from_file(&args.ifname, 0);
-    let (r, d) = run(args.algorithm, args.rounds, &tris);
+    init!(&args.pin);
+    let tris = read_triangles_from_file(&args.ifname, 0, BadLines::Skip)
+        .unwrap_or_else(|e| panic!("{e}"));
+    let (r, d) = run(args.algorithm, &args.time, &tris);
 
-    if !args.ofname.is_empty() { write_triangles_to_file(&r, args.ofname); }
-    println!("{:?}", d);
+    let checked = write_checked!(args, "dr", [], f => write_triangles_to_file(&r, &f));
+    println!("{:?}", d.mean);
+    report!(args, d, checked);
 }
@@ -348,9 +348,14 @@ fn add_refining_vertices<'a>(
 
 static QSIZE: usize = 20000;
 
-fn refine_internal(tris: &Triangles<P>, dest: &mut Triangles<P>) {
+/// Refines `tris` into `dest` with room for `expand_factor` new vertices per
+/// input point. Returns false, leaving `dest` alone, if that is not enough.
+fn refine_internal(
+    tris: &Triangles<P>,
+    dest: &mut Triangles<P>,
+    expand_factor: usize,
+) -> bool {
     let mut t = Timer::new("dr"); t.start();
-    let expand_factor = 4;
     let n = tris.num_points();
     let m = tris.num_triangles();
     let extra_vertices = expand_factor*n;
@@ -415,7 +420,8 @@ fn refine_internal(tris: &Triangles<P>, dest: &mut Triangles<P>) {
         println!("numBad = {num_bad}  out of {}", bad_tt.len());
         if num_bad == 0 { break; }
         if num_points + num_bad > total_vertices {
-            panic!("ran out of vertices");
+            println!("ran out of vertices");
+            return false;
         }
         let offset = num_points - n;
 
@@ -479,18 +485,24 @@ fn refine_internal(tris: &Triangles<P>, dest: &mut Triangles<P>) {
     pack_index(&flags, &mut is);
 
     let rt: Vec<_> = (0..is.len()).into_par_iter().map(|i| {
-        let t = triangles[is[i]];
+        let t = &triangles[is[i]];
         [t.vtx[0].unwrap().id, t.vtx[1].unwrap().id, t.vtx[2].unwrap().id]
     }).collect();
 
     println!("total triangles = {}", is.len());
     t.next("finish");
     *dest = Triangles::new(rp, rt);
+    true
 }
 
 pub fn refine(tris: &Triangles<P>, dest: &mut Triangles<P>) {
     #[cfg(feature = "AW_safe")]
     eprintln!("Incremental delaunay refinement cannot satisfy AW_safe");
 
-    refine_internal(tris, dest);
+    // the mesh is allocated up front, so start over with twice the room
+    // whenever the refinement needs more vertices than it has
+    let mut expand_factor = 4;
+    while !refine_internal(tris, dest, expand_factor) {
+        expand_factor *= 2;
+    }
 }
@@ -1,4 +1,4 @@
-pub(crate) mod incremental;
+pub mod incremental;
 // ============================================================================
 // This code is part of RPB.
 // ----------------------------------------------------------------------------
@@ -25,3 +25,4 @@ pub(crate) mod incremental;
 // SOFTWARE.
 // ============================================================================
 
+pub mod bench;
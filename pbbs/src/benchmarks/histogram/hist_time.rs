@@ -1,4 +1,3 @@
-use std::time::Duration;
 // ============================================================================
 // This code is part of RPB.
 // ----------------------------------------------------------------------------
@@ -27,14 +26,13 @@ use std::time::Duration;
 
 use clap::Parser;
 
-#[path ="mod.rs"] mod hist;
-#[path ="../../misc.rs"] mod misc;
-#[path ="../macros.rs"] mod macros;
-#[path ="../../common/io.rs"] mod io;
 
-use misc::*;
-use hist::{sequential, parallel};
-use io::{read_big_file_to_vec, write_slice_to_file_seq};
+use pbbs::{count_alloc, define_algs, init, finalize};
+use pbbs::benchmarks::histogram::{sequential, parallel};
+use pbbs::common::io::{read_big_file_to_vec, write_sequence, BadLines};
+use pbbs::common::time_loop::{RunStats, TimeConfig};
+use pbbs::common::report::ReportFormat;
+use pbbs::common::pool::Pinning;
 
 
 #[derive(Parser, Debug)]
@@ -52,20 +50,34 @@ struct Args {
     #[clap(value_parser, required=true)]
     ifname: String,
 
-    /// the number of rounds to execute the benchmark
-    #[clap(short, long, value_parser, required=false, default_value_t=1)]
-    rounds: usize,
+    #[clap(flatten)]
+    time: TimeConfig,
+
+    /// print a machine-readable record of the run
+    #[clap(long, value_enum, required=false)]
+    report: Option<ReportFormat>,
+
+    /// how to pin worker threads: none, compact, scatter,
+    /// physical-cores-first, or a cpu list such as 0-3,8
+    #[clap(long, default_value_t)]
+    pin: Pinning,
+
+    /// check the output the way `pbbs check` does
+    #[clap(short, long)]
+    check: bool,
 
     #[clap(short, long, value_parser, required=true)]
     buckets: usize,
 }
 
+count_alloc!();
+
 define_algs!(
     (PARALLEL, "parallel"),
     (SEQUENTIAL, "sequential")
 );
 
-pub fn run(alg: Algs, rounds: usize, buckets: usize, arr: &[u32]) -> (Vec<u32>, Duration) {
+pub fn run(alg: Algs, tc: &TimeConfig, buckets: usize, arr: &[u32]) -> (Vec<u32>, RunStats) {
     let f = match alg {
         Algs::PARALLEL => {parallel::hist},
         Algs::SEQUENTIAL => {sequential::hist}
@@ -74,32 +86,34 @@ pub fn run(alg: Algs, rounds: usize, buckets: usize, arr: &[u32]) -> (Vec<u32>,
     let mut r = vec![];
     let r_ptr = &r as *const Vec<u32> as usize;
 
-    let mean = time_loop(
+    let stats = time_loop(
         "hist",
-        rounds,
-        Duration::new(1, 0),
+        tc,
         || { unsafe { *(r_ptr as *mut Vec<u32>).as_mut().unwrap() = vec![]; } },
         || { f(&arr, buckets, &mut r); },
         || {}
     );
-    (r, mean)
+    (r, stats)
 }
 
 fn main() {
-    init!();
     let args = Args::parse();
+    init!(&args.pin);
     let mut arr = Vec::new();
     read_big_file_to_vec(
         &args.ifname,
-        Some { 0: |w: &[&str]| {debug_assert_eq!(w[0], "sequenceInt")} },
-        &mut arr
-    );
-    let (r, d) = run(args.algorithm, args.rounds, args.buckets, &arr);
+        Some("sequenceInt"),
+        &mut arr,
+        BadLines::Skip
+    ).unwrap_or_else(|e| panic!("{e}"));
+    let (r, d) = run(args.algorithm, &args.time, args.buckets, &arr);
 
     finalize!(
         args,
         r,
         d,
-        write_slice_to_file_seq(&r, args.ofname)
+        "hist",
+        [format!("buckets={}", args.buckets)],
+        f => write_sequence(&r, "sequenceInt", &f).expect("cannot write to output")
     );
 }
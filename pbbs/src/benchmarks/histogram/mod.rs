@@ -1,4 +1,4 @@
-pub(crate) mod parallel;
+pub mod parallel;
 // ============================================================================
 // This code is part of RPB.
 // ----------------------------------------------------------------------------
@@ -25,5 +25,5 @@ pub(crate) mod parallel;
 // SOFTWARE.
 // ============================================================================
 
-pub(crate) mod sequential;
- 
\ No newline at end of file
+pub mod sequential;
+ pub mod bench;
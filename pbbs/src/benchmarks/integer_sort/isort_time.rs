@@ -26,28 +26,28 @@
 // ============================================================================
 
 
-#[path ="mod.rs"] mod isort;
-#[path ="../macros.rs"] mod macros;
-#[path ="../../common/io.rs"] mod io;
-
-use std::time::Duration;
-use io::{read_big_file_to_vec, write_slice_to_file_seq};
+use pbbs::{count_alloc, define_algs, define_args, init, finalize};
+use pbbs::benchmarks::integer_sort as isort;
+use pbbs::common::io::{read_big_file_to_vec, write_sequence, BadLines};
+use pbbs::common::time_loop::{RunStats, TimeConfig};
 
 define_args!(
     Algs::PARRADIX,
     (bits, usize, 0)
 );
 
+count_alloc!();
+
 define_algs!(
     (PARRADIX, "parradix")
 );
 
 pub fn run(
     alg: Algs,
-    rounds: usize,
+    tc: &TimeConfig,
     g: &[u32],
     bits: usize
-) -> (Vec<u32>, Duration)
+) -> (Vec<u32>, RunStats)
 {
     let f = match alg {
         Algs::PARRADIX => isort::parallel_radix_sort::int_sort,
@@ -56,34 +56,36 @@ pub fn run(
     let mut r = parlay::maybe_uninit_vec![];
     let r_ptr = &r as *const Vec<u32> as usize;
 
-    let mean = time_loop(
+    let stats = time_loop(
         "isort",
-        rounds,
-        Duration::new(1, 0),
+        tc,
         || { unsafe { *(r_ptr as *mut Vec<u32>).as_mut().unwrap() = vec![];}},
         || { f(&g, bits, &mut r); },
         || {}
     );
 
-    (r, mean)
+    (r, stats)
 }
 
 fn main() {
-    init!();
-
     let args = Args::parse();
+    init!(&args.pin);
+
     let mut arr = Vec::new();
     read_big_file_to_vec(
         &args.ifname,
-        Some { 0: |w: &[&str]| {debug_assert_eq!(w[0], "sequenceInt")} },
-        &mut arr
-    );
-    let (r, d) = run(args.algorithm, args.rounds, &arr, args.bits);
+        Some("sequenceInt"),
+        &mut arr,
+        BadLines::Skip
+    ).unwrap_or_else(|e| panic!("{e}"));
+    let (r, d) = run(args.algorithm, &args.time, &arr, args.bits);
 
     finalize!(
         args,
         r,
         d,
-        write_slice_to_file_seq(&r, args.ofname)
+        "isort",
+        [format!("bits={}", args.bits)],
+        f => write_sequence(&r, "sequenceInt", &f).expect("cannot write to output")
     );
 }
@@ -1,4 +1,4 @@
-pub(crate) mod parallel_radix_sort;
+pub mod parallel_radix_sort;
 // ============================================================================
 // This code is part of RPB.
 // ----------------------------------------------------------------------------
@@ -25,3 +25,4 @@ pub(crate) mod parallel_radix_sort;
 // SOFTWARE.
 // ============================================================================
 
+pub mod bench;
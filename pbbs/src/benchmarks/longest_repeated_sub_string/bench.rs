@@ -0,0 +1,92 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::DefChar;
+use crate::common::io::{chars_from_file, read_file_to_vec, write_sequence, InputError};
+use crate::runner::{Benchmark, Params};
+use super::doubling;
+
+
+pub struct LongestRepeatedSubString;
+
+impl Benchmark for LongestRepeatedSubString {
+    type Input = Vec<DefChar>;
+    /// `(len, loc1, loc2)`
+    type Output = (usize, usize, usize);
+
+    fn name(&self) -> &'static str { "lrs" }
+    fn about(&self) -> &'static str { "longest repeated substring" }
+    fn algorithms(&self) -> &'static [&'static str] { &["doubling"] }
+
+    fn read_input(&self, fname: &str, _ps: &Params) -> Result<Vec<DefChar>, InputError> {
+        chars_from_file(fname, false).map_err(|e| InputError::io(fname, e))
+    }
+
+    fn read_output(&self, fname: &str, _inp: &Vec<DefChar>, ps: &Params) -> Result<Self::Output, InputError> {
+        match read_file_to_vec::<usize, _>(fname, Some("sequenceInt"), ps.bad_lines)?[..] {
+            [len, loc1, loc2] => Ok((len, loc1, loc2)),
+            _ => Err(InputError::new(fname, None, "expected the length and the two locations")),
+        }
+    }
+
+    fn output(&self, _inp: &Vec<DefChar>, _ps: &Params) -> Self::Output {
+        (0, 0, 0)
+    }
+
+    fn run(
+        &self,
+        alg: &str,
+        inp: &Vec<DefChar>,
+        _ps: &Params,
+        out: &mut Self::Output
+    ) {
+        let f = match alg {
+            "doubling" => doubling::lrs,
+            _ => unreachable!(),
+        };
+        *out = f(inp);
+    }
+
+    fn check(
+        &self,
+        inp: &Vec<DefChar>,
+        out: &Self::Output,
+        _ps: &Params
+    ) -> Option<bool> {
+        let (len, loc1, loc2) = *out;
+        if len == 0 { return Some(true); }
+        Some(
+            loc1 != loc2
+            && loc1.max(loc2) + len <= inp.len()
+            && inp[loc1..loc1 + len] == inp[loc2..loc2 + len]
+        )
+    }
+
+    fn write(&self, out: &Self::Output, fname: &str) {
+        let (len, loc1, loc2) = *out;
+        write_sequence(&[len, loc1, loc2], "sequenceInt", fname).expect("cannot write to output");
+    }
+}
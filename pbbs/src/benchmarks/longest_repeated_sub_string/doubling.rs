@@ -29,9 +29,9 @@ use rayon::prelude::*;
 use crate::DefChar;
 
 #[cfg(not(any(feature = "AW_safe", feature = "sng_ind_atomic")))]
-use crate::{DefInt, lcp::lcp, suffix_array::suffix_array};
+use crate::{DefInt, algorithm::lcp::lcp, algorithm::suffix_array::suffix_array};
 #[cfg(any(feature = "AW_safe", feature = "sng_ind_atomic"))]
-use crate::{ORDER, DefAtomInt, lcp::atomic_lcp, suffix_array::atomic_suffix_array};
+use crate::{ORDER, DefAtomInt, algorithm::lcp::atomic_lcp, algorithm::suffix_array::atomic_suffix_array};
 
 type Result = (usize, usize, usize);
 
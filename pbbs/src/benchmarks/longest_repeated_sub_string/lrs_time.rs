@@ -25,29 +25,24 @@
 // SOFTWARE.
 // ============================================================================
 
-use std::time::Duration;
 
-#[path ="mod.rs"] mod lrs;
-#[path ="../../misc.rs"] mod misc;
-#[path ="../macros.rs"] mod macros;
-#[path ="../../common/io.rs"] mod io;
-#[path ="../../algorithm/lcp.rs"] mod lcp;
-#[path ="../../algorithm/range_min.rs"] mod range_min;
-#[path ="../../algorithm/suffix_array.rs"] mod suffix_array;
-
-use misc::*;
-use lrs::doubling;
-use io::{chars_from_file, chars_to_file};
+use pbbs::{count_alloc, define_algs, define_args, init, report, write_checked};
+use pbbs::DefChar;
+use pbbs::benchmarks::longest_repeated_sub_string::doubling;
+use pbbs::common::io::{chars_from_file, write_sequence};
+use pbbs::common::time_loop::{RunStats, TimeConfig};
 
 define_args!(Algs::Doubling);
+count_alloc!();
+
 define_algs!((Doubling, "doubling"));
 
 
 pub fn run(
     alg: Algs,
-    rounds: usize,
+    tc: &TimeConfig,
     inp: &[DefChar]
-) -> ((usize, usize, usize), Duration)
+) -> ((usize, usize, usize), RunStats)
 {
     let f = match alg {
         Algs::Doubling => {doubling::lrs},
@@ -55,27 +50,27 @@ pub fn run(
 
     let mut r = (0, 0, 0);
 
-    let mean = time_loop(
+    let stats = time_loop(
         "lrs",
-        rounds,
-        Duration::new(1, 0),
+        tc,
         || {},
         || { r = f(&inp); },
         || {}
     );
-    (r, mean)
+    (r, stats)
 }
 
 fn main() {
-    init!();
     let args = Args::parse();
+    init!(&args.pin);
     let arr = chars_from_file(&args.ifname, false).unwrap();
-    let ((len, loc1, loc2), d) = run(args.algorithm, args.rounds, &arr);
+    let ((len, loc1, loc2), d) = run(args.algorithm, &args.time, &arr);
 
-    let out = format!("len:{len}\tloc1:{loc1}\tloc2:{loc2}");
-    if !args.ofname.is_empty() {
-        chars_to_file(out.as_bytes(), args.ofname).unwrap();
-    } else { println!("{}", out); }
+    let checked = write_checked!(args, "lrs", [], f =>
+        write_sequence(&[len, loc1, loc2], "sequenceInt", &f).expect("cannot write to output")
+    );
+    if args.ofname.is_empty() { println!("len:{len}\tloc1:{loc1}\tloc2:{loc2}"); }
 
-    println!("{:?}", d);
+    println!("{:?}", d.mean);
+    report!(args, d, checked);
 }
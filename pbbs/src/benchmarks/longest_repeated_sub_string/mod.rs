@@ -1,4 +1,4 @@
-pub(crate) mod doubling;
+pub mod doubling;
 // ============================================================================
 // This code is part of RPB.
 // ----------------------------------------------------------------------------
@@ -25,3 +25,4 @@ pub(crate) mod doubling;
 // SOFTWARE.
 // ============================================================================
 
+pub mod bench;
@@ -29,8 +29,7 @@ macro_rules! define_algs {
     ($(($alg: ident, $name: expr)),*) => {
         use std::fmt;
         use clap::ValueEnum;
-        #[path ="../../common/time_loop.rs"] mod time_loop;
-        use time_loop::time_loop;
+        use $crate::common::time_loop::time_loop;
 
         #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
         pub enum Algs { $($alg,)* }
@@ -65,9 +64,21 @@ macro_rules! define_args {
             #[clap(value_parser, required=true)]
             ifname: String,
 
-            /// the number of rounds to execute the benchmark
-            #[clap(short, long, value_parser, required=false, default_value_t=1)]
-            rounds: usize,
+            #[clap(flatten)]
+            time: $crate::common::time_loop::TimeConfig,
+
+            /// print a machine-readable record of the run
+            #[clap(long, value_enum, required=false)]
+            report: Option<$crate::common::report::ReportFormat>,
+
+            /// how to pin worker threads: none, compact, scatter,
+            /// physical-cores-first, or a cpu list such as 0-3,8
+            #[clap(long, default_value_t)]
+            pin: $crate::common::pool::Pinning,
+
+            /// check the output the way `pbbs check` does
+            #[clap(short, long)]
+            check: bool,
 
             $(#[clap(long, value_parser, required=false, default_value_t=$default)]
             $name: $type,)*
@@ -78,32 +89,85 @@ macro_rules! define_args {
 #[macro_export]
 macro_rules! init {
     () => {
-        use rayon::prelude::*;
-        use affinity::set_thread_affinity;
-
-        // pin rayon's threads to cores
-        // TODO: find a better way to do this.
-        (0..rayon::current_num_threads())
-        .par_bridge()
-        .for_each(|_| {
-            set_thread_affinity(
-                [rayon::current_thread_index().unwrap()]
-            ).unwrap();
-            std::thread::sleep(std::time::Duration::from_millis(100))
-        })
+        $crate::init!(&$crate::common::pool::Pinning::default())
+    };
+    ($pin: expr) => {
+        // pin rayon's threads as they start
+        if let Err(e) = $crate::common::pool::pin_global($pin) {
+            eprintln!("error: {e}");
+            std::process::exit(2);
+        }
+    };
+}
+
+/// With the `count_alloc` feature, makes parlay's counting allocator the
+/// global one, so that the reports include what each round allocated.
+#[macro_export]
+macro_rules! count_alloc {
+    () => {
+        #[cfg(feature = "count_alloc")]
+        #[global_allocator]
+        static GLOBAL: parlay::memory::CountingAlloc = parlay::memory::CountingAlloc;
+    };
+}
+
+#[macro_export]
+macro_rules! report {
+    ($args: ident, $d: ident, $check: expr) => {
+        if let Some(fmt) = $args.report {
+            $crate::common::report::Record::new(
+                env!("CARGO_BIN_NAME"),
+                &$args.algorithm.to_string(),
+                &$args.ifname,
+                &$d,
+                $check
+            ).emit(fmt);
+        }
     }
 }
 
+/// Writes the output with `$write` to `$f`, which is the output file or, when
+/// only `--check` needs it, a temporary one, and checks it against the input
+/// with the `pbbs` benchmark `$bench` and its `name=value` params. Evaluates
+/// to the check result, `None` without `--check`.
+#[macro_export]
+macro_rules! write_checked {
+    ($args: ident, $bench: expr, [$($p: expr),*], $f: ident => $write: expr) => {{
+        let keep = !$args.ofname.is_empty();
+        let $f = if keep { std::path::PathBuf::from(&$args.ofname) } else {
+            std::env::temp_dir()
+                .join(format!("{}_{}", env!("CARGO_BIN_NAME"), std::process::id()))
+        };
+        if keep || $args.check { $write; }
+        let checked = $args.check.then(|| {
+            let b = $crate::runner::find($bench).unwrap();
+            let ps = $crate::runner::Params::parse(b.params(), &[$($p.to_string()),*])
+                .unwrap_or_else(|e| panic!("{e}"));
+            b.check_file(&$args.ifname, $f.to_str().unwrap(), &ps)
+                .unwrap_or_else(|e| panic!("{e}"))
+        }).flatten();
+        if !keep && $args.check { let _ = std::fs::remove_file(&$f); }
+        if let Some(ok) = checked {
+            println!("check: {}", if ok { "OK" } else { "ERR" });
+        }
+        checked
+    }};
+}
+
 #[macro_export]
 macro_rules! finalize {
-    ($args: ident, $r: ident, $d: ident, $write: expr) => {
-        if !$args.ofname.is_empty() {
-            $write
-        } else {
+    ($args: ident, $r: ident, $d: ident, $bench: expr, $f: ident => $write: expr) => {
+        $crate::finalize!($args, $r, $d, $bench, [], $f => $write)
+    };
+    ($args: ident, $r: ident, $d: ident, $bench: expr, [$($p: expr),*], $f: ident => $write: expr) => {
+        let checked = $crate::write_checked!($args, $bench, [$($p),*], $f => $write);
+        if $args.ofname.is_empty() {
             if $r.len() < 20 { println!("result:  {:?}", $r); }
             else { println!("result:  {:?} ... [Ommited]", &$r[..20]); }
         }
 
-        println!("mean:  {:?}", $d);
+        println!("mean:  {:?}", $d.mean);
+        println!("{}", $d);
+        $crate::report!($args, $d, checked);
     }
 }
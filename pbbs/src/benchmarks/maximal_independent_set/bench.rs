@@ -0,0 +1,80 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::common::graph::Graph;
+use crate::common::graph_io::read_graph_from_file;
+use crate::common::io::{read_file_to_vec, write_sequence, InputError};
+use crate::runner::{Benchmark, Params};
+use super::{serial_mis, rusty_incremental_mis};
+
+
+pub struct MaximalIndependentSet;
+
+impl Benchmark for MaximalIndependentSet {
+    type Input = Graph;
+    type Output = Vec<u8>;
+
+    fn name(&self) -> &'static str { "mis" }
+    fn about(&self) -> &'static str { "maximal independent set" }
+
+    fn algorithms(&self) -> &'static [&'static str] {
+        &["rustinc", "serial"]
+    }
+
+    fn read_input(&self, fname: &str, ps: &Params) -> Result<Graph, InputError> {
+        read_graph_from_file(fname, ps.bad_lines)
+    }
+
+    fn read_output(&self, fname: &str, _inp: &Self::Input, ps: &Params) -> Result<Vec<u8>, InputError> {
+        read_file_to_vec(fname, Some("sequenceInt"), ps.bad_lines)
+    }
+
+    fn output(&self, _g: &Graph, _ps: &Params) -> Vec<u8> { vec![] }
+
+    fn run(&self, alg: &str, g: &Graph, _ps: &Params, out: &mut Vec<u8>) {
+        let f = match alg {
+            "serial" => serial_mis::maximal_independent_set,
+            "rustinc" => rusty_incremental_mis::maximal_independent_set,
+            _ => unreachable!(),
+        };
+        *out = f(g);
+    }
+
+    /// Flags are 1 for chosen vertices: no two chosen vertices may be
+    /// neighbors and every other vertex needs a chosen neighbor.
+    fn check(&self, g: &Graph, out: &Vec<u8>, _ps: &Params) -> Option<bool> {
+        if out.len() != g.n { return Some(false); }
+        Some((0..g.n).all(|v| {
+            let mut nghs = g.index(v).neighbors.iter().map(|&u| out[u as usize]);
+            if out[v] == 1 { nghs.all(|f| f != 1) }
+            else { nghs.any(|f| f == 1) }
+        }))
+    }
+
+    fn write(&self, out: &Vec<u8>, fname: &str) {
+        write_sequence(out, "sequenceInt", fname).expect("cannot write to output");
+    }
+}
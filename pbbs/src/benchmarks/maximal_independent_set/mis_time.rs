@@ -26,29 +26,23 @@
 // ============================================================================
 
 
-use std::time::Duration;
-
-#[path ="mod.rs"] mod mis;
-#[path ="../../misc.rs"] mod misc;
-#[path ="../macros.rs"] mod macros;
-#[path ="../../common/io.rs"] mod io;
-#[path ="../../common/graph.rs"] mod graph;
-#[path ="../../common/graph_io.rs"] mod graph_io;
-
-use misc::*;
-use mis::{ serial_mis, rusty_incremental_mis};
-use graph::Graph;
-use io::write_slice_to_file_seq;
-use graph_io::read_graph_from_file;
+use pbbs::{count_alloc, define_algs, define_args, init, finalize};
+use pbbs::benchmarks::maximal_independent_set::{serial_mis, rusty_incremental_mis};
+use pbbs::common::graph::Graph;
+use pbbs::common::io::{write_sequence, BadLines};
+use pbbs::common::graph_io::read_graph_from_file;
+use pbbs::common::time_loop::{RunStats, TimeConfig};
 
 define_args!(Algs::RUSTINC);
 
+count_alloc!();
+
 define_algs!(
     (SERIAL, "serial"),
     (RUSTINC, "rustinc")
 );
 
-pub fn run(alg: Algs, rounds: usize, g: Graph) -> (Vec<u8>, Duration) {
+pub fn run(alg: Algs, tc: &TimeConfig, g: Graph) -> (Vec<u8>, RunStats) {
     let mis = match alg {
         Algs::SERIAL => serial_mis::maximal_independent_set,
         Algs::RUSTINC => rusty_incremental_mis::maximal_independent_set,
@@ -56,28 +50,29 @@ pub fn run(alg: Algs, rounds: usize, g: Graph) -> (Vec<u8>, Duration) {
 
     let mut r = vec![];
 
-    let mean = time_loop(
+    let stats = time_loop(
         "mis",
-        rounds,
-        Duration::new(1, 0),
+        tc,
         || {},
         || { r = mis(&g); },
         || {}
     );
-    (r, mean)
+    (r, stats)
 }
 
 fn main() {
-    init!();
-
     let args = Args::parse();
-    let g = read_graph_from_file(&args.ifname);
-    let (r, d) = run(args.algorithm, args.rounds, g);
+    init!(&args.pin);
+
+    let g = read_graph_from_file(&args.ifname, BadLines::Skip)
+        .unwrap_or_else(|e| panic!("{e}"));
+    let (r, d) = run(args.algorithm, &args.time, g);
 
     finalize!(
         args,
         r,
         d,
-        write_slice_to_file_seq(&r, args.ofname)
+        "mis",
+        f => write_sequence(&r, "sequenceInt", &f).expect("cannot write to output")
     );
 }
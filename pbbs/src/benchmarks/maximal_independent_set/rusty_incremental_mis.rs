@@ -28,9 +28,8 @@
 use std::sync::atomic::{AtomicU8, Ordering::Relaxed};
 use rayon::prelude::*;
 
-use crate::graph::Graph;
-#[path="../../common/spec_for.rs"] mod spec_for;
-use spec_for::StatefulSpecFor;
+use crate::common::graph::Graph;
+use crate::common::spec_for::StatefulSpecFor;
 
 
 #[derive(Clone)]
@@ -1,4 +1,4 @@
-use crate::graph::Graph;
+use crate::common::graph::Graph;
 // ============================================================================
 // This code is part of RPB.
 // ----------------------------------------------------------------------------
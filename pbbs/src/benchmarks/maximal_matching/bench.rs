@@ -0,0 +1,96 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::DefInt;
+use crate::common::graph::EdgeArray;
+use crate::common::graph_io::read_edge_array_from_file;
+use crate::common::io::{read_file_to_vec, write_sequence, InputError};
+use crate::runner::{Benchmark, Params};
+use super::{serial_mm, rusty_incremental_mm};
+
+
+pub struct MaximalMatching;
+
+impl Benchmark for MaximalMatching {
+    type Input = EdgeArray;
+    type Output = Vec<DefInt>;
+
+    fn name(&self) -> &'static str { "mm" }
+    fn about(&self) -> &'static str { "maximal matching" }
+
+    fn algorithms(&self) -> &'static [&'static str] {
+        &["rustinc", "serial"]
+    }
+
+    fn read_input(&self, fname: &str, ps: &Params) -> Result<EdgeArray, InputError> {
+        read_edge_array_from_file(fname, ps.bad_lines)
+    }
+
+    fn read_output(&self, fname: &str, _inp: &Self::Input, ps: &Params) -> Result<Vec<DefInt>, InputError> {
+        read_file_to_vec(fname, Some("sequenceInt"), ps.bad_lines)
+    }
+
+    fn output(&self, _ea: &EdgeArray, _ps: &Params) -> Vec<DefInt> { vec![] }
+
+    fn run(&self, alg: &str, ea: &EdgeArray, _ps: &Params, out: &mut Vec<DefInt>) {
+        let f = match alg {
+            "serial" => serial_mm::maximal_matching,
+            "rustinc" => rusty_incremental_mm::maximal_matching,
+            _ => unreachable!(),
+        };
+        *out = f(ea);
+    }
+
+    /// No two matched edges share a vertex and every unmatched edge (that is
+    /// not a self loop) has a matched endpoint.
+    fn check(
+        &self,
+        ea: &EdgeArray,
+        out: &Vec<DefInt>,
+        _ps: &Params
+    ) -> Option<bool> {
+        let n = ea.num_rows.max(ea.num_cols);
+        if out.iter().any(|&i| i as usize >= ea.non_zeros) { return Some(false); }
+        let mut vs = vec![usize::MAX; n];
+        let mut flags = vec![false; ea.non_zeros];
+        for &i in out {
+            let (i, e) = (i as usize, &ea[i as usize]);
+            if vs[e.u as usize] != usize::MAX || vs[e.v as usize] != usize::MAX {
+                return Some(false);
+            }
+            (vs[e.u as usize], vs[e.v as usize]) = (i, i);
+            flags[i] = true;
+        }
+        Some((0..ea.non_zeros).all(|i| {
+            let (u, v) = (ea[i].u as usize, ea[i].v as usize);
+            flags[i] || u == v || vs[u] != usize::MAX || vs[v] != usize::MAX
+        }))
+    }
+
+    fn write(&self, out: &Vec<DefInt>, fname: &str) {
+        write_sequence(out, "sequenceInt", fname).expect("cannot write to output");
+    }
+}
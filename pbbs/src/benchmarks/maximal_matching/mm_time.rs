@@ -26,29 +26,24 @@
 // ============================================================================
 
 
-use std::time::Duration;
-
-#[path ="mod.rs"] mod mm;
-#[path ="../../misc.rs"] mod misc;
-#[path ="../macros.rs"] mod macros;
-#[path ="../../common/io.rs"] mod io;
-#[path ="../../common/graph.rs"] mod graph;
-#[path ="../../common/graph_io.rs"] mod graph_io;
-
-use misc::*;
-use graph::EdgeArray;
-use io::write_slice_to_file_seq;
-use graph_io::read_edge_array_from_file;
-use mm::{ rusty_incremental_mm, serial_mm };
+use pbbs::{count_alloc, define_algs, define_args, init, finalize};
+use pbbs::DefInt;
+use pbbs::common::graph::EdgeArray;
+use pbbs::common::io::{write_sequence, BadLines};
+use pbbs::common::graph_io::read_edge_array_from_file;
+use pbbs::benchmarks::maximal_matching::{rusty_incremental_mm, serial_mm};
+use pbbs::common::time_loop::{RunStats, TimeConfig};
 
 define_args!(Algs::RUSTINC);
 
+count_alloc!();
+
 define_algs!(
     (SERIAL, "serial"),
     (RUSTINC, "rustinc")
 );
 
-pub fn run(alg: Algs, rounds: usize, ea: EdgeArray) -> (Vec<DefInt>, Duration) {
+pub fn run(alg: Algs, tc: &TimeConfig, ea: EdgeArray) -> (Vec<DefInt>, RunStats) {
     let mm = match alg {
         Algs::SERIAL    =>  serial_mm::maximal_matching,
         Algs::RUSTINC   =>  rusty_incremental_mm::maximal_matching,
@@ -56,28 +51,29 @@ pub fn run(alg: Algs, rounds: usize, ea: EdgeArray) -> (Vec<DefInt>, Duration) {
 
     let mut r = vec![];
 
-    let mean = time_loop(
+    let stats = time_loop(
         "mm",
-        rounds,
-        Duration::new(1, 0),
+        tc,
         || {},
         || { r = mm(&ea); },
         || {}
     );
-    (r, mean)
+    (r, stats)
 }
 
 fn main() {
-    init!();
-
     let args = Args::parse();
-    let g = read_edge_array_from_file(&args.ifname);
-    let (r, d) = run(args.algorithm, args.rounds, g);
+    init!(&args.pin);
+
+    let g = read_edge_array_from_file(&args.ifname, BadLines::Skip)
+        .unwrap_or_else(|e| panic!("{e}"));
+    let (r, d) = run(args.algorithm, &args.time, g);
 
     finalize!(
         args,
         r,
         d,
-        write_slice_to_file_seq(&r, args.ofname)
+        "mm",
+        f => write_sequence(&r, "sequenceInt", &f).expect("cannot write to output")
     );
 }
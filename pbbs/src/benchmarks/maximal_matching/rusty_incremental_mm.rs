@@ -29,13 +29,12 @@ use rayon::prelude::*;
 use std::sync::atomic::AtomicBool;
 
 use crate::DefInt;
-use crate::{graph::EdgeArray};
+use crate::common::graph::EdgeArray;
 #[cfg(feature = "AW_safe")]
 use crate::ORDER;
 
-#[path="../../common/spec_for.rs"] mod spec_for;
 
-use spec_for::{SpecFor, Reservation};
+use crate::common::spec_for::{SpecFor, Reservation};
 
 
 pub fn maximal_matching(ea: &EdgeArray) -> Vec<DefInt> {
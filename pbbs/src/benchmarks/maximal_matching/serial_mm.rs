@@ -25,7 +25,7 @@ use crate::DefInt;
 // SOFTWARE.
 // ============================================================================
 
-use crate::graph::EdgeArray;
+use crate::common::graph::EdgeArray;
 
 
 pub fn maximal_matching(ea: &EdgeArray) -> Vec<DefInt> {
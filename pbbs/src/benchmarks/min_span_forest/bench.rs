@@ -0,0 +1,105 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::DefInt;
+use crate::common::graph::WghEdgeArray;
+use crate::common::graph_io::read_wgh_edge_array_from_file;
+use crate::common::io::{read_file_to_vec, write_sequence, InputError};
+use crate::runner::{Benchmark, Params};
+use super::{serial_msf, incremental_msf, inc_msf_mod};
+
+
+pub struct MinSpanForest;
+
+/// The forest's edge indices, plus the copy of the edge array that `incmod`
+/// modifies in place.
+pub struct Forest {
+    pub edges: Vec<DefInt>,
+    work: WghEdgeArray,
+}
+
+fn weight(ea: &WghEdgeArray, edges: &[DefInt]) -> f64 {
+    edges.iter().map(|&i| ea[i as usize].w as f64).sum()
+}
+
+impl Benchmark for MinSpanForest {
+    type Input = WghEdgeArray;
+    type Output = Forest;
+
+    fn name(&self) -> &'static str { "msf" }
+    fn about(&self) -> &'static str { "minimum spanning forest" }
+
+    fn algorithms(&self) -> &'static [&'static str] {
+        &["incremental", "serial", "incmod"]
+    }
+
+    fn read_input(&self, fname: &str, ps: &Params) -> Result<WghEdgeArray, InputError> {
+        read_wgh_edge_array_from_file(fname, ps.bad_lines)
+    }
+
+    fn read_output(&self, fname: &str, ea: &WghEdgeArray, ps: &Params) -> Result<Forest, InputError> {
+        Ok(Forest { edges: read_file_to_vec(fname, Some("sequenceInt"), ps.bad_lines)?, work: ea.clone() })
+    }
+
+    fn output(&self, ea: &WghEdgeArray, _ps: &Params) -> Forest {
+        Forest { edges: vec![], work: ea.clone() }
+    }
+
+    fn reset(&self, alg: &str, ea: &WghEdgeArray, _ps: &Params, out: &mut Forest) {
+        if alg == "incmod" { out.work = ea.clone(); }
+    }
+
+    fn run(&self, alg: &str, _ea: &WghEdgeArray, _ps: &Params, out: &mut Forest) {
+        let f = match alg {
+            "serial" => serial_msf::minimum_spanning_forest,
+            "incremental" => incremental_msf::minimum_spanning_forest,
+            "incmod" => inc_msf_mod::minimum_spanning_forest,
+            _ => unreachable!(),
+        };
+        f(&out.work, &mut out.edges);
+    }
+
+    /// Compares the forest's size and total weight with the serial one's.
+    fn check(
+        &self,
+        ea: &WghEdgeArray,
+        out: &Forest,
+        _ps: &Params
+    ) -> Option<bool> {
+        if out.edges.iter().any(|&i| i as usize >= ea.m) { return Some(false); }
+        let mut expected = vec![];
+        serial_msf::minimum_spanning_forest(ea, &mut expected);
+        let (w, we) = (weight(ea, &out.edges), weight(ea, &expected));
+        Some(
+            out.edges.len() == expected.len()
+            && (w - we).abs() <= 1e-6 * we.abs().max(1.0)
+        )
+    }
+
+    fn write(&self, out: &Forest, fname: &str) {
+        write_sequence(&out.edges, "sequenceInt", fname).expect("cannot write to output");
+    }
+}
@@ -31,16 +31,15 @@ use std::sync::atomic::{AtomicBool};
 use parlay::primitives::pack_index;
 use parlay::internal::sample_sort_inplace;
 use crate::{DefInt, DefIntS};
-use crate::graph::WghEdgeArray;
+use crate::common::graph::WghEdgeArray;
 #[cfg(not(feature = "AW_safe"))]
-use crate::union_find::UnionFind;
+use crate::algorithm::union_find::UnionFind;
 #[cfg(feature = "AW_safe")]
-use crate::union_find::AtomicUnionFind;
+use crate::algorithm::union_find::AtomicUnionFind;
 #[cfg(feature = "AW_safe")]
 use crate::ORDER;
 
-#[path="../../common/spec_for.rs"] mod spec_for;
-use spec_for::{SpecFor, Reservation};
+use crate::common::spec_for::{SpecFor, Reservation};
 
 
 pub fn minimum_spanning_forest(wea: &WghEdgeArray, dest: &mut Vec<DefInt>) {
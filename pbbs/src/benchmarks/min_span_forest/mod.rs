@@ -27,3 +27,4 @@ pub mod serial_msf;
 
 pub mod inc_msf_mod;
 pub mod incremental_msf;
+pub mod bench;
@@ -26,24 +26,18 @@
 // ============================================================================
 
 
-use std::time::Duration;
-
-#[path ="mod.rs"] mod msf;
-#[path ="../../misc.rs"] mod misc;
-#[path ="../macros.rs"] mod macros;
-#[path ="../../common/io.rs"] mod io;
-#[path ="../../common/graph.rs"] mod graph;
-#[path ="../../common/graph_io.rs"] mod graph_io;
-#[path ="../../algorithm/union_find.rs"] mod union_find;
-
-use misc::*;
-use graph::WghEdgeArray;
-use io::write_slice_to_file_seq;
-use graph_io::read_wgh_edge_array_from_file;
-use msf::{ incremental_msf, serial_msf, inc_msf_mod };
+use pbbs::{count_alloc, define_algs, define_args, init, finalize};
+use pbbs::DefInt;
+use pbbs::common::graph::WghEdgeArray;
+use pbbs::common::io::{write_sequence, BadLines};
+use pbbs::common::graph_io::read_wgh_edge_array_from_file;
+use pbbs::benchmarks::min_span_forest::{incremental_msf, serial_msf, inc_msf_mod};
+use pbbs::common::time_loop::{RunStats, TimeConfig};
 
 define_args!(Algs::INCREMENTAL);
 
+count_alloc!();
+
 define_algs!(
     (SERIAL, "serial"),
     (INCREMENTAL, "incremental"),
@@ -53,9 +47,9 @@ define_algs!(
 
 pub fn run(
     alg: Algs,
-    rounds: usize,
+    tc: &TimeConfig,
     ea: WghEdgeArray
-) -> (Vec<DefInt>, Duration)
+) -> (Vec<DefInt>, RunStats)
 {
     let sf = match alg {
         Algs::SERIAL => { serial_msf::minimum_spanning_forest },
@@ -68,27 +62,28 @@ pub fn run(
     let ea_copy_shadow = unsafe {
         (&ea_copy as *const WghEdgeArray).as_ref().unwrap()
     };
-    let mean = time_loop(
+    let stats = time_loop(
         "msf",
-        rounds,
-        Duration::new(1, 0),
+        tc,
         || { if alg == Algs::INCMOD { ea_copy = ea.clone(); }},
         || { sf(&ea_copy_shadow, &mut r); },
         || {}
     );
-    (r, mean)
+    (r, stats)
 }
 
 fn main() {
-    init!();
     let args = Args::parse();
-    let ea = read_wgh_edge_array_from_file(&args.ifname);
-    let (r, d) = run(args.algorithm, args.rounds, ea);
+    init!(&args.pin);
+    let ea = read_wgh_edge_array_from_file(&args.ifname, BadLines::Skip)
+        .unwrap_or_else(|e| panic!("{e}"));
+    let (r, d) = run(args.algorithm, &args.time, ea);
 
     finalize!(
         args,
         r,
         d,
-        write_slice_to_file_seq(&r, args.ofname)
+        "msf",
+        f => write_sequence(&r, "sequenceInt", &f).expect("cannot write to output")
     );
 }
@@ -27,8 +27,8 @@ use std::cmp::{Ordering, min};
 
 
 use crate::{DefInt, DefIntS, DefFloat};
-use crate::graph::{WghEdge, WghEdgeArray};
-use crate::union_find::UnionFind;
+use crate::common::graph::{WghEdge, WghEdgeArray};
+use crate::algorithm::union_find::UnionFind;
 
 #[derive(Clone, Copy)]
 pub struct IndexedEdge { pub u: DefInt, pub v: DefInt, pub id: DefInt, pub w: DefFloat }
@@ -81,6 +81,8 @@ pub fn minimum_spanning_forest(wea: &WghEdgeArray, dest: &mut Vec<DefInt>) {
 
     let mut uf = UnionFind::new(n);
 
+    // the loops append, so drop the previous round's forest
+    dest.clear();
     union_find_loop(&wea, l, &mut uf, dest);
 
     let mut k = 0;
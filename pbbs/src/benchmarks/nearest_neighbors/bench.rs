@@ -0,0 +1,162 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::common::geometry::{Point2d, Point3d};
+use crate::common::geometry_io::{read_points2d_from_file, read_points3d_from_file};
+use crate::common::io::{read_file_to_vec, write_sequence, InputError};
+use crate::runner::{parses, Benchmark, Param, Params};
+use super::{naive, cktree, kdtree, check::is_knn, point::KnnPoint};
+
+
+pub struct NearestNeighbors;
+
+/// The input points, in the dimension given by the `dimension` parameter.
+pub enum Points {
+    Two(Vec<Point2d<f64>>),
+    Three(Vec<Point3d<f64>>),
+}
+
+impl Points {
+    pub fn len(&self) -> usize {
+        match self {
+            Points::Two(p) => p.len(),
+            Points::Three(p) => p.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+}
+
+fn dimension(v: &str) -> Result<(), String> {
+    match v.parse() {
+        Ok(2 | 3) => Ok(()),
+        _ => Err("only 2D and 3D points are supported".to_string()),
+    }
+}
+
+fn ann<P: KnnPoint>(alg: &str, inp: &[P], k: usize, out: &mut Vec<Vec<usize>>) {
+    match alg {
+        "naive" => naive::ann(inp, k, out),
+        "cktree" => cktree::ann(inp, k, out),
+        "kdtree" => kdtree::ann(inp, k, out),
+        _ => unreachable!(),
+    }
+}
+
+impl Benchmark for NearestNeighbors {
+    type Input = Points;
+    type Output = Vec<Vec<usize>>;
+
+    fn name(&self) -> &'static str { "knn" }
+    fn about(&self) -> &'static str { "k nearest neighbors" }
+
+    fn algorithms(&self) -> &'static [&'static str] {
+        &["naive", "cktree", "kdtree"]
+    }
+
+    fn params(&self) -> &'static [Param] {
+        &[
+            Param {
+                name: "k",
+                default: Some("1"),
+                help: "the number of nearest neighbors to find",
+                valid: parses::<usize>,
+            },
+            Param {
+                name: "dimension",
+                default: Some("2"),
+                help: "the dimension (2 for 2D points, 3 for 3D points)",
+                valid: dimension,
+            },
+        ]
+    }
+
+    fn read_input(&self, fname: &str, ps: &Params) -> Result<Self::Input, InputError> {
+        match ps.get::<usize>("dimension") {
+            2 => read_points2d_from_file(fname, ps.bad_lines).map(Points::Two),
+            3 => read_points3d_from_file(fname, ps.bad_lines).map(Points::Three),
+            d => Err(InputError::new(fname, None, format!("cannot read {d}D points"))),
+        }
+    }
+
+    fn read_output(&self, fname: &str, inp: &Self::Input, ps: &Params) -> Result<Self::Output, InputError> {
+        let k: usize = ps.get("k");
+        let r: Vec<usize> = read_file_to_vec(fname, Some("sequenceInt"), ps.bad_lines)?;
+        if r.len() != k * inp.len() {
+            return Err(InputError::new(fname, None, format!(
+                "expected {k} neighbors for each of the {} points", inp.len()
+            )));
+        }
+        Ok(r.chunks(k.max(1)).map(<[usize]>::to_vec).collect())
+    }
+
+    fn output(&self, inp: &Self::Input, ps: &Params) -> Self::Output {
+        vec![vec![0; ps.get("k")]; inp.len()]
+    }
+
+    fn reset(
+        &self,
+        _alg: &str,
+        inp: &Self::Input,
+        ps: &Params,
+        out: &mut Self::Output
+    ) {
+        *out = vec![vec![0; ps.get("k")]; inp.len()];
+    }
+
+    fn run(
+        &self,
+        alg: &str,
+        inp: &Self::Input,
+        ps: &Params,
+        out: &mut Self::Output
+    ) {
+        match inp {
+            Points::Two(p) => ann(alg, p, ps.get("k"), out),
+            Points::Three(p) => ann(alg, p, ps.get("k"), out),
+        }
+    }
+
+    /// Brute force on a sample of the points: compares distances with the
+    /// `k` closest points, farthest first, so ties may come in any order.
+    fn check(
+        &self,
+        inp: &Self::Input,
+        out: &Self::Output,
+        ps: &Params
+    ) -> Option<bool> {
+        Some(match inp {
+            Points::Two(p) => is_knn(p, out, ps.get("k")),
+            Points::Three(p) => is_knn(p, out, ps.get("k")),
+        })
+    }
+
+    fn write(&self, out: &Self::Output, fname: &str) {
+        // the k neighbors of every point, one per line
+        let r: Vec<usize> = out.concat();
+        write_sequence(&r, "sequenceInt", fname).expect("cannot write to output");
+    }
+}
@@ -0,0 +1,67 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use rayon::prelude::*;
+
+use parlay::random::Random;
+use super::point::KnnPoint;
+
+/// How many points [`is_knn`] checks when there are more of them.
+pub const SAMPLES: usize = 200;
+
+/// Brute force on a random sample of the points (all of them when there are
+/// at most [`SAMPLES`]), like PBBS's knnCheck: the neighbors of a point must
+/// be distinct other points, and the i-th farthest of them must be as far as
+/// its i-th farthest among the `k` closest, so ties may come in any order.
+pub fn is_knn<P: KnnPoint>(inp: &[P], out: &[Vec<usize>], k: usize) -> bool {
+    let n = inp.len();
+    if out.len() != n { return false; }
+    let sample: Vec<usize> = if n <= SAMPLES { (0..n).collect() } else {
+        let r = Random::new(0);
+        (0..SAMPLES as u64).map(|i| (r.ith_rand(i) % n as u64) as usize).collect()
+    };
+    sample.par_iter().all(|&i| {
+        let p = &inp[i];
+        let ngh = &out[i];
+        let k = k.min(n - 1);
+        if ngh.len() != k || ngh.iter().any(|&j| j >= n || j == i) { return false; }
+        let mut seen = ngh.clone();
+        seen.sort_unstable();
+        if seen.windows(2).any(|w| w[0] == w[1]) { return false; }
+        if k == 0 { return true; }
+
+        let mut ds: Vec<f64> = inp
+            .par_iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .map(|(_, q)| p.get_distance(q))
+            .collect();
+        let cmp = |a: &f64, b: &f64| a.partial_cmp(b).unwrap();
+        ds.select_nth_unstable_by(k - 1, cmp);
+        ds[..k].sort_unstable_by(cmp);
+        ds[..k].iter().rev().zip(ngh).all(|(&d, &j)| p.get_distance(&inp[j]) == d)
+    })
+}
@@ -1,84 +1,28 @@
 use num_traits::Float;
 use rayon::prelude::*;
 use std::sync::{Arc, Weak, Mutex};
-use crate::common::geometry::Point2d;
-
-impl Point2d<f64> {
-    // Finds the min x and y values of two points
-    fn minv(&self, other: &Self) -> Self {
-        Point2d {
-            x: self.x.min(other.x),
-            y: self.y.min(other.y),
-        }
-    }
-
-    // Finds the max x and y values of two points
-    fn maxv(&self, other: &Self) -> Self {
-        Point2d {
-            x: self.x.max(other.x),
-            y: self.y.max(other.y),
-        }
-    }
-
-    // Finds the center x and y values of two points
-    fn centerv(&self, other: &Self) -> Self {
-        Point2d {
-            x: (self.x + other.x) / 2.0,
-            y: (self.y + other.y) / 2.0,
-        }
-    }
-
-    fn get_max_dim(&self, other: &Self) -> f64 {
-        (self.x - other.x).abs().max((self.y - other.y).abs())
-    }
-
-    // Find the center value and dimension, but it picks x/y that has larger difference
-    fn get_split_point(&self, other: &Self) -> (f64, usize) {
-        let diff_x = (self.x - other.x).abs();
-        let diff_y = (self.y - other.y).abs();
-        if diff_x >= diff_y {
-            ((self.x + other.x) / 2.0, 0) // 0 for x-axis
-        } else {
-            ((self.y + other.y) / 2.0, 1) // 1 for y-axis
-        }
-    }
-
-    fn get_dimension(&self, d: usize) -> f64 {
-        match d {
-            0 => self.x,
-            1 => self.y,
-            _ => panic!("Invalid dimension for 2D point"),
-        }
-    }
-
-    fn get_distance(&self, other: &Self) -> f64 { // same as get_diameter from pbbs
-        let dx = self.x - other.x;
-        let dy = self.y - other.y;
-        (dx * dx + dy * dy).sqrt()
-    }
-}
-
+use super::point::KnnPoint;
 
-pub struct Node {
+pub struct Node<P: KnnPoint> {
     pub n: usize,
     pub diameter: f64,
     pub max_dim: f64,
     pub id: usize,
-    pub parent: Mutex<Weak<Node>>,
-    pub left: Option<Arc<Node>>, 
-    pub right: Option<Arc<Node>>, 
-    pub vertex: Option<(Point2d<f64>, usize)>,
-    pub center: Point2d<f64>,
-    pub b: (Point2d<f64>, Point2d<f64>),
-    pub interactions: Mutex<Vec<Weak<Node>>>,
+    pub parent: Mutex<Weak<Node<P>>>,
+    pub left: Option<Arc<Node<P>>>, 
+    pub right: Option<Arc<Node<P>>>, 
+    pub vertex: Option<(P, usize)>,
+    pub center: P,
+    pub b: (P, P),
+    pub interactions: Mutex<Vec<Weak<Node<P>>>>,
 }
 
 
 
-impl Node {
+impl<P: KnnPoint> Node<P> {
     // Create a new leaf node
-    pub fn new_leaf(p: &[(Point2d<f64>, usize)], idty: usize) -> Arc<Self> {
-        let point = &p[0].0; // Access the single Point2d<f64> directly
+    pub fn new_leaf(p: &[(P, usize)], idty: usize) -> Arc<Self> {
+        let point = &p[0].0; // Access the single point directly
         Arc::new(Node {
             n: 1,
             diameter: 0.0, // No diameter for a single point
@@ -96,7 +40,7 @@ impl Node {
     
 
     // Create a new internal node
-    pub fn new_internal(l: Arc<Node>, r: Arc<Node>, idty: usize) -> Arc<Node> {
+    pub fn new_internal(l: Arc<Self>, r: Arc<Self>, idty: usize) -> Arc<Self> {
         let n = l.n + r.n;
         let bbox = (l.b.0.minv(&r.b.0), l.b.1.maxv(&r.b.1));
         let new_node = Arc::new(Node {
@@ -120,11 +64,11 @@ impl Node {
         new_node
     }
 
-    pub fn set_parent(&self, parent: &Arc<Node>) {
+    pub fn set_parent(&self, parent: &Arc<Self>) {
         *self.parent.lock().unwrap() = Arc::downgrade(parent);
     }
 
-    pub fn add_interaction(&self, other: &Arc<Node>) {
+    pub fn add_interaction(&self, other: &Arc<Self>) {
         self.interactions.lock().unwrap().push(Arc::downgrade(other));
     }
 
@@ -133,7 +77,7 @@ impl Node {
     }
 }
 
-pub fn get_box(v: &[&Point2d<f64>]) -> (Point2d<f64>, Point2d<f64>) {
+pub fn get_box<P: KnnPoint>(v: &[&P]) -> (P, P) {
     let n = v.len();
 
     if n == 0 {
@@ -158,7 +102,7 @@ pub fn get_box(v: &[&Point2d<f64>]) -> (Point2d<f64>, Point2d<f64>) {
     (min_point, max_point)
 }
 
-pub fn well_separated (a: &Arc<Node>, b: &Arc<Node>, s: f64) -> bool {
+pub fn well_separated<P: KnnPoint>(a: &Arc<Node<P>>, b: &Arc<Node<P>>, s: f64) -> bool {
     // Diameter of the smallest sphere that can capture each box
     let diameter = Float::max(a.diameter, b.diameter);
 
@@ -170,7 +114,7 @@ pub fn well_separated (a: &Arc<Node>, b: &Arc<Node>, s: f64) -> bool {
 }
 
 
-pub fn wsr_children(l: &Arc<Node>, r: &Arc<Node>, s: f64, k: usize) {
+pub fn wsr_children<P: KnnPoint>(l: &Arc<Node<P>>, r: &Arc<Node<P>>, s: f64, k: usize) {
     if well_separated(l, r, s) {
         if l.n <= k {
             l.add_interaction(r);
@@ -198,7 +142,7 @@ pub fn wsr_children(l: &Arc<Node>, r: &Arc<Node>, s: f64, k: usize) {
 }
 
 
-pub fn wsr(t: &Arc<Node>, s: f64, k: usize) {
+pub fn wsr<P: KnnPoint>(t: &Arc<Node<P>>, s: f64, k: usize) {
     if t.is_leaf() {
         return;
     }
@@ -221,10 +165,10 @@ pub fn wsr(t: &Arc<Node>, s: f64, k: usize) {
 }
 
 
-pub fn build_recursive(
-    points: &[(Point2d<f64>, usize)],
+pub fn build_recursive<P: KnnPoint>(
+    points: &[(P, usize)],
     id_offset: usize,
-) -> Arc<Node> {
+) -> Arc<Node<P>> {
     if points.is_empty() {
         panic!("Passed in slice of size 0 when building tree.");
     }
@@ -244,7 +188,7 @@ pub fn build_recursive(
     // Parallel computation of the split flags
     let flags_left: Vec<bool> = points
         .par_iter()
-        .map(|(p, _)| p.get_dimension(d) < split_point)
+        .map(|(p, _)| p.coord(d) < split_point)
         .collect();
 
     // Count the number of points in the left subtree
@@ -255,7 +199,7 @@ pub fn build_recursive(
 
     if points.len() > 1000 {
         // Parallel construction for large input
-        let (left, right): (Arc<Node>, Arc<Node>) = rayon::join(
+        let (left, right): (Arc<Node<P>>, Arc<Node<P>>) = rayon::join(
             || build_recursive(tmp_left, id_offset),
             || build_recursive(tmp_right, id_offset + split_index),
         );
@@ -268,7 +212,7 @@ pub fn build_recursive(
     }
 }
 
-fn collect_leaf_nodes(node: &Arc<Node>, leaves: &mut Vec<Arc<Node>>) {
+fn collect_leaf_nodes<P: KnnPoint>(node: &Arc<Node<P>>, leaves: &mut Vec<Arc<Node<P>>>) {
     if node.is_leaf() {
         leaves.push(node.clone());
     } else {
@@ -281,17 +225,17 @@ fn collect_leaf_nodes(node: &Arc<Node>, leaves: &mut Vec<Arc<Node>>) {
     }
 }
 
-fn gather_leaves(root: &Arc<Node>) -> Vec<Arc<Node>> {
+fn gather_leaves<P: KnnPoint>(root: &Arc<Node<P>>) -> Vec<Arc<Node<P>>> {
     let mut leaves = Vec::new();
     collect_leaf_nodes(root, &mut leaves);
     leaves
 }
 
-fn update_nearest(
-    query: &Point2d<f64>,
+fn update_nearest<P: KnnPoint>(
+    query: &P,
     neighbors: &mut Vec<usize>,
     distances: &mut Vec<f64>,
-    vertex: &(Point2d<f64>, usize), // Vertex contains the point and its original index in input vector
+    vertex: &(P, usize), // Vertex contains the point and its original index in input vector
     k: usize,
 ) {
     let dist = query.get_distance(&vertex.0); // Calculate distance to the candidate point
@@ -309,9 +253,9 @@ fn update_nearest(
     }
 }
 
-fn parallel_knn_search(
-    leaves: Vec<Arc<Node>>,
-    queries: Vec<(Point2d<f64>, usize)>,
+fn parallel_knn_search<P: KnnPoint>(
+    leaves: Vec<Arc<Node<P>>>,
+    queries: Vec<(P, usize)>,
     k: usize,
 ) -> Vec<Vec<usize>> {
     queries
@@ -333,9 +277,9 @@ fn parallel_knn_search(
         .collect()
 }
 
-pub fn ann(inp: &[Point2d<f64>], k: usize, res: &mut Vec<Vec<usize>>) {
+pub fn ann<P: KnnPoint>(inp: &[P], k: usize, res: &mut Vec<Vec<usize>>) {
     // Prepare indexed points
-    let indexed_points: Vec<(Point2d<f64>, usize)> = inp
+    let indexed_points: Vec<(P, usize)> = inp
         .iter()
         .enumerate()
         .map(|(idx, point)| (*point, idx))
@@ -0,0 +1,219 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::mem::MaybeUninit;
+use rayon::prelude::*;
+use super::point::KnnPoint;
+
+/// Nodes with at most this many points are not split.
+const LEAF_SIZE: usize = 16;
+/// Subtrees with fewer points than this are built sequentially.
+const SEQ_BUILD: usize = 1000;
+
+/// A node of the tree. Its points are `idx[start..end]`, and its children
+/// are indices into the arena, so the tree needs no pointers or locks.
+pub struct Node<P: KnnPoint> {
+    pub lo: P,
+    pub hi: P,
+    pub start: usize,
+    pub end: usize,
+    pub children: Option<(usize, usize)>,
+}
+
+/// A k-d tree over `points`, built into a flat arena with the root first.
+pub struct KdTree<'a, P: KnnPoint> {
+    points: &'a [P],
+    idx: Vec<usize>,
+    nodes: Vec<Node<P>>,
+}
+
+/// A candidate neighbor, ordered by distance and then by index, so the
+/// `k` kept are the ones a stable sort by distance would pick.
+#[derive(Clone, Copy, PartialEq)]
+struct Candidate(f64, usize);
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
+
+/// The number of nodes of a tree over `len > 0` points. Every node with more
+/// than [`LEAF_SIZE`] points is split at the median, so this only depends
+/// on `len`; the lengths on one level differ by at most one, so each level
+/// is a couple of (length, count) pairs.
+fn tree_size(len: usize) -> usize {
+    let (mut size, mut level) = (0, vec![(len, 1)]);
+    while !level.is_empty() {
+        let mut next: Vec<(usize, usize)> = vec![];
+        for (l, c) in level {
+            size += c;
+            if l <= LEAF_SIZE { continue; }
+            for h in [l / 2, l - l / 2] {
+                match next.iter_mut().find(|e| e.0 == h) {
+                    Some(e) => e.1 += c,
+                    None => next.push((h, c)),
+                }
+            }
+        }
+        level = next;
+    }
+    size
+}
+
+/// Builds the subtree over `idx` into `nodes`, its `tree_size(idx.len())`
+/// slots of the arena starting at `base`. The subtree's first point is at
+/// `start` in the whole index array. Points that all coincide are split
+/// like any others, so the slots are always filled exactly.
+fn build<P: KnnPoint>(
+    points: &[P],
+    idx: &mut [usize],
+    start: usize,
+    nodes: &mut [MaybeUninit<Node<P>>],
+    base: usize
+) {
+    debug_assert_eq!(nodes.len(), tree_size(idx.len()));
+    let (lo, hi) = idx[1..].iter().fold(
+        (points[idx[0]], points[idx[0]]),
+        |(lo, hi), &i| (lo.minv(&points[i]), hi.maxv(&points[i])),
+    );
+    let (n, end) = (idx.len(), start + idx.len());
+    if n <= LEAF_SIZE {
+        nodes[0].write(Node { lo, hi, start, end, children: None });
+        return;
+    }
+
+    // split at the median of the box's longest side
+    let (_, d) = lo.get_split_point(&hi);
+    let mid = n / 2;
+    idx.select_nth_unstable_by(mid, |&a, &b| {
+        points[a].coord(d).total_cmp(&points[b].coord(d))
+    });
+    let (root, rest) = nodes.split_first_mut().unwrap();
+    let (left, right) = rest.split_at_mut(tree_size(mid));
+    let right_base = base + 1 + left.len();
+    root.write(Node { lo, hi, start, end, children: Some((base + 1, right_base)) });
+
+    let (l, r) = idx.split_at_mut(mid);
+    if n > SEQ_BUILD {
+        rayon::join(
+            || build(points, l, start, left, base + 1),
+            || build(points, r, start + mid, right, right_base),
+        );
+    } else {
+        build(points, l, start, left, base + 1);
+        build(points, r, start + mid, right, right_base);
+    }
+}
+
+impl<'a, P: KnnPoint> KdTree<'a, P> {
+    pub fn new(points: &'a [P]) -> Self {
+        let mut idx: Vec<usize> = (0..points.len()).collect();
+        if points.is_empty() { return Self { points, idx, nodes: vec![] }; }
+        let size = tree_size(points.len());
+        let mut nodes = Vec::with_capacity(size);
+        build(points, &mut idx, 0, &mut nodes.spare_capacity_mut()[..size], 0);
+        // SAFETY: build wrote every one of the first size nodes
+        unsafe { nodes.set_len(size); }
+        Self { points, idx, nodes }
+    }
+
+    /// The squared distance from `q` to the box of `node`.
+    fn box_distance(&self, node: &Node<P>, q: &P) -> f64 {
+        (0..P::DIM)
+            .map(|d| {
+                let c = q.coord(d);
+                (node.lo.coord(d) - c).max(c - node.hi.coord(d)).max(0.0).powi(2)
+            })
+            .sum()
+    }
+
+    /// Descends into the closer child first, and skips the nodes whose box
+    /// is farther than the `k`-th closest point found so far.
+    fn search(&self, node: usize, q: usize, k: usize, heap: &mut BinaryHeap<Candidate>) {
+        let n = &self.nodes[node];
+        let p = &self.points[q];
+        match n.children {
+            None => {
+                for &i in &self.idx[n.start..n.end] {
+                    if i == q { continue; }
+                    let c = Candidate(p.get_distance(&self.points[i]), i);
+                    if heap.len() < k { heap.push(c); }
+                    else if c < *heap.peek().unwrap() {
+                        heap.pop();
+                        heap.push(c);
+                    }
+                }
+            }
+            Some((l, r)) => {
+                let (dl, dr) = (
+                    self.box_distance(&self.nodes[l], p),
+                    self.box_distance(&self.nodes[r], p),
+                );
+                let order = if dl <= dr { [(l, dl), (r, dr)] } else { [(r, dr), (l, dl)] };
+                for (child, dist) in order {
+                    if heap.len() == k && dist > heap.peek().unwrap().0.powi(2) {
+                        continue;
+                    }
+                    self.search(child, q, k, heap);
+                }
+            }
+        }
+    }
+
+    /// Writes the `k` nearest other points to point `q` into `row`,
+    /// farthest first. Slots without a neighbor are left as `q`.
+    pub fn knn(&self, q: usize, k: usize, row: &mut [usize]) {
+        if k == 0 || self.nodes.is_empty() { return; }
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        self.search(0, q, k, &mut heap);
+        row.fill(q);
+        for (slot, c) in row.iter_mut().zip(heap.into_sorted_vec().into_iter().rev()) {
+            *slot = c.1;
+        }
+    }
+}
+
+/// k-NN over a k-d tree kept in a flat arena: the tree is built once, then
+/// every point runs its own bounded-heap search in parallel and writes only
+/// its own row of `res`.
+pub fn ann<P: KnnPoint>(inp: &[P], k: usize, res: &mut Vec<Vec<usize>>) {
+    let tree = KdTree::new(inp);
+    res.par_iter_mut()
+        .enumerate()
+        .for_each(|(q, row)| {
+            row.resize(k, q);
+            tree.knn(q, k, row);
+        });
+}
@@ -1,5 +1,4 @@
 use core::panic;
-use std::time::Duration;
 // ============================================================================
 // This code is part of RPB.
 // ----------------------------------------------------------------------------
@@ -29,20 +28,14 @@ use std::time::Duration;
 
 use clap::Parser;
 
-#[path ="mod.rs"] mod knn;
-#[path ="../../misc.rs"] mod misc;
-#[path ="../macros.rs"] mod macros;
-#[path ="../../common/io.rs"] mod io;
-#[path ="../../common/mod.rs"] mod common;
 
-use misc::*;
-use knn::{naive, cktree};
-use io::{read_big_file_to_vec, write_slice_to_file_seq};
-use common::geometry_io::{read_points2d_from_file, read_points3d_from_file};
-use common::geometry::*;
-use common::traits::Length;
-use std::ops::Sub;
-use num_traits::Float;   // For float traits
+use pbbs::{count_alloc, define_algs, init};
+use pbbs::benchmarks::nearest_neighbors::{naive, cktree, kdtree, check::is_knn, point::KnnPoint};
+use pbbs::common::io::{write_sequence, BadLines, InputError};
+use pbbs::common::geometry_io::{read_points2d_from_file, read_points3d_from_file};
+use pbbs::common::time_loop::{RunStats, TimeConfig};
+use pbbs::common::report::{Record, ReportFormat};
+use pbbs::common::pool::Pinning;
 
 
 #[derive(Parser, Debug)]
@@ -60,9 +53,17 @@ struct Args {
     #[clap(value_parser, required=true)]
     ifname: String,
 
-    /// the number of rounds to execute the benchmark
-    #[clap(short, long, value_parser, required=false, default_value_t=1)]
-    rounds: usize,
+    #[clap(flatten)]
+    time: TimeConfig,
+
+    /// print a machine-readable record of the run
+    #[clap(long, value_enum, required=false)]
+    report: Option<ReportFormat>,
+
+    /// how to pin worker threads: none, compact, scatter,
+    /// physical-cores-first, or a cpu list such as 0-3,8
+    #[clap(long, default_value_t)]
+    pin: Pinning,
 
     /// The number of nearest neighbors to find
     #[clap(short = 'k', long, value_parser, required = false, default_value_t = 1)]
@@ -77,112 +78,74 @@ struct Args {
     check: bool,
 }
 
+count_alloc!();
+
 define_algs!(
     (NAIVE, "naive"),
-    (CKTREE, "cktree")
+    (CKTREE, "cktree"),
+    (KDTREE, "kdtree")
 );
 
-pub fn run(alg: Algs, rounds: usize, arr: &[Point2d<f64>], k: usize) -> (Vec<Vec<usize>>, Duration)
+pub fn run<P: KnnPoint>(alg: Algs, tc: &TimeConfig, arr: &[P], k: usize) -> (Vec<Vec<usize>>, RunStats)
 {
-    // Define the MAXK constant (you need to choose the value based on your use case)
-    const MAXK: usize = 1;
     let n = arr.len();
 
     // Wrap `ann` in a closure with specified generics
     let f = match alg {
         Algs::NAIVE => {naive::ann},
         Algs::CKTREE => {cktree::ann},
+        Algs::KDTREE => {kdtree::ann},
     };
 
     let mut r = vec![vec![0; k]; n];
     let r_ptr = &r as *const Vec<Vec<usize>> as usize;
 
-    let mean = time_loop(
+    let stats = time_loop(
         "knn",
-        rounds,
-        Duration::new(1, 0),
+        tc,
         || { unsafe { *(r_ptr as *mut Vec<Vec<usize>>).as_mut().unwrap() = vec![vec![0; k]; n]; } },
         || { f(arr, k, &mut r); },
         || {},
     );
 
-    (r, mean)
+    (r, stats)
 }
 
-fn check(inp: &[Point2d<f64>], out: &[Vec<usize>], k: usize) -> bool {
-    assert_eq!(out.len(), inp.len());
-
-    // Compute knn for inp
-    let mut knn = Vec::with_capacity(inp.len());
-    for (i, p) in inp.iter().enumerate() {
-        let mut distances = Vec::with_capacity(inp.len());
-        for (j, q) in inp.iter().enumerate() {
-            if i != j {
-                let dist = (*p - *q).length();
-                distances.push((dist, j));
-            }
-        }
-        // Sort distances
-        distances.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
-        // Pick the k nearest neighbors
-        let mut knn_p: Vec<usize> = distances.iter().take(k).map(|&(_, idx)| idx).collect();
-        knn_p.reverse();
-        knn.push(knn_p);
+fn bench<P: KnnPoint>(args: &Args, points: Result<Vec<P>, InputError>) {
+    let points = points.unwrap_or_else(|e| panic!("{e}"));
+
+    // print points size
+    println!("points size {:?}", points.len());
+
+    let (r, d) = run(args.algorithm, &args.time, &points, args.k);
+
+    // check the results
+    let checked = args.check.then(|| is_knn(&points, &r, args.k));
+    if let Some(res) = checked {
+        println!("Result is {}", res);
     }
 
-    // Compare knn with out
-    let mut diff_count = 0usize;
-    for i in 0..inp.len() {
-        let knn_indices = &knn[i];
-        let out_indices = &out[i];
-        for j in 0..k {
-            if knn_indices[j] != out_indices[j] {
-                diff_count += 1;
-            }
-        }
+    // the k neighbors of every point, one per line
+    let r: Vec<usize> = r.concat();
+    if !args.ofname.is_empty() {
+        write_sequence(&r, "sequenceInt", &args.ofname).expect("cannot write to output");
     }
 
-    diff_count == 0
+    // print the runtime
+    println!("Runtime {:?}", d.mean);
+    if let Some(fmt) = args.report {
+        Record::new("knn", &args.algorithm.to_string(), &args.ifname, &d, checked)
+            .emit(fmt);
+    }
 }
 
 fn main() {
-    init!();
     let args = Args::parse();
+    init!(&args.pin);
 
-    let ifname = args.ifname;
-    let ofname = args.ofname;
-    let k = args.k;
-    let rounds = args.rounds;
-    let dimension = args.dimension;
-
-    if dimension != 2 && dimension != 3 {
-        panic!("Only 2D and 3D points are supported");
+    match args.dimension {
+        2 => bench(&args, read_points2d_from_file(&args.ifname, BadLines::Skip)),
+        3 => bench(&args, read_points3d_from_file(&args.ifname, BadLines::Skip)),
+        _ => panic!("Only 2D and 3D points are supported"),
     }
-
-    if dimension == 2 {
-        let points: Vec<Point2d<f64>> = read_points2d_from_file(&ifname);
-
-        // print points size
-        println!("points size {:?}", points.len());
-
-        let (r, d) = run
-                                              (args.algorithm, args.rounds, &points, k);
-
-        // check the results
-        if args.check {
-            let res = check(&points, &r, k);
-            println!("Result is {}", res);
-        }
-
-        // convert r to list of strings
-        let r: Vec<String> = r.iter().map(|x| x.iter().map(|y| y.to_string()).collect()).collect();
-        if !ofname.is_empty() {
-            write_slice_to_file_seq(&r, &ofname);
-        }
-
-        // print the runtime
-        println!("Runtime {:?}", d);
-    } else if dimension == 3 {
-        panic!("3D points are not supported yet");
-    }
-}
\ No newline at end of file
+}
@@ -1,2 +1,6 @@
-pub(crate) mod naive;
-pub(crate) mod cktree;
+pub mod point;
+pub mod naive;
+pub mod cktree;
+pub mod kdtree;
+pub mod check;
+pub mod bench;
@@ -1,14 +1,7 @@
-use num_traits::PrimInt; // For integer traits
-use num_traits::Float;   // For float traits
-use rayon::{prelude::*, vec};
-use std::ops::Sub;
-use crate::DefInt;
-use crate::common::geometry::*;
+use rayon::prelude::*;
+use super::point::KnnPoint;
 
-// Declare `trait.rs` directly in main.rs
-use crate::common::traits::Length;
-
-pub fn ann(inp: &[Point2d<f64>], k: usize, res: &mut Vec<Vec<usize>>)
+pub fn ann<P: KnnPoint>(inp: &[P], k: usize, res: &mut Vec<Vec<usize>>)
 {
     // does not support for k > 1
     if k > 1 {
@@ -23,11 +16,11 @@ pub fn ann(inp: &[Point2d<f64>], k: usize, res: &mut Vec<Vec<usize>>)
         .enumerate()
         .for_each(|(i, index)| {
             let mut current_nearest_index = (i + 1) % n;
-            let mut min_distance = (inp[i].clone() - inp[current_nearest_index].clone()).length();
+            let mut min_distance = inp[i].get_distance(&inp[current_nearest_index]);
 
             for j in 0..n {
                 if j != i {
-                    let dist = (inp[i].clone() - inp[j].clone()).length();
+                    let dist = inp[i].get_distance(&inp[j]);
                     if dist < min_distance {
                         current_nearest_index = j;
                         min_distance = dist;
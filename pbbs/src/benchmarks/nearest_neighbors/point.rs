@@ -0,0 +1,104 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::common::geometry::{Point2d, Point3d};
+
+/// What the k-NN algorithms need of a point: its coordinates and the
+/// component-wise combination of two points, from which the bounding boxes
+/// and the k-d splits are built in any dimension.
+pub trait KnnPoint: Copy + Send + Sync {
+    const DIM: usize;
+
+    fn coord(&self, d: usize) -> f64;
+
+    /// The point whose `d`-th coordinate is `f` of the two `d`-th coordinates.
+    fn zip_with(&self, other: &Self, f: impl Fn(f64, f64) -> f64) -> Self;
+
+    fn minv(&self, other: &Self) -> Self { self.zip_with(other, f64::min) }
+
+    fn maxv(&self, other: &Self) -> Self { self.zip_with(other, f64::max) }
+
+    fn centerv(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| (a + b) / 2.0)
+    }
+
+    /// The largest extent of the box with corners `self` and `other`.
+    fn get_max_dim(&self, other: &Self) -> f64 {
+        (0..Self::DIM)
+            .map(|d| (self.coord(d) - other.coord(d)).abs())
+            .fold(0.0, f64::max)
+    }
+
+    /// Where to cut the box with corners `self` and `other`: the middle of
+    /// its longest side, and which dimension that is.
+    fn get_split_point(&self, other: &Self) -> (f64, usize) {
+        let d = (1..Self::DIM).fold(0, |best, d| {
+            let extent = |d| (self.coord(d) - other.coord(d)).abs();
+            if extent(d) > extent(best) { d } else { best }
+        });
+        ((self.coord(d) + other.coord(d)) / 2.0, d)
+    }
+
+    fn get_distance(&self, other: &Self) -> f64 {
+        (0..Self::DIM)
+            .map(|d| (self.coord(d) - other.coord(d)).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+}
+
+impl KnnPoint for Point2d<f64> {
+    const DIM: usize = 2;
+
+    fn coord(&self, d: usize) -> f64 {
+        match d {
+            0 => self.x,
+            1 => self.y,
+            _ => panic!("Invalid dimension for 2D point"),
+        }
+    }
+
+    fn zip_with(&self, other: &Self, f: impl Fn(f64, f64) -> f64) -> Self {
+        Point2d::new(f(self.x, other.x), f(self.y, other.y))
+    }
+}
+
+impl KnnPoint for Point3d<f64> {
+    const DIM: usize = 3;
+
+    fn coord(&self, d: usize) -> f64 {
+        match d {
+            0 => self.x,
+            1 => self.y,
+            2 => self.z,
+            _ => panic!("Invalid dimension for 3D point"),
+        }
+    }
+
+    fn zip_with(&self, other: &Self, f: impl Fn(f64, f64) -> f64) -> Self {
+        Point3d::new(f(self.x, other.x), f(self.y, other.y), f(self.z, other.z))
+    }
+}
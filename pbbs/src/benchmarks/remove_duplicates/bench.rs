@@ -0,0 +1,83 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::common::io::{read_big_file_to_vec, read_file_to_vec, write_sequence, InputError};
+use crate::runner::{Benchmark, Params};
+use super::parlay_hash;
+
+
+pub struct RemoveDuplicates;
+
+impl Benchmark for RemoveDuplicates {
+    type Input = Vec<u32>;
+    type Output = Vec<u32>;
+
+    fn name(&self) -> &'static str { "dedup" }
+    fn about(&self) -> &'static str { "remove duplicates" }
+    fn algorithms(&self) -> &'static [&'static str] { &["parhash"] }
+
+    fn read_input(&self, fname: &str, ps: &Params) -> Result<Vec<u32>, InputError> {
+        let mut arr = Vec::new();
+        read_big_file_to_vec(fname, Some("sequenceInt"), &mut arr, ps.bad_lines)?;
+        Ok(arr)
+    }
+
+    fn read_output(&self, fname: &str, _inp: &Self::Input, ps: &Params) -> Result<Vec<u32>, InputError> {
+        read_file_to_vec(fname, Some("sequenceInt"), ps.bad_lines)
+    }
+
+    fn output(&self, _inp: &Vec<u32>, _ps: &Params) -> Vec<u32> { vec![] }
+
+    fn reset(&self, _alg: &str, _inp: &Vec<u32>, _ps: &Params, out: &mut Vec<u32>) {
+        *out = vec![];
+    }
+
+    fn run(&self, alg: &str, inp: &Vec<u32>, _ps: &Params, out: &mut Vec<u32>) {
+        let f = match alg {
+            "parhash" => parlay_hash::dedup,
+            _ => unreachable!(),
+        };
+        f(inp, out);
+    }
+
+    fn check(
+        &self,
+        inp: &Vec<u32>,
+        out: &Vec<u32>,
+        _ps: &Params
+    ) -> Option<bool> {
+        let mut expected = inp.clone();
+        expected.sort_unstable();
+        expected.dedup();
+        let mut out = out.clone();
+        out.sort_unstable();
+        Some(expected == out)
+    }
+
+    fn write(&self, out: &Vec<u32>, fname: &str) {
+        write_sequence(out, "sequenceInt", fname).expect("cannot write to output");
+    }
+}
@@ -1,4 +1,3 @@
-use std::time::Duration;
 // ============================================================================
 // This code is part of RPB.
 // ----------------------------------------------------------------------------
@@ -26,18 +25,17 @@ use std::time::Duration;
 // ============================================================================
 
 
-#[path ="mod.rs"] mod dedup;
-#[path ="../../misc.rs"] mod misc;
-#[path ="../macros.rs"] mod macros;
-#[path ="../../common/io.rs"] mod io;
-
-use dedup::parlay_hash;
-use io::{read_big_file_to_vec, write_slice_to_file_seq};
+use pbbs::{count_alloc, define_algs, define_args, init, finalize};
+use pbbs::benchmarks::remove_duplicates::parlay_hash;
+use pbbs::common::io::{read_big_file_to_vec, write_sequence, BadLines};
+use pbbs::common::time_loop::{RunStats, TimeConfig};
 
 define_args!(Algs::PARHASH);
+count_alloc!();
+
 define_algs!((PARHASH, "parhash"));
 
-pub fn run(alg: Algs, rounds: usize, arr: &[u32]) -> (Vec<u32>, Duration) {
+pub fn run(alg: Algs, tc: &TimeConfig, arr: &[u32]) -> (Vec<u32>, RunStats) {
     let f = match alg {
         Algs::PARHASH => {parlay_hash::dedup},
     };
@@ -45,32 +43,33 @@ pub fn run(alg: Algs, rounds: usize, arr: &[u32]) -> (Vec<u32>, Duration) {
     let mut r = vec![];
     let r_ptr = &r as *const Vec<u32> as usize;
 
-    let mean = time_loop(
+    let stats = time_loop(
         "dedup",
-        rounds,
-        Duration::new(1, 0),
+        tc,
         || { unsafe { *(r_ptr as *mut Vec<u32>).as_mut().unwrap() = vec![]; } },
         || { f(&arr, &mut r); },
         || {}
     );
-    (r, mean)
+    (r, stats)
 }
 
 fn main() {
-    init!();
     let args = Args::parse();
+    init!(&args.pin);
     let mut arr = Vec::new();
     read_big_file_to_vec(
         &args.ifname,
-        Some { 0: |w: &[&str]| {debug_assert_eq!(w[0], "sequenceInt")} },
-        &mut arr
-    );
-    let (r, d) = run(args.algorithm, args.rounds, &arr);
+        Some("sequenceInt"),
+        &mut arr,
+        BadLines::Skip
+    ).unwrap_or_else(|e| panic!("{e}"));
+    let (r, d) = run(args.algorithm, &args.time, &arr);
 
     finalize!(
         args,
         r,
         d,
-        write_slice_to_file_seq(&r, args.ofname)
+        "dedup",
+        f => write_sequence(&r, "sequenceInt", &f).expect("cannot write to output")
     );
 }
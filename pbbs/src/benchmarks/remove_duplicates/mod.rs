@@ -1,4 +1,4 @@
-pub(crate) mod parlay_hash;
+pub mod parlay_hash;
 // ============================================================================
 // This code is part of RPB.
 // ----------------------------------------------------------------------------
@@ -25,3 +25,4 @@ pub(crate) mod parlay_hash;
 // SOFTWARE.
 // ============================================================================
 
+pub mod bench;
@@ -0,0 +1,93 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use parlay::primitives::pack;
+
+use crate::DefInt;
+use crate::common::graph::EdgeArray;
+use crate::common::graph_io::read_edge_array_from_file;
+use crate::common::io::{read_file_to_vec, write_sequence, InputError};
+use crate::runner::{Benchmark, Params};
+use super::{serial_sf, incremental_sf};
+
+
+pub struct SpanningForest;
+
+impl Benchmark for SpanningForest {
+    type Input = EdgeArray;
+    type Output = Vec<DefInt>;
+
+    fn name(&self) -> &'static str { "sf" }
+    fn about(&self) -> &'static str { "spanning forest" }
+
+    fn algorithms(&self) -> &'static [&'static str] {
+        &["incremental", "serial"]
+    }
+
+    fn read_input(&self, fname: &str, ps: &Params) -> Result<EdgeArray, InputError> {
+        read_edge_array_from_file(fname, ps.bad_lines)
+    }
+
+    fn read_output(&self, fname: &str, _inp: &Self::Input, ps: &Params) -> Result<Vec<DefInt>, InputError> {
+        read_file_to_vec(fname, Some("sequenceInt"), ps.bad_lines)
+    }
+
+    fn output(&self, _ea: &EdgeArray, _ps: &Params) -> Vec<DefInt> { vec![] }
+
+    fn run(&self, alg: &str, ea: &EdgeArray, _ps: &Params, out: &mut Vec<DefInt>) {
+        let f = match alg {
+            "serial" => serial_sf::spanning_forest,
+            "incremental" => incremental_sf::spanning_forest,
+            _ => unreachable!(),
+        };
+        *out = f(ea);
+    }
+
+    /// The forest must be as large as the serial one and acyclic, i.e.
+    /// the serial algorithm keeps all of its edges.
+    fn check(
+        &self,
+        ea: &EdgeArray,
+        out: &Vec<DefInt>,
+        _ps: &Params
+    ) -> Option<bool> {
+        if out.len() != serial_sf::spanning_forest(ea).len()
+            || out.iter().any(|&i| i as usize >= ea.non_zeros)
+        {
+            return Some(false);
+        }
+        let mut flags = vec![false; ea.non_zeros];
+        out.iter().for_each(|&o| flags[o as usize] = true);
+        let mut es = vec![];
+        pack(&ea.es, &flags, &mut es);
+        let forest = EdgeArray::new(es, ea.num_rows, ea.num_cols);
+        Some(serial_sf::spanning_forest(&forest).len() == out.len())
+    }
+
+    fn write(&self, out: &Vec<DefInt>, fname: &str) {
+        write_sequence(out, "sequenceInt", fname).expect("cannot write to output");
+    }
+}
@@ -28,15 +28,14 @@ use std::mem::swap;
 use rayon::prelude::*;
 
 use crate::{DefIntS, DefInt};
-use crate::graph::EdgeArray;
+use crate::common::graph::EdgeArray;
 
-#[path="../../common/spec_for.rs"] mod spec_for;
-use spec_for::{Reservation, StatefulSpecFor};
+use crate::common::spec_for::{Reservation, StatefulSpecFor};
 
 #[cfg(not(feature = "AW_safe"))]
-use crate::union_find::UnionFind;
+use crate::algorithm::union_find::UnionFind;
 #[cfg(feature = "AW_safe")]
-use crate::union_find::AtomicUnionFind;
+use crate::algorithm::union_find::AtomicUnionFind;
 
 
 #[derive(Clone)]
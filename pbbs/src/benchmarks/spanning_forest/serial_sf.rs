@@ -26,8 +26,8 @@ use parlay::maybe_uninit_vec;
 // ============================================================================
 
 use crate::{DefInt, DefIntS};
-use crate::graph::EdgeArray;
-use crate::union_find::UnionFind;
+use crate::common::graph::EdgeArray;
+use crate::algorithm::union_find::UnionFind;
 
 pub fn spanning_forest(ea: &EdgeArray) -> Vec<DefInt> {
     let n = ea.num_rows;
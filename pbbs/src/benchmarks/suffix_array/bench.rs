@@ -0,0 +1,104 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use rayon::prelude::*;
+
+use crate::{DefChar, DefInt};
+#[cfg(feature = "AW_safe")]
+use crate::{DefAtomInt, ORDER};
+use crate::common::io::{chars_from_file, read_file_to_vec, write_sequence, InputError};
+use crate::runner::{Benchmark, Params};
+use super::parallel_range;
+
+#[cfg(not(feature = "AW_safe"))]
+type Rank = DefInt;
+#[cfg(feature = "AW_safe")]
+type Rank = DefAtomInt;
+
+
+pub struct SuffixArray;
+
+#[cfg(not(feature = "AW_safe"))]
+fn ranks(r: &[Rank]) -> Vec<DefInt> { r.to_vec() }
+#[cfg(feature = "AW_safe")]
+fn ranks(r: &[Rank]) -> Vec<DefInt> {
+    r.par_iter().map(|ri| ri.load(ORDER)).collect()
+}
+
+impl Benchmark for SuffixArray {
+    type Input = Vec<DefChar>;
+    type Output = Vec<Rank>;
+
+    fn name(&self) -> &'static str { "sa" }
+    fn about(&self) -> &'static str { "suffix array" }
+    fn algorithms(&self) -> &'static [&'static str] { &["par-range"] }
+
+    fn read_input(&self, fname: &str, _ps: &Params) -> Result<Vec<DefChar>, InputError> {
+        chars_from_file(fname, false).map_err(|e| InputError::io(fname, e))
+    }
+
+    fn read_output(&self, fname: &str, _inp: &Vec<DefChar>, ps: &Params) -> Result<Vec<Rank>, InputError> {
+        let r: Vec<DefInt> = read_file_to_vec(fname, Some("sequenceInt"), ps.bad_lines)?;
+        Ok(r.into_par_iter().map(Rank::from).collect())
+    }
+
+    fn output(&self, inp: &Vec<DefChar>, _ps: &Params) -> Vec<Rank> {
+        (0..inp.len())
+            .into_par_iter()
+            .map(|_| Rank::default())
+            .collect()
+    }
+
+    fn run(&self, alg: &str, inp: &Vec<DefChar>, _ps: &Params, out: &mut Vec<Rank>) {
+        let f = match alg {
+            "par-range" => parallel_range::suffix_array,
+            _ => unreachable!(),
+        };
+        f(inp, out);
+    }
+
+    /// Every suffix must be smaller than the next one.
+    fn check(
+        &self,
+        inp: &Vec<DefChar>,
+        out: &Vec<Rank>,
+        _ps: &Params
+    ) -> Option<bool> {
+        let sa = ranks(out);
+        if sa.len() != inp.len() || sa.iter().any(|&i| i as usize >= inp.len()) {
+            return Some(false);
+        }
+        Some(
+            sa
+                .par_windows(2)
+                .all(|w| inp[w[0] as usize..] < inp[w[1] as usize..])
+        )
+    }
+
+    fn write(&self, out: &Vec<Rank>, fname: &str) {
+        write_sequence(&ranks(out), "sequenceInt", fname).expect("cannot write to output");
+    }
+}
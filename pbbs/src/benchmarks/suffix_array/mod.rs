@@ -1,4 +1,4 @@
-pub(crate) mod parallel_range;
+pub mod parallel_range;
 // ============================================================================
 // This code is part of RPB.
 // ----------------------------------------------------------------------------
@@ -25,3 +25,4 @@ pub(crate) mod parallel_range;
 // SOFTWARE.
 // ============================================================================
 
+pub mod bench;
@@ -1,4 +1,4 @@
-use crate::suffix_array;
+use crate::algorithm::suffix_array;
 // ============================================================================
 // This code is part of RPB.
 // ----------------------------------------------------------------------------
@@ -25,27 +25,27 @@
 // SOFTWARE.
 // ============================================================================
 
-use std::time::Duration;
 use rayon::prelude::*;
 
-#[path ="mod.rs"] mod sa;
-#[path ="../../misc.rs"] mod misc;
-#[path ="../macros.rs"] mod macros;
-#[path ="../../common/io.rs"] mod io;
-#[path ="../../algorithm/suffix_array.rs"] mod suffix_array;
 
-use misc::*;
-use sa::parallel_range;
-use io::{chars_from_file, write_slice_to_file_seq};
+use pbbs::{count_alloc, define_algs, define_args, init, finalize};
+use pbbs::{DefChar, DefInt};
+use pbbs::common::time_loop::{RunStats, TimeConfig};
+#[cfg(feature = "AW_safe")]
+use pbbs::{DefAtomInt, ORDER};
+use pbbs::benchmarks::suffix_array::parallel_range;
+use pbbs::common::io::{chars_from_file, write_sequence};
 
 define_args!(Algs::ParRange);
+count_alloc!();
+
 define_algs!((ParRange, "par-range"));
 
 pub fn run(
     alg: Algs,
-    rounds: usize,
+    tc: &TimeConfig,
     inp: &[DefChar]
-) -> (Vec<DefInt>, Duration)
+) -> (Vec<DefInt>, RunStats)
 {
     let f = match alg {
         Algs::ParRange => {parallel_range::suffix_array},
@@ -62,29 +62,29 @@ pub fn run(
         .map(|_| DefAtomInt::default())
         .collect();
 
-    let mean = time_loop(
+    let stats = time_loop(
         "sa",
-        rounds,
-        Duration::new(1, 0),
+        tc,
         || {},
         || { f(&inp, &mut r); },
         || {}
     );
     #[cfg(feature = "AW_safe")]
     let r: Vec<_> = r.into_par_iter().map(|ri| ri.load(ORDER)).collect();
-    (r, mean)
+    (r, stats)
 }
 
 fn main() {
-    init!();
     let args = Args::parse();
+    init!(&args.pin);
     let arr = chars_from_file(&args.ifname, false).unwrap();
-    let (r, d) = run(args.algorithm, args.rounds, &arr);
+    let (r, d) = run(args.algorithm, &args.time, &arr);
 
     finalize!(
         args,
         r,
         d,
-        write_slice_to_file_seq(&r, args.ofname)
+        "sa",
+        f => write_sequence(&r, "sequenceInt", &f).expect("cannot write to output")
     );
 }
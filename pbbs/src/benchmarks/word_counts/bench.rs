@@ -0,0 +1,114 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::DefChar;
+use crate::common::io::{chars_from_file, read_with_header, split_lines, write_sequence, InputError};
+use crate::runner::{Benchmark, Params};
+use super::{serial, parallel};
+
+
+pub struct WordCounts;
+
+impl Benchmark for WordCounts {
+    type Input = Vec<DefChar>;
+    type Output = Vec<(String, i64)>;
+
+    fn name(&self) -> &'static str { "wc" }
+    fn about(&self) -> &'static str { "word counts" }
+
+    fn algorithms(&self) -> &'static [&'static str] {
+        &["parallel", "sequential"]
+    }
+
+    fn read_input(&self, fname: &str, _ps: &Params) -> Result<Vec<DefChar>, InputError> {
+        chars_from_file(fname, false).map_err(|e| InputError::io(fname, e))
+    }
+
+    /// A `sequenceStringIntPair`: one `word count` per line.
+    fn read_output(&self, fname: &str, _inp: &Vec<DefChar>, _ps: &Params) -> Result<Self::Output, InputError> {
+        let s = read_with_header(fname, Some("sequenceStringIntPair"))?;
+        String::from_utf8_lossy(split_lines(&s, 1).1)
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+            .map(|(i, l)| l.rsplit_once(' ')
+                .and_then(|(w, c)| Some((w.to_string(), c.trim().parse().ok()?)))
+                .ok_or_else(|| InputError::new(fname, Some(i + 2), format!("cannot parse '{l}'"))))
+            .collect()
+    }
+
+    fn output(&self, _inp: &Vec<DefChar>, _ps: &Params) -> Self::Output {
+        vec![]
+    }
+
+    fn reset(
+        &self,
+        _alg: &str,
+        _inp: &Vec<DefChar>,
+        _ps: &Params,
+        out: &mut Self::Output
+    ) {
+        *out = vec![];
+    }
+
+    fn run(
+        &self,
+        alg: &str,
+        inp: &Vec<DefChar>,
+        _ps: &Params,
+        out: &mut Self::Output
+    ) {
+        let f = match alg {
+            "parallel" => parallel::wc,
+            "sequential" => serial::wc,
+            _ => unreachable!(),
+        };
+        f(&mut inp.clone(), out);
+    }
+
+    /// Compares with the sequential counts.
+    fn check(
+        &self,
+        inp: &Vec<DefChar>,
+        out: &Self::Output,
+        _ps: &Params
+    ) -> Option<bool> {
+        let mut expected = vec![];
+        serial::wc(&mut inp.clone(), &mut expected);
+        expected.sort();
+        let mut out = out.clone();
+        out.sort();
+        Some(expected == out)
+    }
+
+    fn write(&self, out: &Self::Output, fname: &str) {
+        let r: Vec<String> = out
+            .iter()
+            .map(|(word, count)| format!("{} {}", word, count))
+            .collect();
+        write_sequence(&r, "sequenceStringIntPair", fname).expect("cannot write to output");
+    }
+}
@@ -1,13 +1,11 @@
 use clap::Parser;
-use std::time::Duration;
-#[path ="mod.rs"] mod wc;
-#[path ="../../misc.rs"] mod misc;
-#[path ="../macros.rs"] mod macros;
-#[path ="../../common/io.rs"] mod io;
 
-use misc::*;
-use wc::{serial, parallel};
-use io::{chars_from_file, write_slice_to_file_seq};
+use pbbs::{count_alloc, define_algs, init, finalize};
+use pbbs::benchmarks::word_counts::{serial, parallel};
+use pbbs::common::io::{chars_from_file, write_sequence};
+use pbbs::common::time_loop::{RunStats, TimeConfig};
+use pbbs::common::report::ReportFormat;
+use pbbs::common::pool::Pinning;
 
 
 #[derive(Parser, Debug)]
@@ -25,17 +23,31 @@ struct Args {
     #[clap(value_parser, required=true)]
     ifname: String,
 
-    /// the number of rounds to execute the benchmark
-    #[clap(short, long, value_parser, required=false, default_value_t=1)]
-    rounds: usize,
+    #[clap(flatten)]
+    time: TimeConfig,
+
+    /// print a machine-readable record of the run
+    #[clap(long, value_enum, required=false)]
+    report: Option<ReportFormat>,
+
+    /// how to pin worker threads: none, compact, scatter,
+    /// physical-cores-first, or a cpu list such as 0-3,8
+    #[clap(long, default_value_t)]
+    pin: Pinning,
+
+    /// check the output the way `pbbs check` does
+    #[clap(short, long)]
+    check: bool,
 }
 
+count_alloc!();
+
 define_algs!(
     (PARALLEL, "parallel"),
     (SEQUENTIAL, "sequential")
 );
 
-pub fn run(alg: Algs, rounds: usize, arr: Vec<u8>) -> (Vec<String>, Duration) {
+pub fn run(alg: Algs, tc: &TimeConfig, arr: Vec<u8>) -> (Vec<String>, RunStats) {
     let f = match alg {
         Algs::PARALLEL => {parallel::wc},
         Algs::SEQUENTIAL => {serial::wc},
@@ -44,29 +56,29 @@ pub fn run(alg: Algs, rounds: usize, arr: Vec<u8>) -> (Vec<String>, Duration) {
     let res_ptr = &res as *const Vec<(String, i64)> as usize;
     
     
-    let mean = time_loop(
+    let stats = time_loop(
         "wc",
-        rounds,
-        Duration::new(1, 0),
+        tc,
         || { unsafe { *(res_ptr as *mut Vec<(String, i64)>).as_mut().unwrap() = vec![]; } },
         || { f(&mut arr.clone(), &mut res); },
         || {}
     );
 
     let res = res.iter().map(|(word, count)| format!("{} {}", word.to_string(), count.to_string())).collect();
-    (res, mean)
+    (res, stats)
 }
 
 fn main() {
-    init!();
     let args: Args = Args::parse();
+    init!(&args.pin);
     let arr = chars_from_file(&args.ifname, false).unwrap();
-    let (r, d) = run(args.algorithm, args.rounds, arr);
+    let (r, d) = run(args.algorithm, &args.time, arr);
 
     finalize!(
         args,
         r,
         d,
-        write_slice_to_file_seq(&r, args.ofname)
+        "wc",
+        f => write_sequence(&r, "sequenceStringIntPair", &f).expect("cannot write to output")
     );
 }
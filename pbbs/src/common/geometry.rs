@@ -29,7 +29,7 @@ use std::str::FromStr;
 use std::fmt::{Debug, Display, LowerExp};
 use num_traits::Float;
 
-use crate::common::io::fmt_f64;
+use crate::common::io::{fmt_f64, FromWords};
 use crate::common::traits::Length;
 
 
@@ -123,6 +123,12 @@ impl<T: Float + Display + LowerExp> Display for Point2d<T> {
     }
 }
 
+impl<T: Float + FromWords> FromWords for Point2d<T> {
+    fn from_words<'a, I: Iterator<Item = &'a [u8]>>(w: &mut I) -> Option<Self> {
+        Some(Self::new(T::from_words(w)?, T::from_words(w)?))
+    }
+}
+
 pub struct ParsePoint2dError;
 
 impl Display for ParsePoint2dError {
@@ -226,6 +232,12 @@ where
     }
 }
 
+impl<T: Float + FromWords> FromWords for Point3d<T> {
+    fn from_words<'a, I: Iterator<Item = &'a [u8]>>(w: &mut I) -> Option<Self> {
+        Some(Self::new(T::from_words(w)?, T::from_words(w)?, T::from_words(w)?))
+    }
+}
+
 pub struct ParsePoint3dError;
 
 impl Display for ParsePoint3dError {
@@ -25,82 +25,75 @@ use std::fs;
 // SOFTWARE.
 // ============================================================================
 
-use std::fmt::Debug;
-use std::str::FromStr;
 use num_traits::Float;
 use rayon::prelude::*;
-use rayon::str::ParallelString;
 
-use crate::common::io::read_file_to_vec;
+use crate::common::io::{
+    parse_line, parse_words, read_file_to_vec, read_with_header, split_records, BadLines, FromWords,
+    InputError
+};
 use crate::common::geometry::*;
 
 
 const HEADER_TRI: &str = "pbbs_triangles";
 
-pub fn read_points2d_from_file<T>(fname: &str) -> Vec<Point2d<T>>
+pub fn read_points2d_from_file<T>(fname: &str, bad: BadLines) -> Result<Vec<Point2d<T>>, InputError>
 where
-    T: Float + FromStr + Send,
-    <T as std::str::FromStr>::Err: Debug
+    T: Float + FromWords + Send,
 {
-    read_file_to_vec(
-        fname,
-        Some {0: |w: &[&str]| debug_assert_eq!(w[0], "pbbs_sequencePoint2d")}
-    )
+    read_file_to_vec(fname, Some("pbbs_sequencePoint2d"), bad)
 }
 
-pub fn read_points3d_from_file<T>(fname: &str) -> Vec<Point3d<T>>
+pub fn read_points3d_from_file<T>(fname: &str, bad: BadLines) -> Result<Vec<Point3d<T>>, InputError>
 where
-    T: Float + FromStr + Send,
-    <T as std::str::FromStr>::Err: Debug
+    T: Float + FromWords + Send,
 {
-    read_file_to_vec(
-        fname,
-        Some {0: |w: &[&str]| debug_assert_eq!(w[0], "pbbs_sequencePoint3d")}
-    )
+    read_file_to_vec(fname, Some("pbbs_sequencePoint3d"), bad)
 }
 
-pub fn read_triangles_from_file<P>(fname: &str, offset: usize) -> Triangles<P>
+pub fn read_triangles_from_file<P>(
+    fname: &str,
+    offset: usize,
+    bad: BadLines
+) -> Result<Triangles<P>, InputError>
 where
-    P: FromStr + Send,
-    <P as std::str::FromStr>::Err: Debug + Send
+    P: FromWords + Send,
 {
-    let w = fs::read_to_string(fname)
-        .expect("cannot read input triangles's file");
-    let mut w: Vec<&str> = w.trim().par_split('\n').collect();
-
-    // Parse header: string
-    assert_eq!(w.remove(0), HEADER_TRI);
+    let s = read_with_header(fname, Some(HEADER_TRI))?;
+    let (head, body, skip) = split_records(&s, 3);
+    let head: Vec<&[u8]> = head.split(|&c| c == b'\n')
+        .filter(|l| !l.trim_ascii().is_empty())
+        .collect();
 
     // Parse header: n and m
-    let n: usize = w.remove(0).parse().unwrap();
-    let m: usize = w.remove(0).parse().unwrap();
-    debug_assert_eq!(w.len(), n + m);
+    let size = |i: usize| head.get(i)
+        .and_then(|l| parse_line::<usize>(l))
+        .ok_or_else(|| InputError::new(fname, Some(i + 1), "expected the number of points and of triangles"));
+    let (n, m) = (size(1)?, size(2)?);
 
     // Parse points and triangles
-    let pnts: Vec<P> = w[..n]
-        .into_par_iter()
-        .cloned()
-        .map(str::parse)
-        .filter(Result::is_ok)
-        .map(Result::unwrap)
-        .collect();
+    // blank lines are skipped, so the triangles start after n non-blank ones
+    let (pnts, tris, lines) = split_records(body, n);
+    let pnts: Vec<P> = parse_words(fname, pnts, skip, bad)?;
+    let tris: Vec<Tri> = parse_words(fname, tris, skip + lines, bad)?;
+    if pnts.len() != n || tris.len() != m {
+        return Err(InputError::new(fname, None, format!(
+            "expected {n} points and {m} triangles, got {} and {}", pnts.len(), tris.len()
+        )));
+    }
 
     let offset = offset as i32;
-    let tris: Vec<Tri> = w[n..]
+    let tris: Vec<Tri> = tris
         .into_par_iter()
-        .cloned()
-        .map( |s: &str| {
-            let s: Vec<&str> = s.trim().split_whitespace().collect();
-            [
-                s[0].parse::<i32>().unwrap() - offset,
-                s[1].parse::<i32>().unwrap() - offset,
-                s[2].parse::<i32>().unwrap() - offset
-            ]
-        }).collect();
-    debug_assert_eq!(pnts.len(), n);
-    debug_assert_eq!(tris.len(), m);
+        .map(|t| t.map(|c| c - offset))
+        .collect();
+    if let Some(t) = tris.par_iter().find_first(|t| t.iter().any(|&c| c < 0 || c as usize >= n)) {
+        return Err(InputError::new(fname, None, format!(
+            "the triangle {} {} {} has a corner past the {n} points", t[0], t[1], t[2]
+        )));
+    }
 
-    Triangles::new(pnts, tris)
+    Ok(Triangles::new(pnts, tris))
 }
 
 pub fn write_triangles_to_file<P, F>(tris: &Triangles<P>, fname: F)
@@ -25,9 +25,14 @@ use std::{ops::Index, str::FromStr};
 // SOFTWARE.
 // ============================================================================
 
+use std::ops::Deref;
+use std::sync::Arc;
+
 use rayon::prelude::*;
 
 use crate::{DefInt, DefFloat};
+use super::graph_bin::MappedGraph;
+use super::io::FromWords;
 
 // **************************************************************
 //    EDGE ARRAY REPRESENTATION
@@ -60,6 +65,12 @@ impl FromStr for Edge {
     }
 }
 
+impl FromWords for Edge {
+    fn from_words<'a, I: Iterator<Item = &'a [u8]>>(w: &mut I) -> Option<Self> {
+        Some(Self::new(DefInt::from_words(w)?, DefInt::from_words(w)?))
+    }
+}
+
 pub struct ParseEdgeError;
 
 impl std::fmt::Display for ParseEdgeError {
@@ -137,6 +148,12 @@ impl FromStr for WghEdge {
     }
 }
 
+impl FromWords for WghEdge {
+    fn from_words<'a, I: Iterator<Item = &'a [u8]>>(w: &mut I) -> Option<Self> {
+        Some(Self::new(DefInt::from_words(w)?, DefInt::from_words(w)?, DefFloat::from_words(w)?))
+    }
+}
+
 #[derive(Clone)]
 pub struct WghEdgeArray {
     pub es: Vec<WghEdge>,
@@ -185,9 +202,43 @@ impl<'a> Default for Vertex<'a> {
     }
 }
 
+/// The offsets or the edges of a [`Graph`]: owned, or borrowed from a
+/// mapped binary graph, which stays mapped as long as they are used.
+pub enum GraphBuf {
+    Owned(Vec<DefInt>),
+    Mapped { _map: Arc<MappedGraph>, ptr: *const DefInt, len: usize },
+}
+
+// SAFETY: a mapped buffer only reads the mapping it keeps alive
+unsafe impl Send for GraphBuf {}
+unsafe impl Sync for GraphBuf {}
+
+impl GraphBuf {
+    /// Borrows `s`, which has to be a section of `map`.
+    pub(crate) fn mapped(map: &Arc<MappedGraph>, s: &[DefInt]) -> Self {
+        GraphBuf::Mapped { _map: map.clone(), ptr: s.as_ptr(), len: s.len() }
+    }
+}
+
+impl Deref for GraphBuf {
+    type Target = [DefInt];
+
+    fn deref(&self) -> &[DefInt] {
+        match self {
+            GraphBuf::Owned(v) => v,
+            // SAFETY: the mapping is kept alive by _map and never changes
+            GraphBuf::Mapped { ptr, len, .. } => unsafe { std::slice::from_raw_parts(*ptr, *len) },
+        }
+    }
+}
+
+impl From<Vec<DefInt>> for GraphBuf {
+    fn from(v: Vec<DefInt>) -> Self { GraphBuf::Owned(v) }
+}
+
 pub struct Graph {
-    pub offsets: Vec<DefInt>,
-    pub edges: Vec<DefInt>,
+    pub offsets: GraphBuf,
+    pub edges: GraphBuf,
     pub degrees: Vec<DefInt>,
     pub n: usize,
     pub m: usize,
@@ -205,7 +256,7 @@ impl Graph {
         }
     }
 
-    pub const fn get_offsets(&self) -> &Vec<DefInt>
+    pub fn get_offsets(&self) -> &[DefInt]
     { &self.offsets }
 
     pub fn add_degrees(&mut self) {
@@ -222,8 +273,8 @@ impl Graph {
         debug_assert_eq!(edges.len(), offsets[n] as usize);
 
         Self {
-            offsets: offsets.to_vec(),
-            edges: edges.to_vec(),
+            offsets: offsets.to_vec().into(),
+            edges: edges.to_vec().into(),
             n: n,
             m: edges.len(),
             degrees: vec![],
@@ -0,0 +1,251 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! A binary CSR graph format that loads by mapping the file into memory.
+//! Its offsets and edges have the width [`Graph`] uses, so a graph read
+//! from it borrows the mapping instead of copying it.
+//!
+//! All fields are little-endian:
+//!
+//! | bytes      | field                                          |
+//! |------------|------------------------------------------------|
+//! | 8          | the magic `RPBGRAPH`                           |
+//! | 4          | the format version, [`VERSION`]                |
+//! | 4          | flags; bit 0 is set if the graph is weighted,  |
+//! |            | bit 1 if its weights are floats                |
+//! | 8          | n, the number of vertices                      |
+//! | 8          | m, the number of edges                         |
+//! | 4 (n + 1)  | the offsets, as u32, ending with m             |
+//! | 4 m        | the edge targets, as u32                       |
+//! | 4 m        | the weights, as u32 or f32, if it is weighted  |
+//!
+//! Weights are written as integers if they all are integers that fit in 32
+//! bits, and as f32 otherwise, so fractional weights are not rounded.
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use memmap2::Mmap;
+use rayon::prelude::*;
+
+use super::graph::{Graph, GraphBuf};
+
+
+pub const MAGIC: &[u8; 8] = b"RPBGRAPH";
+pub const VERSION: u32 = 2;
+const WEIGHTED: u32 = 1;
+const FLOAT_WEIGHTS: u32 = 2;
+const HEADER_LEN: usize = 32;
+
+fn invalid(msg: String) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, msg) }
+
+/// Whether the file starts with the binary graph magic.
+pub fn is_binary<P: AsRef<Path>>(path: P) -> bool {
+    let mut magic = [0; 8];
+    File::open(path).and_then(|mut f| f.read_exact(&mut magic)).is_ok() && &magic == MAGIC
+}
+
+/// The weights of a binary graph, borrowed from the mapping.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Weights<'a> {
+    Int(&'a [u32]),
+    Float(&'a [f32]),
+}
+
+impl Weights<'_> {
+    /// The weight of the `i`th edge.
+    pub fn get(&self, i: usize) -> f64 {
+        match self {
+            Weights::Int(w) => w[i] as f64,
+            Weights::Float(w) => w[i] as f64,
+        }
+    }
+}
+
+/// A binary graph file mapped into memory. The offsets, edges and weights
+/// are borrowed straight from the mapping, without parsing or copying, and
+/// a [`Graph`] made from it (`From<Arc<MappedGraph>>`) borrows them too.
+pub struct MappedGraph {
+    map: Mmap,
+    pub n: usize,
+    pub m: usize,
+    pub weighted: bool,
+    float_weights: bool,
+}
+
+impl MappedGraph {
+    /// Maps `path` and checks its header and length, that the offsets go
+    /// from 0 up to m without decreasing and that every edge target is a
+    /// vertex, so that a corrupt file is an error here rather than an out of
+    /// bounds access in a benchmark.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        if cfg!(target_endian = "big") {
+            return Err(invalid("binary graphs are little-endian".into()));
+        }
+        let map = unsafe { Mmap::map(&File::open(path)?)? };
+        if map.len() < HEADER_LEN || &map[..8] != MAGIC {
+            return Err(invalid("not a binary graph".into()));
+        }
+        let word = |i: usize| u32::from_le_bytes(map[i..i + 4].try_into().unwrap());
+        let long = |i: usize| u64::from_le_bytes(map[i..i + 8].try_into().unwrap()) as usize;
+        if word(8) != VERSION {
+            return Err(invalid(format!("binary graph version {}, expected {VERSION}", word(8))));
+        }
+        let (weighted, n, m) = (word(12) & WEIGHTED != 0, long(16), long(24));
+        let float_weights = weighted && word(12) & FLOAT_WEIGHTS != 0;
+        let len = n.checked_add(1)
+            .and_then(|x| x.checked_mul(4))
+            .zip(m.checked_mul(if weighted { 8 } else { 4 }))
+            .and_then(|(o, e)| o.checked_add(e)?.checked_add(HEADER_LEN));
+        if len != Some(map.len()) {
+            return Err(invalid(format!(
+                "a binary graph with n={n} and m={m} does not take {} bytes", map.len()
+            )));
+        }
+        let g = Self { map, n, m, weighted, float_weights };
+
+        let offsets = g.offsets();
+        if offsets[0] != 0 || offsets[n] as usize != m {
+            return Err(invalid("the offsets do not go from 0 to m".into()));
+        }
+        if let Some(v) = (0..n).into_par_iter().find_any(|&v| offsets[v] > offsets[v + 1]) {
+            return Err(invalid(format!("the offsets decrease at vertex {v}")));
+        }
+        if let Some(t) = g.edges().par_iter().find_any(|&&t| t as usize >= n) {
+            return Err(invalid(format!("edge target {t} is not a vertex (n={n})")));
+        }
+        Ok(g)
+    }
+
+    fn slice<T>(&self, at: usize, len: usize) -> &[T] {
+        let bytes = &self.map[at..at + len * std::mem::size_of::<T>()];
+        // the mapping is page aligned and every section starts at a multiple
+        // of its element size
+        let (pre, s, _) = unsafe { bytes.align_to::<T>() };
+        debug_assert!(pre.is_empty());
+        s
+    }
+
+    pub fn offsets(&self) -> &[u32] { self.slice(HEADER_LEN, self.n + 1) }
+
+    pub fn edges(&self) -> &[u32] { self.slice(HEADER_LEN + 4 * (self.n + 1), self.m) }
+
+    pub fn weights(&self) -> Option<Weights<'_>> {
+        let at = HEADER_LEN + 4 * (self.n + 1) + 4 * self.m;
+        match (self.weighted, self.float_weights) {
+            (false, _) => None,
+            (true, false) => Some(Weights::Int(self.slice(at, self.m))),
+            (true, true) => Some(Weights::Float(self.slice(at, self.m))),
+        }
+    }
+
+    /// The targets of `v`'s edges, borrowed from the mapping.
+    pub fn neighbors(&self, v: usize) -> &[u32] {
+        let o = self.offsets();
+        &self.edges()[o[v] as usize..o[v + 1] as usize]
+    }
+}
+
+/// Writes a graph in the binary format; `offsets` has n + 1 entries. The
+/// weights are stored as u32 if they all are integers that fit, and as f32
+/// otherwise.
+pub fn write_binary_graph<P: AsRef<Path>>(
+    path: P,
+    offsets: &[u32],
+    edges: &[u32],
+    weights: Option<&[f64]>,
+) -> io::Result<()> {
+    let (n, m) = (offsets.len() - 1, edges.len());
+    debug_assert_eq!(offsets[n] as usize, m);
+    let int = weights.is_some_and(|ws| {
+        ws.par_iter().all(|&w| w.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(&w))
+    });
+    let flags = match weights {
+        None => 0,
+        Some(_) if int => WEIGHTED,
+        Some(_) => WEIGHTED | FLOAT_WEIGHTS,
+    };
+    let mut f = BufWriter::new(File::create(path)?);
+    f.write_all(MAGIC)?;
+    f.write_all(&VERSION.to_le_bytes())?;
+    f.write_all(&flags.to_le_bytes())?;
+    f.write_all(&(n as u64).to_le_bytes())?;
+    f.write_all(&(m as u64).to_le_bytes())?;
+    offsets.iter().try_for_each(|x| f.write_all(&x.to_le_bytes()))?;
+    edges.iter().try_for_each(|x| f.write_all(&x.to_le_bytes()))?;
+    weights.unwrap_or(&[]).iter().try_for_each(|&x| {
+        if int { f.write_all(&(x as u32).to_le_bytes()) } else { f.write_all(&(x as f32).to_le_bytes()) }
+    })?;
+    f.flush()
+}
+
+/// Converts a PBBS `AdjacencyGraph` or `WeightedAdjacencyGraph` text file
+/// to the binary format.
+pub fn convert_text_graph<P: AsRef<Path>, Q: AsRef<Path>>(input: P, output: Q) -> io::Result<()> {
+    let text = fs::read_to_string(input)?;
+    let mut parts = text.splitn(4, '\n');
+    let weighted = match parts.next().map(str::trim) {
+        Some("AdjacencyGraph") => false,
+        Some("WeightedAdjacencyGraph") => true,
+        h => return Err(invalid(format!("expected an adjacency graph, got {h:?}"))),
+    };
+    let mut num = |what: &str| parts.next()
+        .and_then(|l| l.trim().parse::<usize>().ok())
+        .ok_or_else(|| invalid(format!("cannot read {what}")));
+    let (n, m) = (num("n")?, num("m")?);
+    let nums: Vec<u64> = parts.next().unwrap_or("")
+        .par_split_ascii_whitespace()
+        .map(|s| s.parse().map_err(|_| invalid(format!("'{s}' is not an integer"))))
+        .collect::<io::Result<_>>()?;
+    let expected = n + m * if weighted { 2 } else { 1 };
+    if nums.len() != expected {
+        return Err(invalid(format!("expected {expected} integers after the header, got {}", nums.len())));
+    }
+    let to_u32 = |s: &[u64]| s.par_iter()
+        .map(|&x| u32::try_from(x).map_err(|_| invalid(format!("{x} does not fit in 32 bits"))))
+        .collect::<io::Result<Vec<u32>>>();
+
+    let mut offsets = to_u32(&nums[..n])?;
+    offsets.push(u32::try_from(m).map_err(|_| invalid(format!("m={m} does not fit in 32 bits")))?);
+    let edges = to_u32(&nums[n..n + m])?;
+    let weights: Option<Vec<f64>> = weighted.then(|| nums[n + m..].par_iter().map(|&w| w as f64).collect());
+    write_binary_graph(output, &offsets, &edges, weights.as_deref())
+}
+
+/// Borrows the offsets and edges of a mapped graph, without copying them.
+impl From<Arc<MappedGraph>> for Graph {
+    fn from(g: Arc<MappedGraph>) -> Self {
+        Graph {
+            offsets: GraphBuf::mapped(&g, g.offsets()),
+            edges: GraphBuf::mapped(&g, g.edges()),
+            degrees: vec![],
+            n: g.n,
+            m: g.m,
+        }
+    }
+}
@@ -0,0 +1,234 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Readers for graphs distributed in other formats than PBBS': SNAP edge
+//! lists, Matrix Market files (e.g. from SuiteSparse) and DIMACS shortest
+//! path graphs.
+
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+use clap::ValueEnum;
+use rayon::prelude::*;
+
+use crate::{DefInt, DefFloat};
+use super::graph::{Edge, EdgeArray, Graph, WghEdge, WghEdgeArray};
+use super::graph_bin::is_binary;
+
+
+/// The formats [`import`] reads.
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum ImportFormat {
+    /// SNAP edge lists: `u v` per line, 0-based, `#` comments
+    Snap,
+    /// Matrix Market coordinate files: 1-based `i j [value]` entries after
+    /// a size line, `%` comments
+    #[clap(name = "mtx")]
+    MatrixMarket,
+    /// DIMACS shortest path graphs: `p sp n m`, then 1-based `a u v w`
+    /// arcs, `c` comments
+    #[clap(name = "dimacs")]
+    Dimacs,
+}
+
+impl ImportFormat {
+    /// The format files with this extension are usually in, or `None` if
+    /// the file starts with a PBBS header or the binary graph magic (a PBBS
+    /// graph saved as `.txt` is not a SNAP edge list).
+    pub fn guess<P: AsRef<Path>>(path: P) -> Option<Self> {
+        if has_pbbs_header(&path) { return None; }
+        match path.as_ref().extension()?.to_str()? {
+            "txt" => Some(ImportFormat::Snap),
+            "mtx" => Some(ImportFormat::MatrixMarket),
+            "gr" => Some(ImportFormat::Dimacs),
+            _ => None,
+        }
+    }
+}
+
+/// The first line of the PBBS graph formats.
+const PBBS_HEADERS: [&str; 4] =
+    ["AdjacencyGraph", "WeightedAdjacencyGraph", "EdgeArray", "WeightedEdgeArray"];
+
+/// Whether `path` starts with one of [`PBBS_HEADERS`] or the binary graph
+/// magic; only its first line is read.
+fn has_pbbs_header<P: AsRef<Path>>(path: P) -> bool {
+    if is_binary(&path) { return true; }
+    let mut line = Vec::new();
+    let read = File::open(path)
+        .and_then(|f| BufReader::new(f.take(64)).read_until(b'\n', &mut line));
+    read.is_ok() && PBBS_HEADERS.contains(&String::from_utf8_lossy(&line).trim_end())
+}
+
+/// An imported graph: `n` vertices and the edges sorted by source, then
+/// target, without self loops or duplicates.
+pub struct Imported {
+    pub n: usize,
+    pub edges: Vec<(DefInt, DefInt)>,
+    /// the weight of every edge, if the file had weights
+    pub weights: Option<Vec<f64>>,
+}
+
+fn invalid(line: usize, msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {msg}", line + 1))
+}
+
+/// A parsed entry: source, target and weight.
+type Entry = (u64, u64, Option<f64>);
+
+/// Parses the entry `l`, `u v [w]` after skipping `skip` tokens, with ids
+/// counted from `base`.
+fn entry(i: usize, l: &str, skip: usize, base: u64) -> io::Result<Entry> {
+    let mut t = l.split_ascii_whitespace().skip(skip);
+    let mut id = || t.next()
+        .and_then(|s| s.parse::<u64>().ok())
+        .filter(|&x| x >= base && x - base <= DefInt::MAX as u64)
+        .map(|x| x - base)
+        .ok_or_else(|| invalid(i, format!("expected two vertex ids from {base} in '{l}'")));
+    let (u, v) = (id()?, id()?);
+    let w = match t.next() {
+        Some(s) => Some(s.parse().map_err(|_| invalid(i, format!("'{s}' is not a weight")))?),
+        None => None,
+    };
+    Ok((u, v, w))
+}
+
+/// Reads `path` in `format`, adding the reverse of every edge if
+/// `symmetric` (Matrix Market files marked symmetric always are). Self
+/// loops are dropped, and of duplicate edges the lightest is kept.
+pub fn import<P: AsRef<Path>>(path: P, format: ImportFormat, symmetric: bool) -> io::Result<Imported> {
+    let text = fs::read_to_string(path)?;
+    let lines: Vec<&str> = text.lines().collect();
+    let (comment, skip, base) = match format {
+        ImportFormat::Snap => ('#', 0, 0),
+        ImportFormat::MatrixMarket => ('%', 0, 1),
+        ImportFormat::Dimacs => ('c', 1, 1),
+    };
+    let body = |l: &&str| !l.trim().is_empty() && !l.trim_start().starts_with(comment);
+
+    // the header: the number of vertices the file declares, if any, whether
+    // it is symmetric and the index of the first line with an edge
+    let (declared, symmetric, first) = match format {
+        ImportFormat::Snap => (0, symmetric, 0),
+        ImportFormat::MatrixMarket => {
+            let banner: Vec<String> = lines.first().unwrap_or(&"")
+                .split_ascii_whitespace().map(str::to_lowercase).collect();
+            if banner.len() != 5 || banner[0] != "%%matrixmarket" || banner[1] != "matrix" {
+                return Err(invalid(0, "expected a %%MatrixMarket matrix banner".into()));
+            }
+            if banner[2] != "coordinate" || banner[3] == "complex" {
+                return Err(invalid(0, format!("{} {} matrices are not graphs", banner[2], banner[3])));
+            }
+            let size = (1..lines.len()).find(|&i| body(&lines[i]))
+                .ok_or_else(|| invalid(lines.len(), "missing the size line".into()))?;
+            let dims: Vec<usize> = lines[size].split_ascii_whitespace()
+                .map(str::parse).collect::<Result<_, _>>()
+                .map_err(|_| invalid(size, format!("expected rows columns entries, got '{}'", lines[size])))?;
+            let n = dims.iter().take(2).copied().max().unwrap_or(0);
+            // skew-symmetric entries mirror with negated values, and
+            // hermitian ones with conjugated (complex) values
+            let mirrored = match banner[4].as_str() {
+                "general" => false,
+                "symmetric" => true,
+                s => return Err(invalid(0, format!("{s} matrices are not supported"))),
+            };
+            (n, symmetric || mirrored, size + 1)
+        },
+        ImportFormat::Dimacs => {
+            let p = lines.iter().position(|l| l.starts_with('p'))
+                .ok_or_else(|| invalid(lines.len(), "missing the 'p sp n m' line".into()))?;
+            let n = lines[p].split_ascii_whitespace().nth(2).and_then(|s| s.parse().ok())
+                .ok_or_else(|| invalid(p, format!("expected 'p sp n m', got '{}'", lines[p])))?;
+            (n, symmetric, 0)
+        },
+    };
+
+    let entries: Vec<Entry> = lines[first..].par_iter().enumerate()
+        .filter(|(_, l)| body(l) && !(format == ImportFormat::Dimacs && l.starts_with('p')))
+        .map(|(i, l)| {
+            if format == ImportFormat::Dimacs && !l.starts_with('a') {
+                return Err(invalid(first + i, format!("expected an arc, got '{l}'")));
+            }
+            entry(first + i, l, skip, base)
+        })
+        .collect::<io::Result<_>>()?;
+
+    let max = entries.par_iter().map(|e| e.0.max(e.1) as usize + 1).max().unwrap_or(0);
+    let n = declared.max(max);
+    let weighted = entries.par_iter().any(|e| e.2.is_some());
+    let mut es: Vec<(DefInt, DefInt, f64)> = entries.into_par_iter()
+        .map(|(u, v, w)| (u as DefInt, v as DefInt, w.unwrap_or(1.0)))
+        .collect();
+    if symmetric {
+        let rev: Vec<_> = es.par_iter().map(|&(u, v, w)| (v, u, w)).collect();
+        es.extend(rev);
+    }
+    es.retain(|e| e.0 != e.1);
+    es.par_sort_unstable_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)).then(a.2.total_cmp(&b.2)));
+    es.dedup_by_key(|e| (e.0, e.1));
+
+    Ok(Imported {
+        n,
+        weights: weighted.then(|| es.par_iter().map(|e| e.2).collect()),
+        edges: es.into_par_iter().map(|e| (e.0, e.1)).collect(),
+    })
+}
+
+impl Imported {
+    pub fn num_edges(&self) -> usize { self.edges.len() }
+
+    /// The offset of every vertex's first edge, and m at the end.
+    pub fn offsets(&self) -> Vec<usize> {
+        (0..=self.n).into_par_iter()
+            .map(|i| self.edges.partition_point(|e| (e.0 as usize) < i))
+            .collect()
+    }
+
+    pub fn to_graph(&self) -> Graph {
+        let offsets: Vec<DefInt> = self.offsets().into_par_iter().map(|o| o as DefInt).collect();
+        let edges: Vec<DefInt> = self.edges.par_iter().map(|e| e.1).collect();
+        Graph::new(&offsets, &edges, self.n)
+    }
+
+    pub fn to_edge_array(&self) -> EdgeArray {
+        let es = self.edges.par_iter().map(|&(u, v)| Edge::new(u, v)).collect();
+        EdgeArray::new(es, self.n, self.n)
+    }
+
+    /// The edges with their weights, or weight 1 if the file had none.
+    pub fn to_wgh_edge_array(&self) -> WghEdgeArray {
+        let es = self.edges.par_iter().enumerate()
+            .map(|(i, &(u, v))| WghEdge::new(u, v, self.weight(i) as DefFloat))
+            .collect();
+        WghEdgeArray::new(es, self.n)
+    }
+
+    /// The weight of the `i`th edge, 1 if the file had none.
+    pub fn weight(&self, i: usize) -> f64 {
+        self.weights.as_ref().map_or(1.0, |w| w[i])
+    }
+}
@@ -25,57 +25,111 @@ use std::cmp::max;
 // SOFTWARE.
 // ============================================================================
 
-use std::fs::File;
-use std::io::{prelude::*, BufReader};
+use std::sync::Arc;
 
 use rayon::prelude::*;
 
 use parlay::verbose_println;
-use crate::DefInt;
-use super::io::{read_file_to_vec, read_big_file_to_vec};
+use crate::{DefFloat, DefInt};
+use super::io::{
+    parse_line, FromWords, parse_words, read_file_to_vec, read_big_file_to_vec, read_with_header,
+    split_records, BadLines, InputError
+};
 use super::graph::*;
+use super::graph_bin::{is_binary, MappedGraph};
 
 const ADJ_GRAPH_HEADER: &str = "AdjacencyGraph";
+const WGH_ADJ_GRAPH_HEADER: &str = "WeightedAdjacencyGraph";
+
+/// Reads an `AdjacencyGraph`, or a binary graph (see [`super::graph_bin`]).
+pub fn read_graph_from_file(fname: &str, bad: BadLines) -> Result<Graph, InputError> {
+    if is_binary(fname) {
+        verbose_println!("mapping binary graph...");
+        let g = MappedGraph::open(fname).map_err(|e| InputError::io(fname, e))?;
+        return Ok(Arc::new(g).into());
+    }
 
-pub fn read_graph_from_file(fname: &str) -> Graph {
-    let file = File::open(&fname).unwrap();
-    let reader = BufReader::new(file);
-    let mut lines = reader.lines();
+    let (n, m, nums) = read_adjacency::<DefInt>(fname, ADJ_GRAPH_HEADER, bad)?;
+    if nums.len() != n + m {
+        return Err(InputError::new(fname, None, format!(
+            "expected {} offsets and edges for n={n} and m={m}, got {}", n + m, nums.len()
+        )));
+    }
+    let g = into_graph(fname, n, m, nums)?;
+    verbose_println!("graph generated.");
+    Ok(g)
+}
 
-    verbose_println!("reading file header...");
-    assert_eq!(lines.next().unwrap().unwrap(), ADJ_GRAPH_HEADER);
-    let n = lines.next().unwrap().unwrap().parse().unwrap();
-    let m = lines.next().unwrap().unwrap().parse().unwrap();
-
-    verbose_println!("making the graph (n={n}, m={m})...");
-    let mut g = Graph {
-        offsets: Vec::with_capacity(n+1),
-        edges: Vec::with_capacity(m),
-        degrees: vec![],
-        n,
-        m,
-    };
-    unsafe { g.offsets.set_len(n+1); g.edges.set_len(m); }
-
-    verbose_println!("reading offsets...");
-    for i in 0..n {
-        let tt = lines.next().unwrap().unwrap();
-        let ttt = tt.parse();
-        if ttt.is_err() { println!("{i}, {tt}") }
-        g.offsets[i] = ttt.unwrap();
+/// Reads a `WeightedAdjacencyGraph`, or a binary graph with weights: the
+/// graph and the weight of every edge.
+pub fn read_wgh_graph_from_file(
+    fname: &str,
+    bad: BadLines
+) -> Result<(Graph, Vec<DefFloat>), InputError> {
+    if is_binary(fname) {
+        let g = MappedGraph::open(fname).map_err(|e| InputError::io(fname, e))?;
+        let w = g.weights()
+            .ok_or_else(|| InputError::new(fname, None, "the binary graph has no weights"))?;
+        let weights = (0..g.m).into_par_iter().map(|i| w.get(i) as DefFloat).collect();
+        return Ok((Arc::new(g).into(), weights));
     }
-    g.offsets[n] = m as DefInt;
 
-    verbose_println!("reading edges...");
-    for i in 0..m {
-        g.edges[i] = lines.next().unwrap().unwrap().parse().unwrap();
+    let (n, m, mut nums) = read_adjacency::<i64>(fname, WGH_ADJ_GRAPH_HEADER, bad)?;
+    if nums.len() != n + 2 * m {
+        return Err(InputError::new(fname, None, format!(
+            "expected {} offsets, edges and weights for n={n} and m={m}, got {}", n + 2 * m, nums.len()
+        )));
     }
+    let weights = nums.split_off(n + m).into_par_iter().map(|w| w as DefFloat).collect();
+    let nums = nums.into_par_iter()
+        .map(|x| DefInt::try_from(x).map_err(|_| InputError::new(fname, None, format!(
+            "{x} is not an offset or a vertex"
+        ))))
+        .collect::<Result<_, _>>()?;
+    Ok((into_graph(fname, n, m, nums)?, weights))
+}
 
-    verbose_println!("graph generated.");
-    g
+/// The n and m of an adjacency graph file with `header`, and the numbers
+/// after them.
+fn read_adjacency<T: FromWords + Send>(
+    fname: &str,
+    header: &str,
+    bad: BadLines
+) -> Result<(usize, usize, Vec<T>), InputError> {
+    verbose_println!("reading file header...");
+    let s = read_with_header(fname, Some(header))?;
+    let (head, body, skip) = split_records(&s, 3);
+    let head: Vec<&[u8]> = head.split(|&c| c == b'\n')
+        .filter(|l| !l.trim_ascii().is_empty())
+        .collect();
+    let size = |i: usize| head.get(i)
+        .and_then(|l| parse_line::<usize>(l))
+        .ok_or_else(|| InputError::new(fname, Some(i + 1), "expected the number of vertices and of edges"));
+    let (n, m) = (size(1)?, size(2)?);
+
+    verbose_println!("reading offsets and edges (n={n}, m={m})...");
+    Ok((n, m, parse_words(fname, body, skip, bad)?))
+}
+
+/// The graph of the `n` offsets and `m` edges in `nums`, checked.
+fn into_graph(fname: &str, n: usize, m: usize, mut nums: Vec<DefInt>) -> Result<Graph, InputError> {
+    let edges = nums.split_off(n);
+    let mut offsets = nums;
+    offsets.push(m as DefInt);
+    if let Some(i) = (0..n).into_par_iter().find_first(|&i| offsets[i] > offsets[i + 1]) {
+        return Err(InputError::new(fname, None, format!(
+            "the offset of vertex {i} is {}, past the next one or m", offsets[i]
+        )));
+    }
+    if let Some(i) = edges.par_iter().position_first(|&v| v as usize >= n) {
+        return Err(InputError::new(fname, None, format!(
+            "edge {i} goes to vertex {}, but n={n}", edges[i]
+        )));
+    }
+    Ok(Graph { offsets: offsets.into(), edges: edges.into(), degrees: vec![], n, m })
 }
 
-pub fn read_edge_array_from_file(fname: &str) -> EdgeArray {
+pub fn read_edge_array_from_file(fname: &str, bad: BadLines) -> Result<EdgeArray, InputError> {
     let mut ea = EdgeArray {
         es: vec![],
         num_rows: 0,
@@ -84,11 +138,7 @@ pub fn read_edge_array_from_file(fname: &str) -> EdgeArray {
     };
 
     verbose_println!("reading file...");
-    read_big_file_to_vec(
-        fname,
-        Some { 0: |w: &[&str]| {debug_assert_eq!(w[0], "EdgeArray")} },
-        &mut ea.es
-    );
+    read_big_file_to_vec(fname, Some("EdgeArray"), &mut ea.es, bad)?;
     let n = ea.es.len();
 
     verbose_println!("finding_max...");
@@ -106,15 +156,11 @@ pub fn read_edge_array_from_file(fname: &str) -> EdgeArray {
     ea.num_cols = rm;
 
     verbose_println!("done.");
-    ea
+    Ok(ea)
 }
 
-pub fn read_wgh_edge_array_from_file(fname: &str) -> WghEdgeArray {
-    let es: Vec<WghEdge> = read_file_to_vec(
-        fname,
-        Some { 0: |w: &[&str]| {
-            debug_assert_eq!(w[0], "WeightedEdgeArray")
-        }} );
+pub fn read_wgh_edge_array_from_file(fname: &str, bad: BadLines) -> Result<WghEdgeArray, InputError> {
+    let es: Vec<WghEdge> = read_file_to_vec(fname, Some("WeightedEdgeArray"), bad)?;
 
     let m = es
         .par_iter()
@@ -126,5 +172,5 @@ pub fn read_wgh_edge_array_from_file(fname: &str) -> WghEdgeArray {
 
     println!("extracted graph n={} m={}", max(m.u, m.v) as usize + 1, es.len());
 
-    WghEdgeArray::new(es, max(m.u, m.v) as usize + 1)
+    Ok(WghEdgeArray::new(es, max(m.u, m.v) as usize + 1))
 }
@@ -0,0 +1,235 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Conversions between the graph representations, and the clean-ups the
+//! graph benchmarks expect of their inputs (graphUtils in PBBS).
+
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+
+use clap::ValueEnum;
+use parlay::internal::integer_sort::integer_sort;
+use parlay::internal::sequence_ops::scan_inplace;
+use parlay::random::Random;
+use parlay::utilities::log2_up;
+use rayon::prelude::*;
+
+use crate::{DefFloat, DefInt};
+use super::graph::{Edge, EdgeArray, Graph, WghEdge, WghEdgeArray};
+
+
+/// Sorts `es`, on vertices below `n`, by source with an integer sort and
+/// then every run of edges with the same source by target.
+fn sort_edges(es: &[Edge], n: usize) -> Vec<Edge> {
+    let mut sorted = vec![];
+    integer_sort(es, &|e: Edge| e.u, log2_up(n.max(2)), &mut sorted);
+    sorted
+        .par_chunk_by_mut(|a, b| a.u == b.u)
+        .for_each(|s| s.sort_unstable_by_key(|e| e.v));
+    sorted
+}
+
+/// The adjacency graph of `ea`, with every neighbor list sorted.
+pub fn edges_to_graph(ea: &EdgeArray) -> Graph {
+    let n = ea.num_rows.max(ea.num_cols);
+    let es = sort_edges(&ea.es, n);
+    let offsets: Vec<DefInt> = (0..=n).into_par_iter()
+        .map(|i| es.partition_point(|e| (e.u as usize) < i) as DefInt)
+        .collect();
+    let edges: Vec<DefInt> = es.par_iter().map(|e| e.v).collect();
+    Graph { offsets: offsets.into(), edges: edges.into(), degrees: vec![], n, m: es.len() }
+}
+
+/// The edges of `g`, by source.
+pub fn graph_to_edges(g: &Graph) -> EdgeArray {
+    let es = (0..g.n).into_par_iter()
+        .flat_map_iter(|u| g.index(u).neighbors.iter().map(move |&v| Edge::new(u as DefInt, v)))
+        .collect();
+    EdgeArray::new(es, g.n, g.n)
+}
+
+/// Sorts weighted edges like [`sort_edges`], parallel edges by weight.
+fn sort_wgh_edges(es: &[WghEdge], n: usize) -> Vec<WghEdge> {
+    let mut sorted = vec![];
+    integer_sort(es, &|e: WghEdge| e.u, log2_up(n.max(2)), &mut sorted);
+    sorted
+        .par_chunk_by_mut(|a, b| a.u == b.u)
+        .for_each(|s| s.sort_unstable_by(|a, b| a.v.cmp(&b.v).then(a.w.total_cmp(&b.w))));
+    sorted
+}
+
+/// The adjacency graph of `ea`, as [`edges_to_graph`], and the weight of
+/// every edge of it.
+pub fn wgh_edges_to_graph(ea: &WghEdgeArray) -> (Graph, Vec<DefFloat>) {
+    let es = sort_wgh_edges(&ea.es, ea.n);
+    let offsets: Vec<DefInt> = (0..=ea.n).into_par_iter()
+        .map(|i| es.partition_point(|e| (e.u as usize) < i) as DefInt)
+        .collect();
+    let edges: Vec<DefInt> = es.par_iter().map(|e| e.v).collect();
+    let weights = es.par_iter().map(|e| e.w).collect();
+    let g = Graph { offsets: offsets.into(), edges: edges.into(), degrees: vec![], n: ea.n, m: es.len() };
+    (g, weights)
+}
+
+/// The edges of `g` with their `weights`, by source.
+pub fn graph_to_wgh_edges(g: &Graph, weights: &[DefFloat]) -> WghEdgeArray {
+    let es = graph_to_edges(g).es.par_iter().zip(weights)
+        .map(|(e, &w)| WghEdge::new(e.u, e.v, w))
+        .collect();
+    WghEdgeArray::new(es, g.n)
+}
+
+/// Keeps the `j`th neighbor of `u` if `keep(u, neighbors of u, j)`.
+fn filter<F>(g: &Graph, keep: F) -> Graph
+where
+    F: Fn(usize, &[DefInt], usize) -> bool + Sync,
+{
+    let keep = &keep;
+    let mut offsets: Vec<DefInt> = (0..g.n).into_par_iter()
+        .map(|u| {
+            let s = g.index(u).neighbors;
+            (0..s.len()).filter(|&j| keep(u, s, j)).count() as DefInt
+        })
+        .collect();
+    offsets.push(0);
+    let m = scan_inplace(&mut offsets, false, |a, b| a + b) as usize;
+    let edges: Vec<DefInt> = (0..g.n).into_par_iter()
+        .flat_map_iter(|u| {
+            let s = g.index(u).neighbors;
+            (0..s.len()).filter(move |&j| keep(u, s, j)).map(move |j| s[j])
+        })
+        .collect();
+    Graph { offsets: offsets.into(), edges: edges.into(), degrees: vec![], n: g.n, m }
+}
+
+/// `g` with sorted neighbor lists and without duplicate edges.
+pub fn dedup(g: &Graph) -> Graph {
+    let g = edges_to_graph(&graph_to_edges(g));
+    filter(&g, |_, s, j| j == 0 || s[j] != s[j - 1])
+}
+
+/// `g` with the reverse of every edge added, without duplicate edges.
+pub fn symmetrize(g: &Graph) -> Graph {
+    let mut ea = graph_to_edges(g);
+    let rev: Vec<Edge> = ea.es.par_iter().map(|e| Edge::new(e.v, e.u)).collect();
+    ea.es.extend(rev);
+    let g = edges_to_graph(&ea);
+    filter(&g, |_, s, j| j == 0 || s[j] != s[j - 1])
+}
+
+pub fn remove_self_loops(g: &Graph) -> Graph {
+    filter(g, |u, s, j| s[j] as usize != u)
+}
+
+/// `es` sorted, keeping the lightest of parallel edges.
+fn dedup_wgh_edges(es: &[WghEdge], n: usize) -> WghEdgeArray {
+    let es = sort_wgh_edges(es, n);
+    let es = (0..es.len()).into_par_iter()
+        .filter(|&i| i == 0 || (es[i].u, es[i].v) != (es[i - 1].u, es[i - 1].v))
+        .map(|i| es[i])
+        .collect();
+    WghEdgeArray::new(es, n)
+}
+
+/// [`dedup`] of a weighted graph; the lightest of duplicate edges is kept.
+pub fn wgh_dedup(ea: &WghEdgeArray) -> WghEdgeArray {
+    dedup_wgh_edges(&ea.es, ea.n)
+}
+
+/// [`symmetrize`] of a weighted graph; reverse edges have the same weight,
+/// and the lightest of duplicate edges is kept.
+pub fn wgh_symmetrize(ea: &WghEdgeArray) -> WghEdgeArray {
+    let es: Vec<WghEdge> = ea.es.par_iter()
+        .flat_map_iter(|&e| [e, WghEdge::new(e.v, e.u, e.w)])
+        .collect();
+    dedup_wgh_edges(&es, ea.n)
+}
+
+pub fn wgh_remove_self_loops(ea: &WghEdgeArray) -> WghEdgeArray {
+    WghEdgeArray::new(ea.es.par_iter().filter(|e| e.u != e.v).copied().collect(), ea.n)
+}
+
+/// The vertex orders of [`relabeling`].
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum Relabel {
+    /// a random permutation
+    Random,
+    /// by decreasing degree
+    Degree,
+    /// in breadth-first order from vertex 0, then from the first vertex
+    /// not reached yet, and so on
+    Bfs,
+}
+
+/// The new label of every vertex of `g` when put in `order`; `seed` is
+/// only used by [`Relabel::Random`].
+pub fn relabeling(g: &Graph, order: Relabel, seed: u64) -> Vec<DefInt> {
+    let mut ids: Vec<DefInt> = (0..g.n as DefInt).collect();
+    match order {
+        Relabel::Random => {
+            let r = Random::new(seed);
+            ids.par_sort_unstable_by_key(|&v| (r.ith_rand(v as u64), v));
+        },
+        Relabel::Degree => ids.par_sort_by_key(|&v| Reverse(g.index(v as usize).degree)),
+        Relabel::Bfs => {
+            let mut seen = vec![false; g.n];
+            let mut queue = VecDeque::new();
+            ids.clear();
+            for s in 0..g.n {
+                if seen[s] { continue; }
+                seen[s] = true;
+                queue.push_back(s);
+                while let Some(u) = queue.pop_front() {
+                    ids.push(u as DefInt);
+                    for &v in g.index(u).neighbors {
+                        if !seen[v as usize] {
+                            seen[v as usize] = true;
+                            queue.push_back(v as usize);
+                        }
+                    }
+                }
+            }
+        },
+    }
+    let mut label = vec![0; g.n];
+    for (i, &v) in ids.iter().enumerate() { label[v as usize] = i as DefInt; }
+    label
+}
+
+/// `g` with every vertex `v` renamed `label[v]`.
+pub fn relabel(g: &Graph, label: &[DefInt]) -> Graph {
+    let mut ea = graph_to_edges(g);
+    ea.es.par_iter_mut().for_each(|e| *e = Edge::new(label[e.u as usize], label[e.v as usize]));
+    edges_to_graph(&ea)
+}
+
+/// [`relabel`] of a weighted graph, sorted by source.
+pub fn wgh_relabel(ea: &WghEdgeArray, label: &[DefInt]) -> WghEdgeArray {
+    let es: Vec<WghEdge> = ea.es.par_iter()
+        .map(|e| WghEdge::new(label[e.u as usize], label[e.v as usize], e.w))
+        .collect();
+    WghEdgeArray::new(sort_wgh_edges(&es, ea.n), ea.n)
+}
@@ -25,11 +25,278 @@ use std::{fs, io, io::prelude::*};
 // SOFTWARE.
 // ============================================================================
 
+use std::fmt::{self, Display};
+use std::path::{Path, PathBuf};
+
 use rayon::prelude::*;
 
+/// Why an input file could not be read.
+#[derive(Debug)]
+pub struct InputError {
+    pub file: PathBuf,
+    /// the line at fault, counted from 1, if the error is about one line
+    pub line: Option<usize>,
+    pub reason: String,
+}
+
+impl InputError {
+    pub fn new<P: AsRef<Path>>(file: P, line: Option<usize>, reason: impl Into<String>) -> Self {
+        Self { file: file.as_ref().to_path_buf(), line, reason: reason.into() }
+    }
+
+    pub fn io<P: AsRef<Path>>(file: P, e: io::Error) -> Self {
+        Self::new(file, None, e.to_string())
+    }
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(l) => write!(f, "{}:{l}: {}", self.file.display(), self.reason),
+            None => write!(f, "{}: {}", self.file.display(), self.reason),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// What the readers do with lines that do not parse.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum BadLines {
+    /// Skip them with a warning.
+    #[default]
+    Skip,
+    /// Fail with the first of them.
+    Reject,
+}
+
+/// Reads `fname` and, if `header` is given, checks that its first line is
+/// `header`.
+pub fn read_with_header<P: AsRef<Path>>(
+    fname: P,
+    header: Option<&str>
+) -> Result<Vec<u8>, InputError> {
+    let s = fs::read(&fname).map_err(|e| InputError::io(&fname, e))?;
+    if let Some(h) = header {
+        let first = split_lines(&s, 1).0.trim_ascii();
+        if first != h.as_bytes() {
+            let first = &first[..first.len().min(64)];
+            return Err(InputError::new(&fname, Some(1), format!(
+                "expected the header '{h}', got '{}'", String::from_utf8_lossy(first)
+            )));
+        }
+    }
+    Ok(s)
+}
+
+/// Splits `buf` after its first `k` lines.
+pub fn split_lines(buf: &[u8], k: usize) -> (&[u8], &[u8]) {
+    let mut at = 0;
+    for _ in 0..k {
+        match buf[at..].iter().position(|&c| c == b'\n') {
+            Some(p) => at += p + 1,
+            None => return (buf, &[]),
+        }
+    }
+    buf.split_at(at)
+}
+
+/// Splits `buf` after its first `k` non-blank lines, the ones
+/// [`parse_words`] reads, and counts all the lines before the split.
+pub fn split_records(buf: &[u8], k: usize) -> (&[u8], &[u8], usize) {
+    let chunks = chunks(buf);
+    let counts: Vec<usize> = chunks.par_iter().map(|c| lines(c).count()).collect();
+    let (mut at, mut k) = (0, k);
+    for (c, &count) in chunks.iter().zip(&counts) {
+        if k > count {
+            at += c.len();
+            k -= count;
+            continue;
+        }
+        for l in c.split(|&c| c == b'\n') {
+            if k == 0 { break; }
+            if !l.iter().all(u8::is_ascii_whitespace) { k -= 1; }
+            at += l.len() + 1;
+        }
+        break;
+    }
+    let (head, body) = buf.split_at(at.min(buf.len()));
+    (head, body, head.par_iter().filter(|&&c| c == b'\n').count())
+}
+
+/// A value written as the whitespace-separated words of one line, parsed
+/// straight from the bytes of a file (see [`parse_words`]).
+pub trait FromWords: Sized {
+    /// Parses `Self` from the next words of `w`.
+    fn from_words<'a, I: Iterator<Item = &'a [u8]>>(w: &mut I) -> Option<Self>;
+}
+
+fn parse_uint(w: &[u8]) -> Option<u64> {
+    let w = w.strip_prefix(b"+").unwrap_or(w);
+    if w.is_empty() { return None; }
+    w.iter().try_fold(0u64, |x, &c| {
+        if !c.is_ascii_digit() { return None; }
+        x.checked_mul(10)?.checked_add((c - b'0') as u64)
+    })
+}
+
+fn parse_int(w: &[u8]) -> Option<i64> {
+    match w.strip_prefix(b"-") {
+        Some(w) if !w.starts_with(b"+") => 0i64.checked_sub_unsigned(parse_uint(w)?),
+        Some(_) => None,
+        None => i64::try_from(parse_uint(w)?).ok(),
+    }
+}
+
+macro_rules! int_words {
+    ($parse:ident: $($t:ty),*) => {$(
+        impl FromWords for $t {
+            fn from_words<'a, I: Iterator<Item = &'a [u8]>>(w: &mut I) -> Option<Self> {
+                $parse(w.next()?).and_then(|x| x.try_into().ok())
+            }
+        }
+    )*}
+}
+
+int_words!(parse_uint: u8, u16, u32, u64, usize);
+int_words!(parse_int: i8, i16, i32, i64, isize);
+
+macro_rules! float_words {
+    ($($t:ty),*) => {$(
+        impl FromWords for $t {
+            fn from_words<'a, I: Iterator<Item = &'a [u8]>>(w: &mut I) -> Option<Self> {
+                std::str::from_utf8(w.next()?).ok()?.parse().ok()
+            }
+        }
+    )*}
+}
+
+float_words!(f32, f64);
+
+impl<A: FromWords, B: FromWords> FromWords for (A, B) {
+    fn from_words<'a, I: Iterator<Item = &'a [u8]>>(w: &mut I) -> Option<Self> {
+        Some((A::from_words(w)?, B::from_words(w)?))
+    }
+}
+
+impl<A: FromWords, B: FromWords, C: FromWords> FromWords for (A, B, C) {
+    fn from_words<'a, I: Iterator<Item = &'a [u8]>>(w: &mut I) -> Option<Self> {
+        Some((A::from_words(w)?, B::from_words(w)?, C::from_words(w)?))
+    }
+}
+
+impl<T: FromWords + Copy + Default, const N: usize> FromWords for [T; N] {
+    fn from_words<'a, I: Iterator<Item = &'a [u8]>>(w: &mut I) -> Option<Self> {
+        let mut a = [T::default(); N];
+        for x in &mut a { *x = T::from_words(w)?; }
+        Some(a)
+    }
+}
+
+fn words(line: &[u8]) -> impl Iterator<Item = &[u8]> {
+    line.split(u8::is_ascii_whitespace).filter(|w| !w.is_empty())
+}
+
+fn lines(buf: &[u8]) -> impl Iterator<Item = &[u8]> {
+    buf.split(|&c| c == b'\n').filter(|l| !l.iter().all(u8::is_ascii_whitespace))
+}
+
+/// Parses `line` as one `T`, with no words left over.
+pub fn parse_line<T: FromWords>(line: &[u8]) -> Option<T> {
+    let mut w = words(line);
+    T::from_words(&mut w).filter(|_| w.next().is_none())
+}
+
+/// Cuts `buf` into a few pieces per worker, each ending with a line.
+fn chunks(buf: &[u8]) -> Vec<&[u8]> {
+    const MIN_CHUNK: usize = 1 << 16;
+    let k = (buf.len() / MIN_CHUNK).clamp(1, 8 * rayon::current_num_threads());
+    let mut cuts = vec![0];
+    for i in 1..k {
+        let c = (i * buf.len() / k).max(cuts[cuts.len() - 1]);
+        match buf[c..].iter().position(|&c| c == b'\n') {
+            Some(p) => cuts.push(c + p + 1),
+            None => break,
+        }
+    }
+    cuts.push(buf.len());
+    cuts.dedup();
+    cuts.windows(2).map(|w| &buf[w[0]..w[1]]).collect()
+}
+
+/// Parses every non-blank line of `buf`, the first of which is line
+/// `first + 1` of `fname`, into one `T`. The buffer is split into chunks
+/// at line breaks; one pass counts the lines of each chunk and a second
+/// parses each chunk into its part of the result. `bad` says what to do
+/// with lines that do not parse.
+pub fn parse_words<T, P>(
+    fname: P,
+    buf: &[u8],
+    first: usize,
+    bad: BadLines
+) -> Result<Vec<T>, InputError>
+where
+    T: FromWords + Send,
+    P: AsRef<Path>,
+{
+    let chunks = chunks(buf);
+    let counts: Vec<usize> = chunks.par_iter().map(|c| lines(c).count()).collect();
+    let total = counts.iter().sum();
+
+    let mut v: Vec<T> = Vec::with_capacity(total);
+    let mut rest = &mut v.spare_capacity_mut()[..total];
+    let mut slots = Vec::with_capacity(chunks.len());
+    for &c in &counts {
+        let (s, r) = std::mem::take(&mut rest).split_at_mut(c);
+        slots.push(s);
+        rest = r;
+    }
+    let parsed: Vec<(usize, usize, Option<&[u8]>)> = chunks
+        .par_iter()
+        .zip(slots)
+        .map(|(c, s)| {
+            let (mut k, mut bad, mut first_bad) = (0, 0, None);
+            for l in lines(c) {
+                match parse_line(l) {
+                    Some(x) => { s[k].write(x); k += 1; }
+                    None => { bad += 1; first_bad.get_or_insert(l); }
+                }
+            }
+            (k, bad, first_bad)
+        })
+        .collect();
+
+    // move the parsed values of each chunk over the gaps left by bad lines
+    let (mut len, mut start) = (0, 0);
+    let p = v.as_mut_ptr();
+    for (&(k, _, _), &c) in parsed.iter().zip(&counts) {
+        // SAFETY: the first k slots from start are initialized and len <= start
+        if len != start { unsafe { std::ptr::copy(p.add(start), p.add(len), k); } }
+        len += k;
+        start += c;
+    }
+    // SAFETY: the first len slots are initialized
+    unsafe { v.set_len(len); }
+
+    if let Some(l) = parsed.iter().find_map(|&(_, _, l)| l) {
+        let at = l.as_ptr() as usize - buf.as_ptr() as usize;
+        let line = first + 1 + buf[..at].iter().filter(|&&c| c == b'\n').count();
+        if bad == BadLines::Reject {
+            return Err(InputError::new(fname, Some(line), format!(
+                "cannot parse '{}'", String::from_utf8_lossy(l.trim_ascii())
+            )));
+        }
+        eprintln!(
+            "warning: {}: skipped {} unparsable line(s), the first is line {}",
+            fname.as_ref().display(), parsed.iter().map(|p| p.1).sum::<usize>(), line
+        );
+    }
+    Ok(v)
+}
+
 #[allow(dead_code)]
 #[inline(always)]
-pub(crate) fn fmt_f64(num: f64, precision: usize, exp_pad: usize) -> String {
+pub fn fmt_f64(num: f64, precision: usize, exp_pad: usize) -> String {
     let mut num = format!("{:.precision$e}", num, precision = precision);
     let exp = num.split_off(num.find('e').unwrap());
 
@@ -43,8 +310,22 @@ pub(crate) fn fmt_f64(num: f64, precision: usize, exp_pad: usize) -> String {
     num
 }
 
+/// Writes `s` in the PBBS sequence format: the `header` line (e.g.
+/// `sequenceInt`), then one element per line.
+pub fn write_sequence<T, P>(s: &[T], header: &str, fname: P) -> io::Result<()>
+where
+    T: Display + Sync,
+    P: AsRef<Path>,
+{
+    let lines: Vec<String> = s.par_iter().map(|x| format!("{x}\n")).collect();
+    let mut f = io::BufWriter::new(fs::File::create(fname)?);
+    writeln!(f, "{header}")?;
+    lines.iter().try_for_each(|l| f.write_all(l.as_bytes()))?;
+    f.flush()
+}
+
 #[allow(dead_code)]
-pub(crate) fn write_slice_to_file_seq<T, F>(s: &[T], of: F)
+pub fn write_slice_to_file_seq<T, F>(s: &[T], of: F)
 where
     T: std::string::ToString,
     F: AsRef<std::path::Path>,
@@ -60,72 +341,60 @@ where
 }
 
 #[allow(dead_code)]
-pub(crate) fn read_file_to_vec_seq<T, P>(fname: P) -> Vec<T>
+pub fn read_file_to_vec_seq<T, P>(fname: P, bad: BadLines) -> Result<Vec<T>, InputError>
 where
     T: std::str::FromStr,
-    <T as std::str::FromStr>::Err : std::fmt::Debug,
     P: AsRef<std::path::Path>
 {
-    let s = fs::read_to_string(fname)
-        .expect("cannot read input file");
-    let w: Vec<_> = s.split('\n').collect();
-    w
-        .into_iter()
-        .map(str::parse)
-        .filter(Result::is_ok)
-        .map(Result::unwrap)
-        .collect::<Vec<T>>()
+    let s = fs::read_to_string(&fname).map_err(|e| InputError::io(&fname, e))?;
+    let mut v = Vec::new();
+    for (i, l) in s.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+        match l.parse() {
+            Ok(x) => v.push(x),
+            Err(_) if bad == BadLines::Reject => return Err(InputError::new(
+                &fname, Some(i + 1), format!("cannot parse '{}'", l.trim())
+            )),
+            Err(_) => eprintln!(
+                "warning: {}:{}: skipped '{}'", fname.as_ref().display(), i + 1, l.trim()
+            ),
+        }
+    }
+    Ok(v)
 }
 
+/// Reads one element per line, after the `header` line if there is one;
+/// `bad` says what to do with lines that do not parse.
 #[allow(dead_code)]
-pub(crate) fn read_file_to_vec<T, P, F>(
+pub fn read_file_to_vec<T, P>(
     fname: P,
-    debug_assert: Option<F>
-) -> Vec<T> where
-    T: std::str::FromStr + Send,
-    <T as std::str::FromStr>::Err : std::fmt::Debug + Send,
+    header: Option<&str>,
+    bad: BadLines
+) -> Result<Vec<T>, InputError> where
+    T: FromWords + Send,
     P: AsRef<std::path::Path>,
-    F: Fn(&[&str]),
 {
-    let s = fs::read_to_string(fname)
-        .expect("cannot read input file");
-    let w: Vec<_> = s.par_split('\n').collect();
-    if debug_assert.is_some() {
-        debug_assert.unwrap()(&w);
-    }
-    w
-        .into_par_iter()
-        .map(str::parse)
-        .filter(Result::is_ok)
-        .map(Result::unwrap)
-        .collect::<Vec<T>>()
+    let s = read_with_header(&fname, header)?;
+    let skip = header.is_some() as usize;
+    parse_words(fname, split_lines(&s, skip).1, skip, bad)
 }
 
+/// [`read_file_to_vec`] into `dest`.
 #[allow(dead_code)]
-pub(crate) fn read_big_file_to_vec<T, P, F>(
+pub fn read_big_file_to_vec<T, P>(
     fname: P,
-    debug_assert: Option<F>,
-    dest: &mut Vec<T>
-) where
-    T: std::str::FromStr + Send,
-    <T as std::str::FromStr>::Err : std::fmt::Debug + Send,
+    header: Option<&str>,
+    dest: &mut Vec<T>,
+    bad: BadLines
+) -> Result<(), InputError> where
+    T: FromWords + Send,
     P: AsRef<std::path::Path>,
-    F: Fn(&[&str]),
 {
-    if debug_assert.is_some() {
-        eprintln!("debug_assert is not supported for read_big_file_to_vec");
-    }
-    *dest = fs::read_to_string(fname)
-        .expect("cannot read input file")
-        .par_split('\n')
-        .map(str::parse)
-        .filter(Result::is_ok)
-        .map(Result::unwrap)
-        .collect::<Vec<T>>();
+    *dest = read_file_to_vec(fname, header, bad)?;
+    Ok(())
 }
 
 #[allow(dead_code)]
-pub(crate) fn chars_from_file<P: AsRef<std::path::Path>>(
+pub fn chars_from_file<P: AsRef<std::path::Path>>(
     fname: P,
     null_terminate: bool
 ) -> io::Result<Vec<u8>>
@@ -138,7 +407,7 @@ pub(crate) fn chars_from_file<P: AsRef<std::path::Path>>(
 }
 
 #[allow(dead_code)]
-pub(crate) fn chars_to_file<P: AsRef<std::path::Path>>(
+pub fn chars_to_file<P: AsRef<std::path::Path>>(
     buffer: &[u8],
     fname: P
 ) -> io::Result<()>
@@ -1,4 +1,4 @@
-pub(crate) mod io;
+pub mod io;
 // ============================================================================
 // This code is part of RPB.
 // ----------------------------------------------------------------------------
@@ -25,14 +25,20 @@ pub(crate) mod io;
 // SOFTWARE.
 // ============================================================================
 
-pub(crate) mod graph;
-pub(crate) mod atomics;
-pub(crate) mod graph_io;
-pub(crate) mod spec_for;
-pub(crate) mod topology;
-pub(crate) mod geometry;
-pub(crate) mod time_loop;
-pub(crate) mod geometry_io;
-pub(crate) mod topology_from_triangles;
-pub(crate) mod traits;
+pub mod graph;
+pub mod atomics;
+pub mod graph_io;
+pub mod graph_bin;
+pub mod graph_import;
+pub mod graph_utils;
+pub mod spec_for;
+pub mod topology;
+pub mod geometry;
+pub mod time_loop;
+pub mod report;
+pub mod pool;
+pub mod perf;
+pub mod geometry_io;
+pub mod topology_from_triangles;
+pub mod traits;
 
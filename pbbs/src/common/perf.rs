@@ -0,0 +1,205 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+
+use serde::Serialize;
+
+
+/// The names of the counted events, in the order of [`PerfCounts::values`].
+pub const EVENTS: [&str; 5] = [
+    "cycles", "instructions", "cache_misses", "branch_misses", "context_switches"
+];
+
+/// What the hardware and software counters counted in one round. An event
+/// is `None` if the machine does not have it or `perf_event_paranoid` does
+/// not allow it.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct PerfCounts {
+    pub cycles: Option<u64>,
+    pub instructions: Option<u64>,
+    pub cache_misses: Option<u64>,
+    pub branch_misses: Option<u64>,
+    pub context_switches: Option<u64>,
+}
+
+impl PerfCounts {
+    pub fn values(&self) -> [Option<u64>; 5] {
+        [
+            self.cycles,
+            self.instructions,
+            self.cache_misses,
+            self.branch_misses,
+            self.context_switches,
+        ]
+    }
+
+    pub fn from_values(v: [Option<u64>; 5]) -> Self {
+        PerfCounts {
+            cycles: v[0],
+            instructions: v[1],
+            cache_misses: v[2],
+            branch_misses: v[3],
+            context_switches: v[4],
+        }
+    }
+}
+
+#[cfg(feature = "perf")]
+mod sys {
+    use std::fs;
+    use std::mem::size_of;
+    use std::os::raw::c_int;
+    use std::sync::Once;
+
+    use perf_event_open_sys::{ioctls, perf_event_open};
+    use perf_event_open_sys::bindings::*;
+
+    use super::PerfCounts;
+
+    const EVENTS: [(u32, u32); 5] = [
+        (perf_type_id_PERF_TYPE_HARDWARE, perf_hw_id_PERF_COUNT_HW_CPU_CYCLES),
+        (perf_type_id_PERF_TYPE_HARDWARE, perf_hw_id_PERF_COUNT_HW_INSTRUCTIONS),
+        (perf_type_id_PERF_TYPE_HARDWARE, perf_hw_id_PERF_COUNT_HW_CACHE_MISSES),
+        (perf_type_id_PERF_TYPE_HARDWARE, perf_hw_id_PERF_COUNT_HW_BRANCH_MISSES),
+        (perf_type_id_PERF_TYPE_SOFTWARE, perf_sw_ids_PERF_COUNT_SW_CONTEXT_SWITCHES),
+    ];
+
+    /// One counter per event and thread of the process.
+    pub struct Counters {
+        fds: Vec<(usize, c_int)>,
+    }
+
+    fn open(event: (u32, u32), tid: i32, exclude_kernel: bool) -> c_int {
+        let mut attr = perf_event_attr {
+            size: size_of::<perf_event_attr>() as u32,
+            type_: event.0,
+            config: event.1 as u64,
+            read_format: (perf_event_read_format_PERF_FORMAT_TOTAL_TIME_ENABLED
+                | perf_event_read_format_PERF_FORMAT_TOTAL_TIME_RUNNING) as u64,
+            ..Default::default()
+        };
+        attr.set_disabled(1);
+        attr.set_inherit(1);
+        attr.set_exclude_kernel(exclude_kernel as u64);
+        attr.set_exclude_hv(1);
+        unsafe { perf_event_open(&mut attr, tid, -1, -1, PERF_FLAG_FD_CLOEXEC as _) }
+    }
+
+    fn close(fd: c_int) { unsafe { libc::close(fd); } }
+
+    impl Counters {
+        pub fn open() -> Option<Self> {
+            let tids: Vec<i32> = fs::read_dir("/proc/self/task").ok()?
+                .filter_map(|e| e.ok()?.file_name().to_str()?.parse().ok())
+                .collect();
+            let mut fds = vec![];
+            for (e, &event) in EVENTS.iter().enumerate() {
+                let mut opened = vec![];
+                for &tid in &tids {
+                    // user space only if kernel counting is not allowed
+                    let fd = match open(event, tid, false) {
+                        fd if fd >= 0 => fd,
+                        _ => open(event, tid, true),
+                    };
+                    if fd < 0 { break; }
+                    opened.push((e, fd));
+                }
+                // an event missing on some thread would undercount
+                if opened.len() == tids.len() { fds.extend(opened); }
+                else { opened.into_iter().for_each(|(_, fd)| close(fd)); }
+            }
+            if fds.is_empty() {
+                static WARN: Once = Once::new();
+                WARN.call_once(|| eprintln!(
+                    "perf: no counters available (perf_event_paranoid is {})",
+                    fs::read_to_string("/proc/sys/kernel/perf_event_paranoid")
+                        .map_or("unknown".to_string(), |s| s.trim().to_string())
+                ));
+                return None;
+            }
+            Some(Counters { fds })
+        }
+
+        pub fn start(&self) {
+            for &(_, fd) in &self.fds {
+                unsafe {
+                    ioctls::RESET(fd, 0);
+                    ioctls::ENABLE(fd, 0);
+                }
+            }
+        }
+
+        pub fn stop(&self) -> PerfCounts {
+            for &(_, fd) in &self.fds { unsafe { ioctls::DISABLE(fd, 0); } }
+            let mut v = [None; 5];
+            for &(e, fd) in &self.fds {
+                // value, time enabled, time running
+                let mut buf = [0u64; 3];
+                let n = unsafe { libc::read(fd, buf.as_mut_ptr() as *mut _, 24) };
+                if n != 24 { continue; }
+                // scale up if the kernel had to multiplex the counters
+                let count = if buf[2] > 0 && buf[2] < buf[1] {
+                    (buf[0] as f64 * buf[1] as f64 / buf[2] as f64) as u64
+                } else { buf[0] };
+                v[e] = Some(v[e].unwrap_or(0) + count);
+            }
+            PerfCounts::from_values(v)
+        }
+    }
+
+    impl Drop for Counters {
+        fn drop(&mut self) { self.fds.iter().for_each(|&(_, fd)| close(fd)); }
+    }
+}
+
+#[cfg(not(feature = "perf"))]
+mod sys {
+    use super::PerfCounts;
+
+    pub struct Counters;
+
+    impl Counters {
+        pub fn open() -> Option<Self> { None }
+        pub fn start(&self) {}
+        pub fn stop(&self) -> PerfCounts { PerfCounts::default() }
+    }
+}
+
+/// Counters of [`EVENTS`] over every thread of the process, including the
+/// ones it starts later.
+pub struct Counters(sys::Counters);
+
+impl Counters {
+    /// `None` without the `perf` feature or if no event can be counted; the
+    /// latter also prints a warning, once.
+    pub fn open() -> Option<Self> { sys::Counters::open().map(Counters) }
+
+    /// Resets and starts counting.
+    pub fn start(&self) { self.0.start() }
+
+    /// Stops counting and returns the counts since [`Counters::start`].
+    pub fn stop(&self) -> PerfCounts { self.0.stop() }
+}
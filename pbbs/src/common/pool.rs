@@ -0,0 +1,269 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use affinity::{get_core_num, get_thread_affinity, set_thread_affinity};
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+
+
+/// A logical cpu, with the socket and the physical core it belongs to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Cpu {
+    pub id: usize,
+    pub package: usize,
+    pub core: usize,
+}
+
+/// The online cpus of the machine.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Topology {
+    pub cpus: Vec<Cpu>,
+}
+
+impl Topology {
+    /// Reads `/sys/devices/system/cpu`, or assumes one cpu per physical core
+    /// on a single socket if that is not available. Only the cpus the calling
+    /// thread may run on are kept, as pinning to the others fails.
+    pub fn detect() -> Self {
+        let mut t = Self::from_sysfs("/sys/devices/system/cpu")
+            .unwrap_or_else(|| Self::flat(get_core_num()));
+        if let Ok(allowed) = get_thread_affinity() {
+            let cpus: Vec<_> = t.cpus.iter()
+                .filter(|c| allowed.contains(&c.id))
+                .copied()
+                .collect();
+            if !cpus.is_empty() { t.cpus = cpus; }
+        }
+        t
+    }
+
+    /// Reads the topology from a sysfs cpu directory.
+    pub fn from_sysfs(root: impl AsRef<Path>) -> Option<Self> {
+        let root = root.as_ref();
+        let read = |p: &Path| fs::read_to_string(p).ok();
+        let online = parse_cpu_list(read(&root.join("online"))?.trim()).ok()?;
+        let cpus = online.into_iter().map(|id| {
+            let dir = root.join(format!("cpu{id}/topology"));
+            let field = |f: &str| read(&dir.join(f))?.trim().parse().ok();
+            Some(Cpu {
+                id,
+                package: field("physical_package_id")?,
+                core: field("core_id")?,
+            })
+        }).collect::<Option<Vec<_>>>()?;
+        if cpus.is_empty() { None } else { Some(Self { cpus }) }
+    }
+
+    /// `n` cpus on one socket, each its own physical core.
+    pub fn flat(n: usize) -> Self {
+        Self {
+            cpus: (0..n.max(1))
+                .map(|id| Cpu { id, package: 0, core: id })
+                .collect()
+        }
+    }
+
+    /// Each cpu with its rank among its core's hyperthreads and its core's
+    /// rank among the cores of its socket.
+    fn ranked(&self) -> Vec<(Cpu, usize, usize)> {
+        let mut cpus = self.cpus.clone();
+        cpus.sort_by_key(|c| (c.package, c.core, c.id));
+        let mut ranked: Vec<(Cpu, usize, usize)> = Vec::with_capacity(cpus.len());
+        for c in cpus {
+            let (smt, core) = match ranked.last() {
+                Some(&(p, s, r)) if (p.package, p.core) == (c.package, c.core) =>
+                    (s + 1, r),
+                Some(&(p, _, r)) if p.package == c.package => (0, r + 1),
+                _ => (0, 0),
+            };
+            ranked.push((c, smt, core));
+        }
+        ranked
+    }
+}
+
+/// How worker threads are pinned to cpus.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Pinning {
+    /// Leave placement to the OS.
+    None,
+    /// Fill a core's hyperthreads, then the next core, then the next socket.
+    Compact,
+    /// Round-robin over sockets, then over cores, then over hyperthreads.
+    Scatter,
+    /// One thread per physical core, socket by socket, before any
+    /// hyperthread sibling gets a second one.
+    #[default]
+    PhysicalCoresFirst,
+    /// Thread `i` goes to the `i`-th cpu of the list, wrapping around.
+    List(Vec<usize>),
+}
+
+impl Pinning {
+    /// The cpu of each worker, in order; thread `i` is pinned to
+    /// `cpus[i % cpus.len()]`. `None` if threads are not pinned.
+    pub fn cpus(&self, topo: &Topology) -> Option<Vec<usize>> {
+        let mut ranked = topo.ranked();
+        match self {
+            Pinning::None => return None,
+            Pinning::List(l) => return Some(l.clone()),
+            Pinning::Compact => {},
+            Pinning::Scatter =>
+                ranked.sort_by_key(|&(c, smt, core)| (smt, core, c.package)),
+            Pinning::PhysicalCoresFirst =>
+                ranked.sort_by_key(|&(c, smt, _)| (smt, c.package, c.core)),
+        }
+        Some(ranked.into_iter().map(|(c, _, _)| c.id).collect())
+    }
+}
+
+impl FromStr for Pinning {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Pinning::None),
+            "compact" => Ok(Pinning::Compact),
+            "scatter" => Ok(Pinning::Scatter),
+            "physical-cores-first" => Ok(Pinning::PhysicalCoresFirst),
+            _ => parse_cpu_list(s).map(Pinning::List).map_err(|e| format!(
+                "{e}; expected none, compact, scatter, physical-cores-first \
+                 or a cpu list such as 0-3,8"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Pinning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Pinning::None => write!(f, "none"),
+            Pinning::Compact => write!(f, "compact"),
+            Pinning::Scatter => write!(f, "scatter"),
+            Pinning::PhysicalCoresFirst => write!(f, "physical-cores-first"),
+            Pinning::List(l) => write!(f, "{}", l.iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+                .join(",")),
+        }
+    }
+}
+
+/// Parses a kernel-style cpu list, e.g. `0-3,8,10-11`.
+pub fn parse_cpu_list(s: &str) -> Result<Vec<usize>, String> {
+    let num = |n: &str| n.trim().parse::<usize>()
+        .map_err(|_| format!("invalid cpu '{n}'"));
+    let mut cpus = Vec::new();
+    for r in s.split(',') {
+        match r.split_once('-') {
+            Some((lo, hi)) => {
+                let (lo, hi) = (num(lo)?, num(hi)?);
+                if lo > hi { return Err(format!("invalid cpu range '{r}'")); }
+                cpus.extend(lo..=hi);
+            },
+            None => cpus.push(num(r)?),
+        }
+    }
+    Ok(cpus)
+}
+
+/// Why a pinned pool could not be set up.
+#[derive(Debug)]
+pub enum PoolError {
+    Build(ThreadPoolBuildError),
+    /// A worker thread could not be pinned to `cpu`.
+    Pin { cpu: usize, reason: String },
+}
+
+impl fmt::Display for PoolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PoolError::Build(e) => write!(f, "cannot build the thread pool: {e}"),
+            PoolError::Pin { cpu, reason } =>
+                write!(f, "cannot pin a thread to cpu {cpu}: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for PoolError {}
+
+impl From<ThreadPoolBuildError> for PoolError {
+    fn from(e: ThreadPoolBuildError) -> Self { PoolError::Build(e) }
+}
+
+/// The first pinning failure of a pool's threads, if any.
+type Failed = Arc<Mutex<Option<PoolError>>>;
+
+fn builder(pin: &Pinning) -> (ThreadPoolBuilder, Failed) {
+    let b = ThreadPoolBuilder::new();
+    let failed = Failed::default();
+    let b = match pin.cpus(&Topology::detect()) {
+        Some(cpus) if !cpus.is_empty() => {
+            let failed = failed.clone();
+            b.start_handler(move |i| {
+                let cpu = cpus[i % cpus.len()];
+                if let Err(e) = set_thread_affinity([cpu]) {
+                    let reason = e.to_string();
+                    failed.lock().unwrap().get_or_insert(PoolError::Pin { cpu, reason });
+                }
+            })
+        },
+        _ => b,
+    };
+    (b, failed)
+}
+
+/// Returns the first pinning failure, once `start` has had every thread
+/// run its start handler.
+fn pinned(failed: Failed, start: impl FnOnce()) -> Result<(), PoolError> {
+    start();
+    match failed.lock().unwrap().take() {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// A rayon pool of `threads` threads, pinned according to `pin`.
+pub fn pinned_pool(threads: usize, pin: &Pinning) -> Result<ThreadPool, PoolError> {
+    let (b, failed) = builder(pin);
+    let pool = b.num_threads(threads).build()?;
+    pinned(failed, || { pool.broadcast(|_| ()); })?;
+    Ok(pool)
+}
+
+/// Builds rayon's global pool with its threads pinned according to `pin`.
+/// Fails if the global pool is already running or a thread cannot be pinned.
+pub fn pin_global(pin: &Pinning) -> Result<(), PoolError> {
+    let (b, failed) = builder(pin);
+    b.build_global()?;
+    pinned(failed, || { rayon::broadcast(|_| ()); })
+}
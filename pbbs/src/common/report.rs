@@ -0,0 +1,224 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+
+use std::fs;
+use std::time::Duration;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::common::perf::{PerfCounts, EVENTS};
+use crate::common::time_loop::RunStats;
+
+
+/// The machine-readable formats a run can be reported in (`--report`).
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum ReportFormat { Json, Csv }
+
+/// The cargo safety features this build of pbbs was compiled with.
+pub fn enabled_features() -> Vec<&'static str> {
+    let features = [
+        ("mem_safe", cfg!(feature = "mem_safe")),
+        ("rng_ind_safe", cfg!(feature = "rng_ind_safe")),
+        ("sng_ind_safe", cfg!(feature = "sng_ind_safe")),
+        ("sng_ind_unsafe", cfg!(feature = "sng_ind_unsafe")),
+        ("sng_ind_atomic", cfg!(feature = "sng_ind_atomic")),
+        ("AW_safe", cfg!(feature = "AW_safe")),
+        ("count_alloc", cfg!(feature = "count_alloc")),
+        ("perf", cfg!(feature = "perf")),
+    ];
+    features.iter().filter(|f| f.1).map(|f| f.0).collect()
+}
+
+/// The mean and per-round time of one phase of the timed code, and what it
+/// allocated in each round (`count_alloc` only).
+#[derive(Clone, Debug, Serialize)]
+pub struct PhaseRecord {
+    pub name: String,
+    pub mean: f64,
+    pub rounds: Vec<f64>,
+    pub allocated: Vec<u64>,
+    pub peak_live: Vec<u64>,
+}
+
+/// One run of a benchmark. Times are in seconds.
+#[derive(Clone, Debug, Serialize)]
+pub struct Record {
+    pub benchmark: String,
+    pub algorithm: String,
+    pub input: String,
+    /// The size of the input file in bytes.
+    pub input_size: u64,
+    pub threads: usize,
+    pub features: Vec<&'static str>,
+    pub warmup: f64,
+    pub mean: f64,
+    pub median: f64,
+    pub min: f64,
+    pub max: f64,
+    pub stddev: f64,
+    /// Half the width of the 95% confidence interval of the mean.
+    pub ci95: f64,
+    pub rounds: Vec<f64>,
+    pub phases: Vec<PhaseRecord>,
+    /// `None` if the output was not checked.
+    pub check: Option<bool>,
+    /// Thread sweeps only: the speedup over 1 thread, the parallel
+    /// efficiency and the speedup over the serial variant.
+    pub speedup: Option<f64>,
+    pub efficiency: Option<f64>,
+    pub serial_speedup: Option<f64>,
+    /// The peak resident set size of each round in bytes.
+    pub peak_rss: Vec<u64>,
+    /// The bytes allocated in each round, and the most that were live at
+    /// once; empty without the `count_alloc` feature.
+    pub allocated: Vec<u64>,
+    pub peak_live: Vec<u64>,
+    /// The hardware counters of each round; empty without the `perf`
+    /// feature or access to the counters.
+    pub perf: Vec<PerfCounts>,
+}
+
+impl Record {
+    pub fn new(
+        benchmark: &str,
+        algorithm: &str,
+        input: &str,
+        stats: &RunStats,
+        check: Option<bool>
+    ) -> Self {
+        Record {
+            benchmark: benchmark.to_string(),
+            algorithm: algorithm.to_string(),
+            input: input.to_string(),
+            input_size: fs::metadata(input).map(|m| m.len()).unwrap_or(0),
+            threads: rayon::current_num_threads(),
+            features: enabled_features(),
+            warmup: stats.warmup.as_secs_f64(),
+            mean: stats.mean.as_secs_f64(),
+            median: stats.median.as_secs_f64(),
+            min: stats.min.as_secs_f64(),
+            max: stats.max.as_secs_f64(),
+            stddev: stats.stddev.as_secs_f64(),
+            ci95: stats.ci95.as_secs_f64(),
+            rounds: stats.samples.iter().map(Duration::as_secs_f64).collect(),
+            phases: stats.phases.iter().map(|p| PhaseRecord {
+                name: p.name.clone(),
+                mean: p.mean().as_secs_f64(),
+                rounds: p.samples.iter().map(Duration::as_secs_f64).collect(),
+                allocated: p.alloc.iter().map(|a| a.allocated).collect(),
+                peak_live: p.alloc.iter().map(|a| a.peak_live).collect(),
+            }).collect(),
+            check,
+            speedup: None,
+            efficiency: None,
+            serial_speedup: None,
+            peak_rss: stats.peak_rss.clone(),
+            allocated: stats.alloc.iter().map(|a| a.allocated).collect(),
+            peak_live: stats.alloc.iter().map(|a| a.peak_live).collect(),
+            perf: stats.perf.clone(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    pub const CSV_HEADER: &'static str = concat!(
+        "benchmark,algorithm,input,input_size,threads,features,",
+        "warmup,mean,median,min,max,stddev,ci95,rounds,phases,check,",
+        "speedup,efficiency,serial_speedup,peak_rss,allocated,peak_live,",
+        "cycles,instructions,cache_misses,branch_misses,context_switches"
+    );
+
+    /// Lists (`features`, `rounds`, `phases` and the memory and counter
+    /// columns) are `;` separated, phases are given as `name=mean`, and
+    /// missing values (e.g. `check` of an unchecked run) are empty.
+    pub fn to_csv(&self) -> String {
+        let join = |v: Vec<String>| v.join(";");
+        let mut fields = vec![
+            csv_field(&self.benchmark),
+            csv_field(&self.algorithm),
+            csv_field(&self.input),
+            self.input_size.to_string(),
+            self.threads.to_string(),
+            join(self.features.iter().map(|f| f.to_string()).collect()),
+            self.warmup.to_string(),
+            self.mean.to_string(),
+            self.median.to_string(),
+            self.min.to_string(),
+            self.max.to_string(),
+            self.stddev.to_string(),
+            self.ci95.to_string(),
+            join(self.rounds.iter().map(|r| r.to_string()).collect()),
+            csv_field(&join(
+                self.phases.iter().map(|p| format!("{}={}", p.name, p.mean)).collect()
+            )),
+            opt(self.check),
+            opt(self.speedup),
+            opt(self.efficiency),
+            opt(self.serial_speedup),
+            join(self.peak_rss.iter().map(|b| b.to_string()).collect()),
+            join(self.allocated.iter().map(|b| b.to_string()).collect()),
+            join(self.peak_live.iter().map(|b| b.to_string()).collect()),
+        ];
+        fields.extend((0..EVENTS.len()).map(|e| {
+            join(self.perf.iter().map(|p| opt(p.values()[e])).collect())
+        }));
+        fields.join(",")
+    }
+
+    /// Prints the record to stdout; csv records are preceded by the header.
+    pub fn emit(&self, fmt: ReportFormat) {
+        Self::emit_all(std::slice::from_ref(self), fmt);
+    }
+
+    /// Prints one line per record, after a single csv header.
+    pub fn emit_all(records: &[Record], fmt: ReportFormat) {
+        match fmt {
+            ReportFormat::Json => {
+                records.iter().for_each(|r| println!("{}", r.to_json()));
+            },
+            ReportFormat::Csv => {
+                println!("{}", Self::CSV_HEADER);
+                records.iter().for_each(|r| println!("{}", r.to_csv()));
+            },
+        }
+    }
+}
+
+fn opt<T: ToString>(v: Option<T>) -> String {
+    v.map_or(String::new(), |v| v.to_string())
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
@@ -26,20 +26,256 @@ use std::time::Duration;
 // ============================================================================
 
 
-use parlay::Timer;
+use std::fmt;
+use std::time::Instant;
 
+use parlay::{phases, Phase, Timer};
+use parlay::memory::{self, AllocStats};
 
-/// Runs a function `runf` for `r` times and returns the mean time.
+use crate::common::perf::{self, PerfCounts, EVENTS};
+
+
+fn parse_secs(s: &str) -> Result<Duration, String> {
+    let secs: f64 = s.parse().map_err(|e| format!("{e}"))?;
+    Duration::try_from_secs_f64(secs).map_err(|e| format!("{e}"))
+}
+
+/// How many rounds [`time_loop`] runs and how long it warms up. Also the
+/// timing flags of every benchmark's command line.
+#[derive(clap::Args, Clone, Debug)]
+pub struct TimeConfig {
+    /// the number of rounds to execute the benchmark
+    #[clap(short, long, value_parser, required=false, default_value_t=1)]
+    pub rounds: usize,
+
+    /// the minimum warm-up time in seconds
+    #[clap(long, value_parser = parse_secs, default_value = "1")]
+    pub warmup: Duration,
+
+    /// keep running rounds until they took at least this many seconds
+    #[clap(long, value_parser = parse_secs, default_value = "0")]
+    pub min_time: Duration,
+
+    /// keep running rounds until the 95% confidence interval is within this
+    /// percentage of the mean
+    #[clap(long, value_parser)]
+    pub ci: Option<f64>,
+
+    /// the maximum number of rounds that --min-time and --ci may run
+    #[clap(long, value_parser, default_value_t=1000)]
+    pub max_rounds: usize,
+}
+
+impl Default for TimeConfig {
+    fn default() -> Self {
+        TimeConfig {
+            rounds: 1,
+            warmup: Duration::new(1, 0),
+            min_time: Duration::ZERO,
+            ci: None,
+            max_rounds: 1000,
+        }
+    }
+}
+
+impl TimeConfig {
+    /// Runs exactly `rounds` rounds after the default warm-up.
+    pub fn rounds(rounds: usize) -> Self {
+        TimeConfig { rounds, ..Default::default() }
+    }
+
+    fn done(&self, samples: &[Duration]) -> bool {
+        let n = samples.len();
+        if n < self.rounds.max(1) { return false; }
+        if n >= self.max_rounds { return true; }
+        if samples.iter().sum::<Duration>() < self.min_time { return false; }
+        match self.ci {
+            Some(pct) => {
+                let s = RunStats::new(Duration::ZERO, samples.to_vec());
+                n >= 2 && s.ci95 <= s.mean.mul_f64(pct / 100.0)
+            },
+            None => true,
+        }
+    }
+}
+
+/// The two sided 95% quantile of Student's t distribution with `df` degrees
+/// of freedom.
+fn t95(df: usize) -> f64 {
+    const T: [f64; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228,
+        2.201, 2.179, 2.160, 2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086,
+        2.080, 2.074, 2.069, 2.064, 2.060, 2.056, 2.052, 2.048, 2.045, 2.042,
+    ];
+    match df {
+        0 => f64::INFINITY,
+        1..=30 => T[df - 1],
+        31..=60 => 2.000,
+        61..=120 => 1.980,
+        _ => 1.960,
+    }
+}
+
+/// The time one [`parlay::Timer`] phase took in each round.
+#[derive(Clone, Debug, Default)]
+pub struct PhaseTimes {
+    /// `timer:phase`, as printed by the timer.
+    pub name: String,
+    /// The total time of the phase in each round; zero in rounds that did
+    /// not report it.
+    pub samples: Vec<Duration>,
+    /// What the phase allocated in each round; empty without the
+    /// `count_alloc` feature.
+    pub alloc: Vec<AllocStats>,
+}
+
+impl PhaseTimes {
+    pub fn mean(&self) -> Duration {
+        self.samples.iter().sum::<Duration>() / self.samples.len().max(1) as u32
+    }
+}
+
+/// Adds the phases of round number `round` to `acc`.
+fn add_round(acc: &mut Vec<PhaseTimes>, round: usize, phases: Vec<Phase>) {
+    let counting = memory::counters().is_some();
+    for p in &mut *acc {
+        p.samples.push(Duration::ZERO);
+        if counting { p.alloc.push(AllocStats::default()); }
+    }
+    for Phase { name, time, alloc } in phases {
+        let i = match acc.iter().position(|p| p.name == name) {
+            Some(i) => i,
+            None => {
+                acc.push(PhaseTimes {
+                    name,
+                    samples: vec![Duration::ZERO; round + 1],
+                    alloc: if counting { vec![AllocStats::default(); round + 1] } else { vec![] },
+                });
+                acc.len() - 1
+            }
+        };
+        acc[i].samples[round] += time;
+        if let Some(a) = alloc {
+            let r = &mut acc[i].alloc[round];
+            r.allocated += a.allocated;
+            r.peak_live = r.peak_live.max(a.peak_live);
+        }
+    }
+}
+
+/// The samples of a [`time_loop`] and their summary.
+#[derive(Clone, Debug, Default)]
+pub struct RunStats {
+    /// The total time spent warming up.
+    pub warmup: Duration,
+    /// The time of each timed round, in order.
+    pub samples: Vec<Duration>,
+    pub min: Duration,
+    pub max: Duration,
+    pub mean: Duration,
+    pub median: Duration,
+    /// The sample standard deviation.
+    pub stddev: Duration,
+    /// Half the width of the 95% confidence interval of the mean, i.e. the
+    /// interval is `mean ± ci95`.
+    pub ci95: Duration,
+    /// The phases the timed code reported, in the order they first appeared.
+    pub phases: Vec<PhaseTimes>,
+    /// The peak resident set size of each round in bytes; empty without
+    /// `/proc`. Where the kernel does not let the peak be reset, it also
+    /// covers everything before the round.
+    pub peak_rss: Vec<u64>,
+    /// What each round allocated; empty without the `count_alloc` feature.
+    pub alloc: Vec<AllocStats>,
+    /// The hardware counters of each round; empty without the `perf`
+    /// feature or access to the counters.
+    pub perf: Vec<PerfCounts>,
+}
+
+impl RunStats {
+    pub fn new(warmup: Duration, samples: Vec<Duration>) -> Self {
+        let n = samples.len();
+        if n == 0 { return RunStats { warmup, ..Default::default() }; }
+
+        let mut sorted = samples.clone();
+        sorted.sort();
+        let median = if n % 2 == 1 { sorted[n / 2] }
+            else { (sorted[n / 2 - 1] + sorted[n / 2]) / 2 };
+
+        let mean = samples.iter().sum::<Duration>() / n as u32;
+        let var = if n < 2 { 0.0 } else {
+            samples
+                .iter()
+                .map(|s| (s.as_secs_f64() - mean.as_secs_f64()).powi(2))
+                .sum::<f64>() / (n - 1) as f64
+        };
+        let stddev = var.sqrt();
+        let ci95 = if n < 2 { 0.0 } else { t95(n - 1) * stddev / (n as f64).sqrt() };
+
+        RunStats {
+            warmup,
+            min: sorted[0],
+            max: sorted[n - 1],
+            mean,
+            median,
+            stddev: Duration::from_secs_f64(stddev),
+            ci95: Duration::from_secs_f64(ci95),
+            samples,
+            ..Default::default()
+        }
+    }
+}
+
+impl fmt::Display for RunStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "median: {:?}  min: {:?}  max: {:?}  stddev: {:?}  ci95: ±{:?}  rounds: {}",
+            self.median,
+            self.min,
+            self.max,
+            self.stddev,
+            self.ci95,
+            self.samples.len()
+        )?;
+        let mib = |b: u64| b as f64 / (1 << 20) as f64;
+        if let Some(rss) = self.peak_rss.iter().max() {
+            write!(f, "  peak rss: {:.1}MiB", mib(*rss))?;
+        }
+        if !self.alloc.is_empty() {
+            let allocated = self.alloc.iter().map(|a| a.allocated).sum::<u64>();
+            let peak = self.alloc.iter().map(|a| a.peak_live).max().unwrap();
+            write!(
+                f,
+                "  allocated: {:.1}MiB  peak live: {:.1}MiB",
+                mib(allocated / self.alloc.len() as u64),
+                mib(peak)
+            )?;
+        }
+        if !self.perf.is_empty() {
+            write!(f, "\nper round:")?;
+            for (e, name) in EVENTS.iter().enumerate() {
+                let v: Vec<u64> = self.perf.iter().filter_map(|p| p.values()[e]).collect();
+                if v.is_empty() { continue; }
+                write!(f, "  {}: {}", name, v.iter().sum::<u64>() / v.len() as u64)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs a function `runf` for as many rounds as `cfg` asks for and returns
+/// the time, memory usage and (with the `perf` feature) hardware counters of
+/// every round, and the time and allocations of every phase that the rounds
+/// report through [`parlay::Timer::next`].
 /// Before each run, `initf` is called and after each run `endf` is called.
-/// `delay` is the minimum time of warm-up.
 pub fn time_loop<S, W, T>(
     name: &str,
-    r: usize,
-    delay: Duration,
+    cfg: &TimeConfig,
     mut initf: S,
     mut runf: W,
     mut endf: T
-) -> Duration
+) -> RunStats
 where
     S: FnMut(),
     W: FnMut(),
@@ -48,19 +284,44 @@ where
     let mut t = Timer::new(name);
     let mut ot = Timer::new("OutLoopTime");
 
+    let warmup = Instant::now();
     t.start();
-    while t.total_time() < delay { initf(); runf(); endf(); }
+    while t.total_time() < cfg.warmup { initf(); runf(); endf(); }
+    let warmup = warmup.elapsed();
 
+    let collecting = phases::is_enabled();
+    phases::enable();
+    let mut samples = Vec::with_capacity(cfg.rounds);
+    let mut round_phases = vec![];
+    let (mut peak_rss, mut alloc, mut counts) = (vec![], vec![], vec![]);
+    let counters = perf::Counters::open();
     ot.start();
-    for _ in 0..r {
+    while !cfg.done(&samples) {
         initf();
+        phases::take();
+        memory::reset_peak_rss();
+        let mark = memory::Watermark::start();
+        if let Some(c) = &counters { c.start(); }
         t.start();
         runf();
-        t.next("");
+        samples.push(t.next_time());
+        if let Some(c) = &counters { counts.push(c.stop()); }
+        let round = phases::take();
+        peak_rss.extend(memory::peak_rss());
+        alloc.extend(mark.map(memory::Watermark::stop));
+        add_round(&mut round_phases, samples.len() - 1, round);
+        t.report(samples[samples.len() - 1], "");
         endf();
     }
     ot.stop();
     ot.total();
+    if !collecting { phases::disable(); }
 
-    t.total_time() / r as u32
-}
\ No newline at end of file
+    RunStats {
+        phases: round_phases,
+        peak_rss,
+        alloc,
+        perf: counts,
+        ..RunStats::new(warmup, samples)
+    }
+}
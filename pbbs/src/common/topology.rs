@@ -25,6 +25,7 @@
 // SOFTWARE.
 // ============================================================================
 
+use std::cell::UnsafeCell;
 use std::default::Default;
 
 use crate::common::geometry::{Point2d, in_circle, counter_clock_wise, angle};
@@ -34,22 +35,40 @@ use parlay::make_mut;
 type Tri<'a> = Triangle<'a>;
 type Vtx<'a> = Vertex<'a>;
 
-#[derive(Clone, Copy)]
+/// The mesh is updated in place through shared references (`make_mut!`).
+/// Holding an `UnsafeCell` keeps the compiler from assuming that a `&Vtx`
+/// or `&Tri` points at memory nobody writes to.
+#[derive(Default)]
+pub struct SharedMut(UnsafeCell<()>);
+
+impl SharedMut {
+    pub const fn new() -> Self { Self(UnsafeCell::new(())) }
+}
+
+impl Clone for SharedMut {
+    fn clone(&self) -> Self { Self::new() }
+}
+
+unsafe impl Sync for SharedMut {}
+
+#[derive(Clone)]
 pub struct Vertex<'a> {
     pub pt: Point2d<f64>,
     pub id: i32,
     pub reserve: i32,
     pub t: Option<&'a Tri<'a>>,
     pub bad_t: Option<&'a Tri<'a>>,
+    pub shared: SharedMut,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct Triangle<'a> {
     pub id: usize,
     pub vtx: [Option<&'a Vtx<'a>>; 3],
     pub ngh: [Option<&'a Tri<'a>>; 3],
     pub initialized: bool,
     pub bad: u8, // used to mark badly shaped triangles.
+    pub shared: SharedMut,
 }
 
 #[derive(Clone, Copy)]
@@ -65,7 +84,10 @@ fn mod3(i: i32) -> i32 { if i > 2 { i-3 } else { i } }
 
 impl<'a> Vertex<'a> {
     pub fn new(p: Point2d<f64>, i: usize) -> Self {
-        Self { pt: p, id: i as i32, reserve: -1, t: None, bad_t: None }
+        Self {
+            pt: p, id: i as i32, reserve: -1, t: None, bad_t: None,
+            shared: SharedMut::new()
+        }
     }
 
     pub fn print(&self) {
@@ -75,7 +97,10 @@ impl<'a> Vertex<'a> {
 
 impl<'a> Default for Vertex<'a> {
     fn default() -> Self {
-        Self { pt: Point2d::default(), id: 0, reserve: -1, t: None, bad_t: None }
+        Self {
+            pt: Point2d::default(), id: 0, reserve: -1, t: None, bad_t: None,
+            shared: SharedMut::new()
+        }
     }
 }
 
@@ -124,7 +149,8 @@ pub static NULL_TRI: Triangle = Triangle {
     vtx: [None, None, None],
     ngh: [None, None, None],
     initialized: false,
-    bad: 0
+    bad: 0,
+    shared: SharedMut::new()
 };
 
 impl<'a> Default for Triangle<'a> {
@@ -134,7 +160,8 @@ impl<'a> Default for Triangle<'a> {
             vtx: [None, None, None],
             ngh: [None, None, None],
             initialized: false,
-            bad: 0
+            bad: 0,
+            shared: SharedMut::new()
         }
     }
 }
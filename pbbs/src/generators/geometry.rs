@@ -0,0 +1,254 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! The PBBS point generators and a Delaunay triangulation for
+//! `pbbs_triangles` inputs.
+
+use clap::ValueEnum;
+use rayon::prelude::*;
+
+use parlay::random::Random;
+use crate::common::geometry::{counter_clock_wise, in_circle, Point2d, Point3d, Tri};
+
+
+/// How the points of `pbbs gen points` are spread.
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum PointDist {
+    /// uniformly in the cube `[-1, 1]^d` (randPoints)
+    InCube,
+    /// uniformly in the unit ball (randPoints -s)
+    InSphere,
+    /// uniformly on the unit sphere (randPoints -S)
+    OnSphere,
+    /// the Kuzmin distribution, heavily clustered around the origin
+    /// (randPoints -k)
+    Kuzmin,
+    /// the Plummer model of a star cluster (randPoints -p)
+    Plummer,
+}
+
+fn unit(h: u64) -> f64 { (h >> 11) as f64 / (1u64 << 53) as f64 }
+
+/// One point of `dist` in `dim` dimensions, drawn from the stream `r`.
+fn sample(dist: PointDist, dim: usize, r: &Random) -> [f64; 3] {
+    let mut h = 0;
+    let mut next = || { h += 1; 2.0 * unit(r.ith_rand(h)) - 1.0 };
+    let mut cube = || {
+        let mut p = [0.0; 3];
+        p[..dim].iter_mut().for_each(|x| *x = next());
+        p
+    };
+    let norm = |p: &[f64; 3]| p.iter().map(|x| x * x).sum::<f64>().sqrt();
+    let mut ball = || loop {
+        let p = cube();
+        if norm(&p) <= 1.0 { return p; }
+    };
+    let mut direction = || loop {
+        let p = ball();
+        let l = norm(&p);
+        if l > 1e-6 { return p.map(|x| x / l); }
+    };
+    let scaled = |p: [f64; 3], s: f64| p.map(|x| x * s);
+    match dist {
+        PointDist::InCube => cube(),
+        PointDist::InSphere => ball(),
+        PointDist::OnSphere => direction(),
+        PointDist::Kuzmin => {
+            let d = direction();
+            // u in (0, 1], so that the radius stays finite
+            let u = 1.0 - unit(r.ith_rand(0));
+            scaled(d, (1.0 / (u * u) - 1.0).sqrt())
+        },
+        PointDist::Plummer => {
+            let d = direction();
+            let u = 1.0 - unit(r.ith_rand(0));
+            scaled(d, 1.0 / (u.powf(-2.0 / 3.0) - 1.0).max(f64::MIN_POSITIVE).sqrt())
+        },
+    }
+}
+
+/// `n` points of `dist` in the plane.
+pub fn points2d(n: usize, dist: PointDist, seed: u64) -> Vec<Point2d<f64>> {
+    let r = Random::new(seed);
+    (0..n as u64).into_par_iter().map(|i| {
+        let p = sample(dist, 2, &r.fork(i));
+        Point2d::new(p[0], p[1])
+    }).collect()
+}
+
+/// `n` points of `dist` in space.
+pub fn points3d(n: usize, dist: PointDist, seed: u64) -> Vec<Point3d<f64>> {
+    let r = Random::new(seed);
+    (0..n as u64).into_par_iter().map(|i| {
+        let p = sample(dist, 3, &r.fork(i));
+        Point3d::new(p[0], p[1], p[2])
+    }).collect()
+}
+
+const NONE: usize = usize::MAX;
+
+/// A triangle of the mesh under construction: its vertices in
+/// counter-clockwise order and, at `ngh[i]`, the triangle across the edge
+/// opposite `v[i]`.
+#[derive(Copy, Clone)]
+struct Face { v: [usize; 3], ngh: [usize; 3], alive: bool }
+
+/// The Delaunay triangulation of `pts` (delaunay), as counter-clockwise
+/// triangles indexing into `pts`. The points are inserted one at a time in
+/// Morton order (Bowyer-Watson), starting from a triangle that encloses
+/// them all; the triangles touching its corners are dropped at the end.
+/// Repeated points are left out of the mesh.
+pub fn delaunay(pts: &[Point2d<f64>]) -> Vec<Tri> {
+    let n = pts.len();
+    if n < 3 { return vec![]; }
+    let (lo, hi) = pts.iter().fold(
+        (Point2d::new(f64::MAX, f64::MAX), Point2d::new(f64::MIN, f64::MIN)),
+        |(lo, hi), p| (
+            Point2d::new(lo.x.min(p.x), lo.y.min(p.y)),
+            Point2d::new(hi.x.max(p.x), hi.y.max(p.y)),
+        ),
+    );
+    let size = (hi.x - lo.x).max(hi.y - lo.y).max(f64::MIN_POSITIVE);
+    let (cx, cy) = ((lo.x + hi.x) / 2.0, (lo.y + hi.y) / 2.0);
+    let mut p = pts.to_vec();
+    let far = 64.0 * size;
+    p.push(Point2d::new(cx - far, cy - far));
+    p.push(Point2d::new(cx + far, cy - far));
+    p.push(Point2d::new(cx, cy + far));
+
+    // insert in Morton order so that each walk starts close to its target
+    let morton = |q: &Point2d<f64>| {
+        let cell = |x: f64, l: f64| (((x - l) / size * 65535.0) as u64).min(65535);
+        let spread = |mut x: u64| {
+            x = (x | (x << 8)) & 0x00ff_00ff;
+            x = (x | (x << 4)) & 0x0f0f_0f0f;
+            x = (x | (x << 2)) & 0x3333_3333;
+            (x | (x << 1)) & 0x5555_5555
+        };
+        spread(cell(q.x, lo.x)) | (spread(cell(q.y, lo.y)) << 1)
+    };
+    let mut order: Vec<usize> = (0..n).collect();
+    order.par_sort_by_key(|&i| morton(&pts[i]));
+
+    let mut mesh = Mesh {
+        p: &p,
+        faces: vec![Face { v: [n, n + 1, n + 2], ngh: [NONE; 3], alive: true }],
+        last: 0,
+    };
+    for i in order { mesh.insert(i); }
+
+    mesh.faces.into_iter()
+        .filter(|f| f.alive && f.v.iter().all(|&v| v < n))
+        .map(|f| f.v.map(|v| v as i32))
+        .collect()
+}
+
+struct Mesh<'a> {
+    p: &'a [Point2d<f64>],
+    faces: Vec<Face>,
+    last: usize,
+}
+
+impl Mesh<'_> {
+    /// Walks from the last triangle inserted towards the one containing
+    /// `q`.
+    fn locate(&self, q: Point2d<f64>) -> usize {
+        let mut t = self.last;
+        'walk: loop {
+            let f = &self.faces[t];
+            for i in 0..3 {
+                let (a, b) = (self.p[f.v[(i + 1) % 3]], self.p[f.v[(i + 2) % 3]]);
+                if counter_clock_wise(b, a, q) && f.ngh[i] != NONE {
+                    t = f.ngh[i];
+                    continue 'walk;
+                }
+            }
+            return t;
+        }
+    }
+
+    fn in_circ(&self, t: usize, q: Point2d<f64>) -> bool {
+        let v = self.faces[t].v;
+        in_circle(self.p[v[0]], self.p[v[1]], self.p[v[2]], q)
+    }
+
+    fn insert(&mut self, i: usize) {
+        let q = self.p[i];
+        let start = self.locate(q);
+        let v = self.faces[start].v;
+        if v.iter().any(|&v| self.p[v].x == q.x && self.p[v].y == q.y) { return; }
+
+        // the cavity: every triangle whose circumcircle holds q, reachable
+        // from the one q lies in
+        let mut cavity = vec![start];
+        self.faces[start].alive = false;
+        let mut k = 0;
+        while k < cavity.len() {
+            let t = cavity[k];
+            for u in self.faces[t].ngh {
+                if u != NONE && self.faces[u].alive && self.in_circ(u, q) {
+                    self.faces[u].alive = false;
+                    cavity.push(u);
+                }
+            }
+            k += 1;
+        }
+
+        // fan q out to the edges on the cavity's boundary; the fan has two
+        // more triangles than the cavity, so it takes over all its slots
+        let old: Vec<Face> = cavity.iter().map(|&t| self.faces[t]).collect();
+        let mut slots = cavity.clone().into_iter();
+        let mut fan: Vec<(usize, usize)> = vec![];
+        for f in old {
+            for j in 0..3 {
+                let u = f.ngh[j];
+                if cavity.contains(&u) { continue; }
+                let (a, b) = (f.v[(j + 1) % 3], f.v[(j + 2) % 3]);
+                let face = Face { v: [i, a, b], ngh: [u, NONE, NONE], alive: true };
+                let id = match slots.next() {
+                    Some(id) => { self.faces[id] = face; id },
+                    None => { self.faces.push(face); self.faces.len() - 1 },
+                };
+                if u != NONE {
+                    // u may border the cavity twice, so find the shared edge
+                    // by its ends rather than by the (reused) slot number
+                    let back = self.faces[u].v.iter().position(|&w| w != a && w != b).unwrap();
+                    self.faces[u].ngh[back] = id;
+                }
+                fan.push((a, id));
+            }
+        }
+        // stitch the fan: the edge (b, q) of [q, a, b] borders the triangle
+        // that starts at b
+        for &(_, id) in &fan {
+            let b = self.faces[id].v[2];
+            let next = fan.iter().find(|&&(a, _)| a == b).unwrap().1;
+            self.faces[id].ngh[1] = next;
+            self.faces[next].ngh[2] = id;
+        }
+        self.last = fan[0].1;
+    }
+}
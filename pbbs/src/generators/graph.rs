@@ -0,0 +1,208 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+
+//! The PBBS graph generators and the graph file formats.
+
+use std::io;
+use std::path::Path;
+
+use clap::ValueEnum;
+use rayon::prelude::*;
+
+use parlay::random::Random;
+use crate::DefInt;
+use super::write_sequence;
+
+
+/// An edge `(u, v)` of a generated graph.
+pub type GenEdge = (DefInt, DefInt);
+
+/// `m` edges of an R-MAT graph on `2^log_n` vertices (rMatGraph). Each edge
+/// picks one of the four quadrants of the adjacency matrix with
+/// probabilities `a`, `b`, `c` and `1 - a - b - c`, recursively.
+pub fn rmat(log_n: u32, m: usize, a: f64, b: f64, c: f64, seed: u64) -> Vec<GenEdge> {
+    let r = Random::new(seed);
+    (0..m as u64).into_par_iter().map(|i| {
+        let e = r.fork(i);
+        let (mut u, mut v) = (0, 0);
+        for level in 0..log_n {
+            let x = (e.ith_rand(level as u64) >> 11) as f64 / (1u64 << 53) as f64;
+            let half = 1 << (log_n - 1 - level);
+            if x >= a + b + c { u += half; v += half; }
+            else if x >= a + b { u += half; }
+            else if x >= a { v += half; }
+        }
+        (u, v)
+    }).collect()
+}
+
+/// `degree` edges out of each of the `n` vertices, to vertices that are
+/// close by in a `dim` dimensional space (randLocalGraph). The offset of a
+/// neighbor is drawn from `0..2^p`, where `p` starts at `dim + 2` and grows
+/// by `dim` with probability 1/2, repeatedly; `dim` 0 picks neighbors
+/// uniformly.
+pub fn rand_local(n: usize, degree: usize, dim: u32, seed: u64) -> Vec<GenEdge> {
+    let r = Random::new(seed);
+    let n64 = n as u64;
+    (0..(n * degree) as u64).into_par_iter().map(|k| {
+        let i = k / degree as u64;
+        let e = r.fork(k);
+        let mut h = 0;
+        let mut next = || { h += 1; e.ith_rand(h) };
+        let j = loop {
+            let j = if dim == 0 { next() % n64 } else {
+                let mut p = dim + 2;
+                while next() % 2 == 0 && p + dim < 62 { p += dim; }
+                (i + next() % (1 << p)) % n64
+            };
+            if j != i || n < 2 { break j; }
+        };
+        (i as DefInt, j as DefInt)
+    }).collect()
+}
+
+/// A `dim` dimensional torus with about `n` vertices, as `(vertices,
+/// edges)`: each vertex has an edge to its successor along every dimension
+/// (gridGraph).
+pub fn grid(n: usize, dim: u32) -> (usize, Vec<GenEdge>) {
+    let side = ((n as f64).powf(1.0 / dim as f64).round() as usize).max(1);
+    let n = side.pow(dim);
+    let edges = (0..n).into_par_iter().flat_map_iter(|i| {
+        (0..dim).map(move |d| {
+            let stride = side.pow(d);
+            let coord = i / stride % side;
+            let j = i - coord * stride + (coord + 1) % side * stride;
+            (i as DefInt, j as DefInt)
+        })
+    }).collect();
+    (n, edges)
+}
+
+/// Drops self loops and duplicates, adding the reverse of every edge first
+/// if `symmetric`. The edges come out sorted.
+pub fn clean(mut edges: Vec<GenEdge>, symmetric: bool) -> Vec<GenEdge> {
+    if symmetric {
+        let rev: Vec<GenEdge> = edges.par_iter().map(|&(u, v)| (v, u)).collect();
+        edges.extend(rev);
+    }
+    edges.retain(|e| e.0 != e.1);
+    edges.par_sort_unstable();
+    edges.dedup();
+    edges
+}
+
+/// The weight hash of an edge; the same in both directions, so that
+/// symmetric graphs get symmetric weights.
+fn weight_hash(r: &Random, (u, v): GenEdge) -> u64 {
+    r.fork(u.min(v) as u64).ith_rand(u.max(v) as u64)
+}
+
+/// The graph file formats of PBBS.
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum GraphFormat {
+    /// `AdjacencyGraph`: n, m, the offsets and the targets
+    Adjacency,
+    /// `WeightedAdjacencyGraph`: also the integer weights, after the targets
+    WeightedAdjacency,
+    /// `EdgeArray`: one `u v` per line
+    EdgeArray,
+    /// `WeightedEdgeArray`: one `u v w` per line, w in [0, 1)
+    WeightedEdgeArray,
+}
+
+/// Writes `edges`, sorted as [`clean`] leaves them, on `n` vertices.
+/// Weights are drawn from `seed` (addWeights): integers in
+/// `1..=max_weight` for adjacency graphs, floats in `[0, 1)` for edge
+/// arrays.
+pub fn write_graph<P: AsRef<Path>>(
+    edges: &[GenEdge],
+    n: usize,
+    format: GraphFormat,
+    max_weight: u64,
+    seed: u64,
+    fname: P,
+) -> io::Result<()> {
+    let r = Random::new(seed);
+    let weights: Option<Vec<f64>> = match format {
+        GraphFormat::WeightedAdjacency => Some(edges.par_iter()
+            .map(|&e| (1 + weight_hash(&r, e) % max_weight.max(1)) as f64)
+            .collect()),
+        GraphFormat::WeightedEdgeArray => Some(edges.par_iter()
+            .map(|&e| (weight_hash(&r, e) >> 40) as f64 / (1u64 << 24) as f64)
+            .collect()),
+        _ => None,
+    };
+    write_weighted_graph(edges, n, format, weights.as_deref(), fname)
+}
+
+/// Writes `edges`, sorted by source, on `n` vertices, with the given
+/// weights (1 if there are none) if `format` is weighted. Adjacency
+/// graphs only have integer weights, so a fractional one is an error.
+pub fn write_weighted_graph<P: AsRef<Path>>(
+    edges: &[GenEdge],
+    n: usize,
+    format: GraphFormat,
+    weights: Option<&[f64]>,
+    fname: P,
+) -> io::Result<()> {
+    let m = edges.len();
+    let weight = |i: usize| weights.map_or(1.0, |w| w[i]);
+    if format == GraphFormat::WeightedAdjacency {
+        if let Some(i) = (0..m).into_par_iter().find_first(|&i| weight(i).fract() != 0.0) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!(
+                "edge {i} has the weight {}, but a WeightedAdjacencyGraph only has integer weights",
+                weight(i)
+            )));
+        }
+    }
+    match format {
+        GraphFormat::Adjacency | GraphFormat::WeightedAdjacency => {
+            let offsets = (0..n).into_par_iter()
+                .map(|i| edges.partition_point(|e| (e.0 as usize) < i).to_string());
+            let targets = edges.par_iter().map(|e| e.1.to_string());
+            let (header, lines): (_, Vec<String>) = if format == GraphFormat::Adjacency {
+                ("AdjacencyGraph", offsets.chain(targets).collect())
+            } else {
+                let weights = (0..m).into_par_iter()
+                    .map(|i| (weight(i) as i64).to_string());
+                ("WeightedAdjacencyGraph", offsets.chain(targets).chain(weights).collect())
+            };
+            write_sequence(&lines, &format!("{header}\n{n}\n{m}"), fname)
+        },
+        GraphFormat::EdgeArray => {
+            let lines: Vec<String> = edges.par_iter().map(|e| format!("{} {}", e.0, e.1)).collect();
+            write_sequence(&lines, "EdgeArray", fname)
+        },
+        GraphFormat::WeightedEdgeArray => {
+            // the readers parse the weights as f32
+            let lines: Vec<String> = edges.par_iter().enumerate()
+                .map(|(i, e)| format!("{} {} {}", e.0, e.1, weight(i) as f32))
+                .collect();
+            write_sequence(&lines, "WeightedEdgeArray", fname)
+        },
+    }
+}
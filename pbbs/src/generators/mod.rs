@@ -0,0 +1,339 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+
+//! Input generators in the formats the benchmarks read (`pbbs gen`).
+
+use std::fs;
+use std::io;
+
+use clap::{Subcommand, ValueEnum};
+use rayon::prelude::*;
+
+pub mod seq;
+pub mod graph;
+pub mod geometry;
+
+use crate::common::geometry::Triangles;
+use crate::common::geometry_io::write_triangles_to_file;
+pub use crate::common::io::write_sequence;
+use geometry::PointDist;
+use graph::GraphFormat;
+
+
+/// The arguments every generator takes.
+#[derive(clap::Args, Debug)]
+pub struct Out {
+    /// the number of elements (characters for text, vertices for graphs)
+    pub n: usize,
+
+    /// the output filename
+    pub ofname: String,
+
+    /// the random seed
+    #[clap(short, long, default_value_t = 0)]
+    pub seed: u64,
+}
+
+/// The output options of the graph generators.
+#[derive(clap::Args, Debug)]
+pub struct GraphOut {
+    /// the file format
+    #[clap(short, long, value_enum, default_value_t = GraphFormat::Adjacency)]
+    pub format: GraphFormat,
+
+    /// add the reverse of every edge (bfs and mis expect symmetric graphs)
+    #[clap(long)]
+    pub symmetric: bool,
+
+    /// the largest weight of a weighted adjacency graph
+    #[clap(long, default_value_t = 1000)]
+    pub max_weight: u64,
+}
+
+/// The element type of a sequence.
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum ElemType { Int, Double }
+
+#[derive(Subcommand, Debug)]
+pub enum Generator {
+    /// Uniformly random integers or doubles (randomSeq)
+    Random {
+        #[clap(short = 't', long = "type", value_enum, default_value_t = ElemType::Int)]
+        ty: ElemType,
+
+        /// integers are drawn from 0..range (default n)
+        #[clap(short, long)]
+        range: Option<u64>,
+
+        #[clap(flatten)]
+        out: Out,
+    },
+
+    /// Integers or doubles with exponentially distributed key frequencies
+    /// (exptSeq)
+    Expt {
+        #[clap(short = 't', long = "type", value_enum, default_value_t = ElemType::Int)]
+        ty: ElemType,
+
+        /// integers are in 0..range (default n)
+        #[clap(short, long)]
+        range: Option<u64>,
+
+        #[clap(flatten)]
+        out: Out,
+    },
+
+    /// 0..n with a few random pairs swapped (almostSortedSeq)
+    AlmostSorted {
+        /// the number of swaps (default sqrt(n))
+        #[clap(long)]
+        swaps: Option<usize>,
+
+        #[clap(flatten)]
+        out: Out,
+    },
+
+    /// The same integer n times
+    Equal {
+        #[clap(long, default_value_t = 0)]
+        value: u64,
+
+        #[clap(flatten)]
+        out: Out,
+    },
+
+    /// Uniformly random pairs of integers (randomSeq -t pairInt)
+    Pairs {
+        /// the elements are drawn from 0..range (default n)
+        #[clap(short, long)]
+        range: Option<u64>,
+
+        #[clap(flatten)]
+        out: Out,
+    },
+
+    /// n characters of words from a letter trigram model (trigramString)
+    Trigram {
+        /// learn the model from this text instead of the built-in one
+        #[clap(long)]
+        corpus: Option<String>,
+
+        #[clap(flatten)]
+        out: Out,
+    },
+
+    /// An R-MAT power-law graph on n vertices, rounded up to a power of two
+    /// (rMatGraph)
+    Rmat {
+        /// the number of edges drawn, before dropping duplicates (default 10n)
+        #[clap(short, long)]
+        m: Option<usize>,
+
+        #[clap(short, default_value_t = 0.5)]
+        a: f64,
+
+        #[clap(short, default_value_t = 0.1)]
+        b: f64,
+
+        #[clap(short, default_value_t = 0.1)]
+        c: f64,
+
+        #[clap(flatten)]
+        graph: GraphOut,
+
+        #[clap(flatten)]
+        out: Out,
+    },
+
+    /// m/n edges out of every vertex, mostly to nearby vertices
+    /// (randLocalGraph)
+    RandLocal {
+        /// the dimension of the space the vertices are close in (0 for
+        /// uniformly random neighbors)
+        #[clap(short, long, default_value_t = 10)]
+        dims: u32,
+
+        /// the number of edges drawn, before dropping duplicates (default 10n)
+        #[clap(short, long)]
+        m: Option<usize>,
+
+        #[clap(flatten)]
+        graph: GraphOut,
+
+        #[clap(flatten)]
+        out: Out,
+    },
+
+    /// A torus with about n vertices (gridGraph)
+    Grid {
+        /// 2 or 3
+        #[clap(short, long, default_value_t = 2, value_parser = clap::value_parser!(u32).range(2..=3))]
+        dims: u32,
+
+        #[clap(flatten)]
+        graph: GraphOut,
+
+        #[clap(flatten)]
+        out: Out,
+    },
+
+    /// Random points in the plane or in space (randPoints)
+    Points {
+        /// 2 or 3
+        #[clap(short, long, default_value_t = 2, value_parser = clap::value_parser!(u32).range(2..=3))]
+        dims: u32,
+
+        #[clap(long, value_enum, default_value_t = PointDist::InCube)]
+        dist: PointDist,
+
+        #[clap(flatten)]
+        out: Out,
+    },
+
+    /// The Delaunay triangulation of random points in the plane, the input
+    /// of dr (delaunay)
+    Triangles {
+        #[clap(long, value_enum, default_value_t = PointDist::InCube)]
+        dist: PointDist,
+
+        #[clap(flatten)]
+        out: Out,
+    },
+}
+
+impl Generator {
+    /// Generates the input and writes it to its output file.
+    pub fn run(&self) -> Result<(), String> {
+        let res = match self {
+            Generator::Random { ty: ElemType::Int, range, out } => write_sequence(
+                &seq::random_ints(out.n, range.unwrap_or(out.n as u64), out.seed),
+                "sequenceInt",
+                &out.ofname,
+            ),
+            Generator::Random { ty: ElemType::Double, out, .. } => write_sequence(
+                &seq::random_doubles(out.n, out.seed),
+                "sequenceDouble",
+                &out.ofname,
+            ),
+            Generator::Expt { ty: ElemType::Int, range, out } => write_sequence(
+                &seq::expt_ints(out.n, range.unwrap_or(out.n as u64), out.seed),
+                "sequenceInt",
+                &out.ofname,
+            ),
+            Generator::Expt { ty: ElemType::Double, out, .. } => write_sequence(
+                &seq::expt_doubles(out.n, out.seed),
+                "sequenceDouble",
+                &out.ofname,
+            ),
+            Generator::AlmostSorted { swaps, out } => {
+                let swaps = swaps.unwrap_or((out.n as f64).sqrt() as usize);
+                write_sequence(
+                    &seq::almost_sorted(out.n, swaps, out.seed),
+                    "sequenceInt",
+                    &out.ofname,
+                )
+            },
+            Generator::Equal { value, out } => write_sequence(
+                &seq::all_equal(out.n, *value),
+                "sequenceInt",
+                &out.ofname,
+            ),
+            Generator::Pairs { range, out } => {
+                let pairs: Vec<String> =
+                    seq::random_pairs(out.n, range.unwrap_or(out.n as u64), out.seed)
+                        .into_par_iter()
+                        .map(|(a, b)| format!("{a} {b}"))
+                        .collect();
+                write_sequence(&pairs, "sequenceIntPair", &out.ofname)
+            },
+            Generator::Trigram { corpus, out } => {
+                let text = match corpus {
+                    Some(f) => fs::read(f).map_err(|e| format!("{f}: {e}"))?,
+                    None => seq::CORPUS.as_bytes().to_vec(),
+                };
+                let model = seq::Trigrams::learn(&text)
+                    .ok_or("the corpus has no words")?;
+                fs::write(&out.ofname, model.text(out.n, out.seed))
+            },
+            Generator::Rmat { m, a, b, c, graph: g, out } => {
+                let log_n = out.n.max(1).next_power_of_two().trailing_zeros();
+                let m = m.unwrap_or(10 * out.n);
+                let es = graph::rmat(log_n, m, *a, *b, *c, out.seed);
+                write_generated(es, 1 << log_n, g, out)
+            },
+            Generator::RandLocal { dims, m, graph: g, out } => {
+                let degree = m.unwrap_or(10 * out.n) / out.n.max(1);
+                let es = graph::rand_local(out.n, degree, *dims, out.seed);
+                write_generated(es, out.n, g, out)
+            },
+            Generator::Grid { dims, graph: g, out } => {
+                let (n, es) = graph::grid(out.n, *dims);
+                write_generated(es, n, g, out)
+            },
+            Generator::Points { dims: 2, dist, out } => write_sequence(
+                &geometry::points2d(out.n, *dist, out.seed),
+                "pbbs_sequencePoint2d",
+                &out.ofname,
+            ),
+            Generator::Points { dist, out, .. } => write_sequence(
+                &geometry::points3d(out.n, *dist, out.seed),
+                "pbbs_sequencePoint3d",
+                &out.ofname,
+            ),
+            Generator::Triangles { dist, out } => {
+                let p = geometry::points2d(out.n, *dist, out.seed);
+                let t = geometry::delaunay(&p);
+                write_triangles_to_file(&Triangles::new(p, t), &out.ofname);
+                Ok(())
+            },
+        };
+        res.map_err(|e| format!("{}: {e}", self.out().ofname))
+    }
+
+    fn out(&self) -> &Out {
+        match self {
+            Generator::Random { out, .. }
+            | Generator::Expt { out, .. }
+            | Generator::AlmostSorted { out, .. }
+            | Generator::Equal { out, .. }
+            | Generator::Pairs { out, .. }
+            | Generator::Trigram { out, .. }
+            | Generator::Rmat { out, .. }
+            | Generator::RandLocal { out, .. }
+            | Generator::Grid { out, .. }
+            | Generator::Points { out, .. }
+            | Generator::Triangles { out, .. } => out,
+        }
+    }
+}
+
+fn write_generated(es: Vec<graph::GenEdge>, n: usize, g: &GraphOut, out: &Out) -> io::Result<()> {
+    let es = graph::clean(es, g.symmetric);
+    // the weights get a seed of their own
+    let seed = parlay::utilities::hash64(out.seed);
+    graph::write_graph(&es, n, g.format, g.max_weight, seed, &out.ofname)
+}
@@ -0,0 +1,197 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+
+//! The PBBS sequence generators. Element `i` only depends on the seed and
+//! on `i`, so the sequences are the same whatever the number of threads.
+
+use std::collections::HashMap;
+
+use rayon::prelude::*;
+
+use parlay::random::Random;
+
+
+/// `n` integers drawn uniformly from `0..range` (randomSeq).
+pub fn random_ints(n: usize, range: u64, seed: u64) -> Vec<u64> {
+    let r = Random::new(seed);
+    (0..n as u64).into_par_iter().map(|i| r.ith_rand(i) % range.max(1)).collect()
+}
+
+/// `n` doubles drawn uniformly from `[0, 1)` (randomSeq -t double).
+pub fn random_doubles(n: usize, seed: u64) -> Vec<f64> {
+    let r = Random::new(seed);
+    (0..n as u64).into_par_iter().map(|i| unit(r.ith_rand(i))).collect()
+}
+
+fn unit(h: u64) -> f64 { (h >> 11) as f64 / (1u64 << 53) as f64 }
+
+/// Picks a bucket `[2^j, 2^(j+1))` uniformly among the `log2(n) + 1`
+/// buckets, then a key uniformly inside it, so that small keys repeat
+/// exponentially more often than large ones.
+fn expt_key(r: &Random, i: u64, n: u64) -> u64 {
+    let lg = 64 - n.max(1).leading_zeros() as u64 + 1;
+    let bucket = 1u64 << (r.ith_rand(2 * i) % lg);
+    bucket + r.ith_rand(2 * i + 1) % bucket
+}
+
+/// `n` integers in `0..range` with exponentially distributed key
+/// frequencies (exptSeq).
+pub fn expt_ints(n: usize, range: u64, seed: u64) -> Vec<u64> {
+    let r = Random::new(seed);
+    let keys = r.next();
+    (0..n as u64).into_par_iter()
+        .map(|i| keys.ith_rand(expt_key(&r, i, n as u64)) % range.max(1))
+        .collect()
+}
+
+/// `n` doubles in `[0, 1)` with exponentially distributed key frequencies
+/// (exptSeq -t double).
+pub fn expt_doubles(n: usize, seed: u64) -> Vec<f64> {
+    let r = Random::new(seed);
+    let keys = r.next();
+    (0..n as u64).into_par_iter()
+        .map(|i| unit(keys.ith_rand(expt_key(&r, i, n as u64))))
+        .collect()
+}
+
+/// `0..n` with `swaps` random pairs of elements swapped (almostSortedSeq);
+/// PBBS uses `sqrt(n)` swaps.
+pub fn almost_sorted(n: usize, swaps: usize, seed: u64) -> Vec<u64> {
+    let r = Random::new(seed);
+    let mut a: Vec<u64> = (0..n as u64).into_par_iter().collect();
+    if n == 0 { return a; }
+    for j in 0..swaps as u64 {
+        let (x, y) = (r.ith_rand(2 * j) % n as u64, r.ith_rand(2 * j + 1) % n as u64);
+        a.swap(x as usize, y as usize);
+    }
+    a
+}
+
+/// `n` copies of `value`.
+pub fn all_equal(n: usize, value: u64) -> Vec<u64> {
+    vec![value; n]
+}
+
+/// `n` pairs of integers drawn uniformly from `0..range` (randomSeq -t
+/// pairInt).
+pub fn random_pairs(n: usize, range: u64, seed: u64) -> Vec<(u64, u64)> {
+    let r = Random::new(seed);
+    let range = range.max(1);
+    (0..n as u64).into_par_iter()
+        .map(|i| (r.ith_rand(2 * i) % range, r.ith_rand(2 * i + 1) % range))
+        .collect()
+}
+
+/// The text the trigram model is learnt from when no corpus is given.
+pub const CORPUS: &str = "\
+it was the best of times it was the worst of times it was the age of \
+wisdom it was the age of foolishness it was the epoch of belief it was the \
+epoch of incredulity it was the season of light it was the season of \
+darkness it was the spring of hope it was the winter of despair we had \
+everything before us we had nothing before us we were all going direct to \
+heaven we were all going direct the other way in short the period was so \
+far like the present period that some of its noisiest authorities insisted \
+on its being received for good or for evil in the superlative degree of \
+comparison only there were a king with a large jaw and a queen with a plain \
+face on the throne of england there were a king with a large jaw and a \
+queen with a fair face on the throne of france in both countries it was \
+clearer than crystal to the lords of the state preserves of loaves and \
+fishes that things in general were settled for ever";
+
+/// A model of which letter follows each pair of letters in a text.
+pub struct Trigrams {
+    /// For each two letter context, the letters that followed it and how
+    /// often, as a cumulative count.
+    next: HashMap<[u8; 2], Vec<(u8, u32)>>,
+}
+
+impl Trigrams {
+    /// Learns the model from the lower-cased words of `text`; `None` if it
+    /// has no words.
+    pub fn learn(text: &[u8]) -> Option<Self> {
+        let mut counts: HashMap<[u8; 2], HashMap<u8, u32>> = HashMap::new();
+        let words = text.split(|c| !c.is_ascii_alphabetic()).filter(|w| !w.is_empty());
+        for w in words {
+            // two spaces lead into the first letter, a space ends the word
+            let mut s = b"  ".to_vec();
+            s.extend(w.iter().map(u8::to_ascii_lowercase));
+            s.push(b' ');
+            for t in s.windows(3) {
+                *counts.entry([t[0], t[1]]).or_default().entry(t[2]).or_default() += 1;
+            }
+        }
+        if counts.is_empty() { return None; }
+        let next = counts.into_iter().map(|(ctx, follow)| {
+            let mut follow: Vec<(u8, u32)> = follow.into_iter().collect();
+            follow.sort();
+            let mut total = 0;
+            for f in &mut follow { total += f.1; f.1 = total; }
+            (ctx, follow)
+        }).collect();
+        Some(Trigrams { next })
+    }
+
+    fn step(&self, ctx: [u8; 2], h: u64) -> u8 {
+        match self.next.get(&ctx) {
+            Some(follow) => {
+                let x = (h % follow.last().unwrap().1 as u64) as u32;
+                follow[follow.partition_point(|f| f.1 <= x)].0
+            },
+            None => b' ',
+        }
+    }
+
+    /// `n` characters of words separated by single spaces (trigramString).
+    /// Every word is generated from its own index, in parallel.
+    pub fn text(&self, n: usize, seed: u64) -> Vec<u8> {
+        const WORDS_PER_CHUNK: u64 = 1024;
+        let r = Random::new(seed);
+        let mut out = Vec::with_capacity(n);
+        let mut chunk = 0;
+        while out.len() < n {
+            let words: Vec<Vec<u8>> = (0..WORDS_PER_CHUNK).into_par_iter().map(|w| {
+                let r = r.fork(chunk * WORDS_PER_CHUNK + w);
+                let mut word = vec![];
+                let mut ctx = [b' ', b' '];
+                for i in 0..32 {
+                    let c = self.step(ctx, r.ith_rand(i));
+                    if c == b' ' { break; }
+                    word.push(c);
+                    ctx = [ctx[1], c];
+                }
+                word
+            }).collect();
+            for w in words.into_iter().filter(|w| !w.is_empty()) {
+                out.extend(w);
+                out.push(b' ');
+            }
+            chunk += 1;
+        }
+        out.truncate(n);
+        out
+    }
+}
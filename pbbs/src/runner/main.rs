@@ -0,0 +1,568 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::exit;
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+use pbbs::init;
+use pbbs::generators::Generator;
+use pbbs::DefFloat;
+use pbbs::common::graph::{Graph, WghEdgeArray};
+use pbbs::common::graph_bin::{convert_text_graph, is_binary, write_binary_graph, MappedGraph};
+use pbbs::common::graph_import::{import, ImportFormat, Imported};
+use pbbs::common::graph_io::{
+    read_edge_array_from_file, read_graph_from_file, read_wgh_edge_array_from_file,
+    read_wgh_graph_from_file
+};
+use pbbs::common::graph_utils::{
+    self, edges_to_graph, graph_to_edges, graph_to_wgh_edges, relabeling, wgh_edges_to_graph, Relabel
+};
+use pbbs::generators::graph::{write_weighted_graph, GraphFormat};
+use pbbs::common::io::BadLines;
+use pbbs::common::pool::Pinning;
+use pbbs::common::report::{Record, ReportFormat};
+use pbbs::common::time_loop::TimeConfig;
+use pbbs::runner::{find, registry, Params, RunConfig, Runnable};
+use pbbs::runner::matrix::{self, Cell, Job, Matrix, BASELINE, PROFILES};
+
+pbbs::count_alloc!();
+
+#[derive(Parser, Debug)]
+#[clap(version, about = "Runs any of the pbbs benchmarks", long_about = None)]
+struct Cli {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// List the benchmarks with their algorithms and parameters
+    List,
+
+    /// Run a benchmark on an input file
+    Run(Box<RunArgs>),
+
+    /// Compare the safety profiles: run the same benchmarks with the runner
+    /// built under each of them, and print the slowdowns relative to unsafe
+    Matrix(MatrixArgs),
+
+    /// Generate an input file
+    #[clap(subcommand)]
+    Gen(Generator),
+
+    /// Convert a graph between the PBBS formats and the binary format, which
+    /// every graph benchmark loads by mapping it into memory, import a SNAP,
+    /// Matrix Market or DIMACS graph, and symmetrize, clean or relabel it
+    Convert(ConvertArgs),
+
+    /// Validate an output file that a benchmark wrote, offline, like the
+    /// `*Check` programs of PBBS
+    Check(CheckArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct RunArgs {
+    /// the benchmark to run (see `pbbs list`)
+    benchmark: String,
+
+    /// the input filename
+    #[clap(value_parser, required=true)]
+    ifname: String,
+
+    /// the algorithm to use (defaults to the benchmark's first one)
+    #[clap(short, long)]
+    algorithm: Option<String>,
+
+    /// the output filename
+    #[clap(short, long, required=false, default_value_t = ("").to_string())]
+    ofname: String,
+
+    #[clap(flatten)]
+    time: TimeConfig,
+
+    /// check the output of the last round
+    #[clap(short, long)]
+    check: bool,
+
+    /// a benchmark specific parameter, as name=value
+    #[clap(short, long = "param")]
+    params: Vec<String>,
+
+    /// print a machine-readable record of the run
+    #[clap(long, value_enum)]
+    report: Option<ReportFormat>,
+
+    /// run once per thread count (e.g. 1,2,4,8), each in a fresh pinned
+    /// pool, and report speedup and efficiency
+    #[clap(short, long, value_delimiter = ',')]
+    threads: Vec<usize>,
+
+    /// how to pin worker threads: none, compact, scatter,
+    /// physical-cores-first, or a cpu list such as 0-3,8
+    #[clap(long, default_value_t)]
+    pin: Pinning,
+
+    /// reject input lines that do not parse instead of skipping them
+    #[clap(long)]
+    strict: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct CheckArgs {
+    /// the benchmark that wrote the output (see `pbbs list`)
+    benchmark: String,
+
+    /// the input it was run on
+    ifname: String,
+
+    /// the output to check
+    ofname: String,
+
+    /// a benchmark specific parameter, as name=value
+    #[clap(short, long = "param")]
+    params: Vec<String>,
+}
+
+#[derive(clap::Args, Debug)]
+struct MatrixArgs {
+    /// the runs, as benchmark:input (e.g. mis:input/small_graph)
+    #[clap(required=true)]
+    jobs: Vec<String>,
+
+    /// only run these algorithms (defaults to all of each benchmark's)
+    #[clap(short, long, value_delimiter = ',')]
+    algorithm: Vec<String>,
+
+    /// a benchmark specific parameter, as name=value, given to the
+    /// benchmarks that have it
+    #[clap(short, long = "param")]
+    params: Vec<String>,
+
+    /// the profiles to compare
+    #[clap(long, value_delimiter = ',', default_values_t = PROFILES.map(String::from))]
+    profiles: Vec<String>,
+
+    /// where the runner of each profile is built, as <dir>/<profile>/release/pbbs
+    #[clap(long, default_value = "target/matrix")]
+    target_dir: PathBuf,
+
+    /// use the runners already in --target-dir instead of building them
+    #[clap(long)]
+    no_build: bool,
+
+    /// arguments given to every `pbbs run` (e.g. -- -r 5 --warmup 0)
+    #[clap(last = true)]
+    run_args: Vec<String>,
+}
+
+#[derive(clap::Args, Debug)]
+struct ConvertArgs {
+    /// the graph to convert
+    input: PathBuf,
+
+    /// the graph to write
+    output: PathBuf,
+
+    /// the format of the input: snap (.txt), mtx (.mtx) or dimacs (.gr);
+    /// guessed from the extension, and a PBBS graph otherwise
+    #[clap(long, value_enum)]
+    from: Option<ImportFormat>,
+
+    /// the format to write
+    #[clap(short = 'f', long, value_enum, default_value_t = ConvertTo::Binary)]
+    to: ConvertTo,
+
+    /// add the reverse of every edge, without duplicates
+    #[clap(long)]
+    symmetric: bool,
+
+    /// drop duplicate edges (imported graphs never have any)
+    #[clap(long)]
+    dedup: bool,
+
+    /// drop the edges from a vertex to itself (imported graphs never have
+    /// any)
+    #[clap(long)]
+    no_self_loops: bool,
+
+    /// rename the vertices in this order
+    #[clap(long, value_enum)]
+    relabel: Option<Relabel>,
+
+    /// the seed of --relabel random
+    #[clap(long, default_value_t = 0)]
+    seed: u64,
+
+    /// give every edge weight 1 when writing a weighted format from a graph
+    /// without weights
+    #[clap(long)]
+    unit_weights: bool,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum ConvertTo {
+    /// the binary CSR format
+    Binary,
+    Adjacency,
+    WeightedAdjacency,
+    EdgeArray,
+    WeightedEdgeArray,
+}
+
+/// A graph being converted, with the weight of every edge if its input had
+/// weights.
+enum Converted {
+    Plain(Graph),
+    Weighted(WghEdgeArray),
+}
+
+fn convert(args: ConvertArgs) -> Result<(), String> {
+    let ConvertArgs {
+        input, output, from, to, symmetric, dedup, no_self_loops, relabel, seed, unit_weights
+    } = args;
+    let err = |e: std::io::Error| format!("{}: {e}", input.display());
+    let out_err = |e: std::io::Error| format!("{}: {e}", output.display());
+    let format = match to {
+        ConvertTo::Binary => None,
+        ConvertTo::Adjacency => Some(GraphFormat::Adjacency),
+        ConvertTo::WeightedAdjacency => Some(GraphFormat::WeightedAdjacency),
+        ConvertTo::EdgeArray => Some(GraphFormat::EdgeArray),
+        ConvertTo::WeightedEdgeArray => Some(GraphFormat::WeightedEdgeArray),
+    };
+    let needs_weights = matches!(to, ConvertTo::WeightedAdjacency | ConvertTo::WeightedEdgeArray);
+    let unweighted = || format!(
+        "{} has no weights; pass --unit-weights to give every edge weight 1", input.display()
+    );
+
+    let from = from.or_else(|| ImportFormat::guess(&input));
+    let g = match from {
+        Some(from) => {
+            let g = import(&input, from, symmetric).map_err(err)?;
+            println!("imported n={} m={}", g.n, g.num_edges());
+            if g.weights.is_none() && needs_weights && !unit_weights { return Err(unweighted()); }
+            // the import already dropped self loops and duplicates
+            if relabel.is_none() { return write_imported(&g, format, &output).map_err(out_err); }
+            match g.weights {
+                Some(_) => Converted::Weighted(g.to_wgh_edge_array()),
+                None => Converted::Plain(g.to_graph()),
+            }
+        },
+        None => {
+            let header = first_line(&input).map_err(err)?;
+            let changes = symmetric || dedup || no_self_loops || relabel.is_some();
+            let input = input.to_string_lossy();
+            let weighted = header == "WeightedAdjacencyGraph"
+                || header.is_empty() && MappedGraph::open(&*input).map_err(err)?.weights().is_some();
+            match header.as_str() {
+                "AdjacencyGraph" | "WeightedAdjacencyGraph" if format.is_none() && !changes =>
+                    return convert_text_graph(&*input, &output).map_err(err),
+                "EdgeArray" => Converted::Plain(edges_to_graph(
+                    &read_edge_array_from_file(&input, BadLines::Skip).map_err(|e| e.to_string())?
+                )),
+                "WeightedEdgeArray" => Converted::Weighted(
+                    read_wgh_edge_array_from_file(&input, BadLines::Skip).map_err(|e| e.to_string())?
+                ),
+                _ if weighted => {
+                    let (g, w) = read_wgh_graph_from_file(&input, BadLines::Skip).map_err(|e| e.to_string())?;
+                    Converted::Weighted(graph_to_wgh_edges(&g, &w))
+                },
+                _ => Converted::Plain(read_graph_from_file(&input, BadLines::Skip).map_err(|e| e.to_string())?),
+            }
+        },
+    };
+
+    match g {
+        Converted::Plain(mut g) => {
+            if needs_weights && !unit_weights { return Err(unweighted()); }
+            if no_self_loops { g = graph_utils::remove_self_loops(&g); }
+            if symmetric && from.is_none() { g = graph_utils::symmetrize(&g); }
+            if dedup { g = graph_utils::dedup(&g); }
+            if let Some(order) = relabel { g = graph_utils::relabel(&g, &relabeling(&g, order, seed)); }
+            println!("converted n={} m={}", g.n, g.m);
+            write_converted(&g, None, format, &output)
+        },
+        Converted::Weighted(mut ea) => {
+            if no_self_loops { ea = graph_utils::wgh_remove_self_loops(&ea); }
+            if symmetric && from.is_none() { ea = graph_utils::wgh_symmetrize(&ea); }
+            if dedup { ea = graph_utils::wgh_dedup(&ea); }
+            if let Some(order) = relabel {
+                let label = relabeling(&wgh_edges_to_graph(&ea).0, order, seed);
+                ea = graph_utils::wgh_relabel(&ea, &label);
+            }
+            let (g, w) = wgh_edges_to_graph(&ea);
+            println!("converted n={} m={}", g.n, g.m);
+            write_converted(&g, Some(&w), format, &output)
+        },
+    }.map_err(out_err)
+}
+
+/// Writes a converted graph, with `weights` if it has them (weight 1 in
+/// the weighted text formats otherwise). Fractional weights are kept, or
+/// an error for the integer `WeightedAdjacencyGraph`.
+fn write_converted(
+    g: &Graph,
+    weights: Option<&[DefFloat]>,
+    format: Option<GraphFormat>,
+    output: &Path
+) -> std::io::Result<()> {
+    let weights: Option<Vec<f64>> = weights.map(|w| w.iter().map(|&x| x as f64).collect());
+    match format {
+        None => {
+            write_binary_graph(output, &g.offsets, &g.edges, weights.as_deref())
+        },
+        Some(format) => {
+            let edges: Vec<(u32, u32)> = graph_to_edges(g).es.iter().map(|e| (e.u, e.v)).collect();
+            write_weighted_graph(&edges, g.n, format, weights.as_deref(), output)
+        },
+    }
+}
+
+/// Writes an imported graph, keeping its weights.
+fn write_imported(g: &Imported, format: Option<GraphFormat>, output: &Path) -> std::io::Result<()> {
+    match format {
+        None => {
+            if u32::try_from(g.num_edges()).is_err() {
+                return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!(
+                    "m={} does not fit in the 32 bit offsets of a binary graph", g.num_edges()
+                )));
+            }
+            let offsets: Vec<u32> = g.offsets().into_iter().map(|o| o as u32).collect();
+            let edges: Vec<u32> = g.edges.iter().map(|e| e.1).collect();
+            write_binary_graph(output, &offsets, &edges, g.weights.as_deref())
+        },
+        Some(format) => write_weighted_graph(&g.edges, g.n, format, g.weights.as_deref(), output),
+    }
+}
+
+/// The first line of a text file, or an empty string for a binary graph.
+fn first_line(path: &Path) -> std::io::Result<String> {
+    if is_binary(path) { return Ok(String::new()); }
+    let mut line = String::new();
+    BufReader::new(File::open(path)?).read_line(&mut line)?;
+    Ok(line.trim().to_string())
+}
+
+fn list() {
+    for b in registry() {
+        println!("{:8}{}", b.name(), b.about());
+        println!("        algorithms: {}", b.algorithms().join(", "));
+        if let Some(s) = b.serial() { println!("        serial: {s}"); }
+        for p in b.params() {
+            match p.default {
+                Some(d) => println!("        -p {}={}: {}", p.name, d, p.help),
+                None => println!("        -p {}=<required>: {}", p.name, p.help),
+            }
+        }
+    }
+}
+
+fn fail(msg: String) -> ! {
+    eprintln!("error: {msg}");
+    exit(2);
+}
+
+/// Prints the outcome of a check; exits if it failed.
+fn finish_check(name: &str, check: Option<bool>, asked: bool) {
+    match check {
+        Some(true) => println!("check: OK"),
+        Some(false) => { println!("check: ERR"); exit(1); },
+        None if asked => println!("check: no checker for {name}"),
+        None => {},
+    }
+}
+
+fn sweep(
+    b: &dyn Runnable,
+    cfg: &RunConfig,
+    threads: &[usize],
+    report: Option<ReportFormat>
+) {
+    let s = b.sweep(cfg, threads).unwrap_or_else(|e| fail(e.to_string()));
+    if let Some((alg, stats)) = &s.serial {
+        println!("serial ({alg}):  {:?}", stats.mean);
+    }
+    println!("threads          mean   speedup  efficiency  vs serial");
+    for p in &s.points {
+        println!(
+            "{:7}  {:>12}  {:8.2}  {:10.2}  {:>9}",
+            p.threads,
+            format!("{:.3?}", p.result.stats.mean),
+            p.speedup,
+            p.efficiency,
+            p.serial_speedup.map_or("-".to_string(), |x| format!("{x:.2}"))
+        );
+    }
+
+    if let Some(fmt) = report {
+        let records: Vec<_> = s.points.iter().map(|p| Record {
+            threads: p.threads,
+            speedup: Some(p.speedup),
+            efficiency: Some(p.efficiency),
+            serial_speedup: p.serial_speedup,
+            ..Record::new(
+                b.name(),
+                cfg.algorithm,
+                cfg.ifname,
+                &p.result.stats,
+                p.result.check
+            )
+        }).collect();
+        Record::emit_all(&records, fmt);
+    }
+    for p in &s.points {
+        finish_check(b.name(), p.result.check, cfg.check);
+    }
+}
+
+fn run(args: RunArgs) {
+    let RunArgs {
+        benchmark, ifname, algorithm, ofname, time, check, params, report,
+        threads, pin, strict
+    } = args;
+
+    let b = find(&benchmark).unwrap_or_else(|| fail(format!(
+        "unknown benchmark '{benchmark}', see `pbbs list`"
+    )));
+    let algorithm = algorithm.unwrap_or(b.algorithms()[0].to_string());
+    if !b.algorithms().contains(&algorithm.as_str()) {
+        fail(format!(
+            "unknown algorithm '{algorithm}' for {benchmark}, expected one of: {}",
+            b.algorithms().join(", ")
+        ));
+    }
+    let mut params = Params::parse(b.params(), &params).unwrap_or_else(|e| fail(e));
+    if strict { params.bad_lines = BadLines::Reject; }
+
+    let cfg = RunConfig {
+        algorithm: &algorithm,
+        ifname: &ifname,
+        ofname: &ofname,
+        time,
+        check,
+        params,
+        pin,
+    };
+    if threads.contains(&0) { fail("thread counts must be positive".into()); }
+    if !threads.is_empty() { return sweep(b, &cfg, &threads, report); }
+
+    init!(&cfg.pin);
+
+    let res = b.execute(&cfg).unwrap_or_else(|e| fail(e.to_string()));
+
+    println!("mean:  {:?}", res.stats.mean);
+    println!("{}", res.stats);
+    if let Some(fmt) = report {
+        Record::new(b.name(), &algorithm, &ifname, &res.stats, res.check)
+            .emit(fmt);
+    }
+    finish_check(b.name(), res.check, check);
+}
+
+/// Turns `benchmark:input` specs into one job per selected algorithm.
+fn jobs(args: &MatrixArgs) -> Vec<Job> {
+    let mut jobs = vec![];
+    for spec in &args.jobs {
+        let (name, input) = spec.split_once(':').unwrap_or_else(|| fail(format!(
+            "expected benchmark:input, got '{spec}'"
+        )));
+        let b = find(name).unwrap_or_else(|| fail(format!(
+            "unknown benchmark '{name}', see `pbbs list`"
+        )));
+        let params: Vec<String> = args.params.iter().filter(|kv| {
+            b.params().iter().any(|p| kv.split_once('=').map(|kv| kv.0) == Some(p.name))
+        }).cloned().collect();
+        Params::parse(b.params(), &params).unwrap_or_else(|e| fail(format!("{name}: {e}")));
+
+        let algs: Vec<&str> = b.algorithms().iter().copied()
+            .filter(|a| args.algorithm.is_empty() || args.algorithm.iter().any(|s| s == a))
+            .collect();
+        if algs.is_empty() {
+            fail(format!("none of the algorithms is one of {name}'s"));
+        }
+        for alg in algs {
+            let mut run_args = args.run_args.clone();
+            params.iter().for_each(|kv| run_args.extend(["-p".to_string(), kv.clone()]));
+            jobs.push(Job {
+                benchmark: name.to_string(),
+                algorithm: alg.to_string(),
+                input: input.to_string(),
+                args: run_args,
+            });
+        }
+    }
+    jobs
+}
+
+fn compare(args: MatrixArgs) {
+    for p in &args.profiles {
+        if !PROFILES.contains(&p.as_str()) {
+            fail(format!("unknown profile '{p}', expected one of: {}", PROFILES.join(", ")));
+        }
+    }
+    if !args.profiles.iter().any(|p| p == BASELINE) {
+        fail(format!("the profiles must include {BASELINE}"));
+    }
+    let jobs = jobs(&args);
+    let dir = std::path::absolute(&args.target_dir).unwrap_or_else(|e| fail(e.to_string()));
+
+    let mut cells: Vec<Vec<Cell>> = jobs.iter().map(|_| vec![]).collect();
+    for p in &args.profiles {
+        let bin = if args.no_build { matrix::binary(&dir, p) } else {
+            matrix::build(&dir, p).unwrap_or_else(|e| fail(e))
+        };
+        for (j, job) in jobs.iter().enumerate() {
+            eprintln!("{p}: {} {}", job.benchmark, job.algorithm);
+            cells[j].push(matrix::run(&bin, job).unwrap_or_else(|e| fail(e)));
+        }
+    }
+    print!("{}", Matrix { profiles: args.profiles, jobs, cells });
+}
+
+fn check(args: CheckArgs) {
+    let CheckArgs { benchmark, ifname, ofname, params } = args;
+    let b = find(&benchmark).unwrap_or_else(|| fail(format!(
+        "unknown benchmark '{benchmark}', see `pbbs list`"
+    )));
+    let params = Params::parse(b.params(), &params).unwrap_or_else(|e| fail(e));
+    let check = b.check_file(&ifname, &ofname, &params).unwrap_or_else(|e| fail(e.to_string()));
+    finish_check(&benchmark, check, true);
+}
+
+fn main() {
+    match Cli::parse().command {
+        Command::List => list(),
+        Command::Run(args) => run(*args),
+        Command::Matrix(args) => compare(args),
+        Command::Gen(g) => g.run().unwrap_or_else(|e| fail(e)),
+        Command::Convert(args) => convert(args).unwrap_or_else(|e| fail(e)),
+        Command::Check(args) => check(args),
+    }
+}
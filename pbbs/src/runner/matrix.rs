@@ -0,0 +1,188 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+
+//! Runs the same benchmarks with the `pbbs` runner built under each safety
+//! profile of `pbbs/Cargo.toml`, and compares them to the `unsafe` one.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::Deserialize;
+
+
+/// The feature sets of `pbbs/Cargo.toml`, from the safest to the least safe.
+pub const PROFILES: [&str; 4] = ["default", "synchronized", "interior_unsafe", "unsafe"];
+
+/// The profile every other one is compared to.
+pub const BASELINE: &str = "unsafe";
+
+/// Where the runner built with `profile` lives under `dir`.
+pub fn binary(dir: &Path, profile: &str) -> PathBuf {
+    dir.join(profile).join("release").join("pbbs")
+}
+
+/// Builds the runner with only the features of `profile`, into its own
+/// target directory under `dir`, and returns its path.
+pub fn build(dir: &Path, profile: &str) -> Result<PathBuf, String> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+    let cargo = std::env::var("CARGO").unwrap_or("cargo".to_string());
+    let status = Command::new(cargo)
+        .current_dir(root)
+        .args(["build", "--release", "-p", "pbbs", "--bin", "pbbs"])
+        .args(["--no-default-features", "--features", profile])
+        .arg("--target-dir")
+        .arg(dir.join(profile))
+        .status()
+        .map_err(|e| format!("cannot run cargo: {e}"))?;
+    if !status.success() {
+        return Err(format!("building the {profile} profile failed"));
+    }
+    Ok(binary(dir, profile))
+}
+
+/// One benchmark, algorithm and input to run in every profile.
+#[derive(Clone, Debug)]
+pub struct Job {
+    pub benchmark: String,
+    pub algorithm: String,
+    pub input: String,
+    /// Extra arguments of `pbbs run` (e.g. `-r 5` or `-p buckets=256`).
+    pub args: Vec<String>,
+}
+
+/// The part of a `--report json` record the matrix needs.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Cell {
+    pub features: Vec<String>,
+    pub mean: f64,
+    pub ci95: f64,
+    pub check: Option<bool>,
+}
+
+impl Cell {
+    /// Finds the record in the output of `pbbs run --report json`.
+    pub fn parse(stdout: &str) -> Result<Self, String> {
+        let line = stdout
+            .lines()
+            .rev()
+            .find(|l| l.starts_with('{'))
+            .ok_or("no report in the output")?;
+        serde_json::from_str(line).map_err(|e| format!("bad report: {e}"))
+    }
+}
+
+/// Runs `job` with the runner at `bin`.
+pub fn run(bin: &Path, job: &Job) -> Result<Cell, String> {
+    let out = Command::new(bin)
+        .args(["run", &job.benchmark, &job.input, "-a", &job.algorithm])
+        .args(["--report", "json"])
+        .args(&job.args)
+        .output()
+        .map_err(|e| format!("cannot run {}: {e}", bin.display()))?;
+    Cell::parse(&String::from_utf8_lossy(&out.stdout)).map_err(|e| format!(
+        "{} {} ({}): {e}\n{}",
+        job.benchmark,
+        job.algorithm,
+        bin.display(),
+        String::from_utf8_lossy(&out.stderr)
+    ))
+}
+
+/// The results of every job (rows) in every profile (columns).
+pub struct Matrix {
+    pub profiles: Vec<String>,
+    pub jobs: Vec<Job>,
+    pub cells: Vec<Vec<Cell>>,
+}
+
+impl Matrix {
+    fn baseline(&self) -> usize {
+        self.profiles.iter().position(|p| p == BASELINE).unwrap()
+    }
+
+    /// How much slower job `j` is in profile `p` than in the baseline.
+    pub fn slowdown(&self, j: usize, p: usize) -> f64 {
+        self.cells[j][p].mean / self.cells[j][self.baseline()].mean
+    }
+
+    /// The geometric mean slowdown of each benchmark's algorithms, per
+    /// profile, in order of first appearance.
+    pub fn by_benchmark(&self) -> Vec<(&str, Vec<f64>)> {
+        let mut names: Vec<&str> = vec![];
+        for j in &self.jobs {
+            if !names.contains(&j.benchmark.as_str()) { names.push(&j.benchmark); }
+        }
+        names.into_iter().map(|b| {
+            let js: Vec<usize> = (0..self.jobs.len())
+                .filter(|&j| self.jobs[j].benchmark == b)
+                .collect();
+            let geo = (0..self.profiles.len()).map(|p| {
+                let logs: f64 = js.iter().map(|&j| self.slowdown(j, p).ln()).sum();
+                (logs / js.len() as f64).exp()
+            }).collect();
+            (b, geo)
+        }).collect()
+    }
+}
+
+/// A table of slowdowns relative to [`BASELINE`], whose column gives the
+/// mean time instead, per algorithm and then per benchmark.
+impl fmt::Display for Matrix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let base = self.baseline();
+        let width = |p: &String| p.len().max(10);
+        write!(f, "{:10} {:12}", "benchmark", "algorithm")?;
+        for p in &self.profiles { write!(f, " {:>w$}", p, w = width(p))?; }
+        writeln!(f)?;
+        for (j, job) in self.jobs.iter().enumerate() {
+            write!(f, "{:10} {:12}", job.benchmark, job.algorithm)?;
+            for (p, name) in self.profiles.iter().enumerate() {
+                let cell = &self.cells[j][p];
+                let mut s = if p == base {
+                    format!("{:.3?}", std::time::Duration::from_secs_f64(cell.mean))
+                } else {
+                    format!("{:.2}x", self.slowdown(j, p))
+                };
+                if cell.check == Some(false) { s.push_str(" ERR"); }
+                write!(f, " {:>w$}", s, w = width(name))?;
+            }
+            writeln!(f)?;
+        }
+        if self.jobs.len() > 1 {
+            writeln!(f)?;
+            for (b, geo) in self.by_benchmark() {
+                write!(f, "{:10} {:12}", b, "(geomean)")?;
+                for (p, name) in self.profiles.iter().enumerate() {
+                    write!(f, " {:>w$}", format!("{:.2}x", geo[p]), w = width(name))?;
+                }
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,346 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Debug, Display};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::common::io::{BadLines, InputError};
+use crate::common::pool::{pinned_pool, Pinning};
+use crate::common::time_loop::{time_loop, RunStats, TimeConfig};
+use crate::benchmarks::*;
+
+pub mod matrix;
+
+
+/// An extra, benchmark specific, command line parameter (e.g. bfs' `source`).
+pub struct Param {
+    pub name: &'static str,
+    /// `None` makes the parameter mandatory.
+    pub default: Option<&'static str>,
+    pub help: &'static str,
+    /// Accepts or refuses a value, e.g. [`parses::<usize>`](parses).
+    pub valid: fn(&str) -> Result<(), String>,
+}
+
+/// A [`Param::valid`] for values that parse as `T`.
+pub fn parses<T>(v: &str) -> Result<(), String>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Display,
+{
+    v.parse::<T>().map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// The values of a benchmark's [`Param`]s, given as `-p name=value`.
+pub struct Params {
+    vals: HashMap<&'static str, String>,
+    /// What the input readers do with lines that do not parse.
+    pub bad_lines: BadLines,
+}
+
+impl Params {
+    /// Resolves `given` (`name=value` pairs) against the declared `params`,
+    /// and checks every value, so that [`Params::get`] cannot fail on them.
+    pub fn parse(params: &[Param], given: &[String]) -> Result<Self, String> {
+        let mut vals = HashMap::new();
+        for kv in given {
+            let (k, v) = kv
+                .split_once('=')
+                .ok_or(format!("expected name=value, got '{kv}'"))?;
+            let p = params
+                .iter()
+                .find(|p| p.name == k)
+                .ok_or(format!("unknown parameter '{k}'"))?;
+            vals.insert(p.name, v.to_string());
+        }
+        for p in params {
+            if vals.contains_key(p.name) { continue; }
+            match p.default {
+                Some(d) => { vals.insert(p.name, d.to_string()); },
+                None => return Err(format!("missing parameter '{}'", p.name)),
+            }
+        }
+        for p in params {
+            let v = &vals[p.name];
+            (p.valid)(v).map_err(|e| format!("bad value '{v}' for '{}': {e}", p.name))?;
+        }
+        Ok(Self { vals, bad_lines: BadLines::default() })
+    }
+
+    /// Returns the value of `name` parsed as `T`, which has to be the type
+    /// its [`Param::valid`] accepts.
+    pub fn get<T>(&self, name: &str) -> T
+    where
+        T: FromStr,
+        <T as FromStr>::Err: Debug,
+    {
+        self.vals[name]
+            .parse()
+            .unwrap_or_else(|e| panic!("bad value for '{name}': {e:?}"))
+    }
+}
+
+/// A benchmark: how to read its input, its algorithm variants, and how to
+/// check and write its output. Implementing this trait and adding the
+/// benchmark to [`registry`] is all the `pbbs` runner needs.
+pub trait Benchmark: Sync {
+    type Input: Sync;
+    type Output: Send;
+
+    /// The name used on the command line, e.g. `bfs`.
+    fn name(&self) -> &'static str;
+
+    /// A one line description.
+    fn about(&self) -> &'static str;
+
+    /// The algorithm variants; the first one is the default.
+    fn algorithms(&self) -> &'static [&'static str];
+
+    /// The sequential variant among [`Benchmark::algorithms`], if any, that
+    /// thread sweeps compare against.
+    fn serial(&self) -> Option<&'static str> {
+        self.algorithms()
+            .iter()
+            .find(|&&a| a == "serial" || a == "sequential")
+            .copied()
+    }
+
+    /// Extra parameters of this benchmark.
+    fn params(&self) -> &'static [Param] { &[] }
+
+    fn read_input(&self, fname: &str, ps: &Params) -> Result<Self::Input, InputError>;
+
+    /// Reads back what [`Benchmark::write`] wrote, for `pbbs check`.
+    fn read_output(
+        &self,
+        fname: &str,
+        inp: &Self::Input,
+        ps: &Params
+    ) -> Result<Self::Output, InputError>;
+
+    /// Creates the output that the first round writes into.
+    fn output(&self, inp: &Self::Input, ps: &Params) -> Self::Output;
+
+    /// Prepares `out` for the next round. Not timed.
+    fn reset(
+        &self,
+        _alg: &str,
+        _inp: &Self::Input,
+        _ps: &Params,
+        _out: &mut Self::Output
+    ) {}
+
+    /// Runs algorithm `alg` once. Timed.
+    fn run(
+        &self,
+        alg: &str,
+        inp: &Self::Input,
+        ps: &Params,
+        out: &mut Self::Output
+    );
+
+    /// Validates `out` against `inp`; `None` if there is no checker.
+    fn check(
+        &self,
+        _inp: &Self::Input,
+        _out: &Self::Output,
+        _ps: &Params
+    ) -> Option<bool> { None }
+
+    fn write(&self, out: &Self::Output, fname: &str);
+}
+
+/// What the runner was asked to do with a benchmark.
+pub struct RunConfig<'a> {
+    pub algorithm: &'a str,
+    pub ifname: &'a str,
+    pub ofname: &'a str,
+    pub time: TimeConfig,
+    pub check: bool,
+    pub params: Params,
+    /// Thread placement of the pools of [`Runnable::sweep`].
+    pub pin: Pinning,
+}
+
+/// The outcome of [`Runnable::execute`].
+pub struct RunResult {
+    pub stats: RunStats,
+    pub check: Option<bool>,
+}
+
+/// One thread count of a [`Runnable::sweep`].
+pub struct SweepPoint {
+    pub threads: usize,
+    pub result: RunResult,
+    /// The 1-thread mean over this one.
+    pub speedup: f64,
+    /// `speedup / threads`.
+    pub efficiency: f64,
+    /// The serial variant's mean over this one, if there is such a variant.
+    pub serial_speedup: Option<f64>,
+}
+
+/// The outcome of [`Runnable::sweep`].
+pub struct Sweep {
+    /// The serial variant and its 1-thread stats, if there is one.
+    pub serial: Option<(&'static str, RunStats)>,
+    pub points: Vec<SweepPoint>,
+}
+
+/// The object-safe face of [`Benchmark`] that the registry stores.
+pub trait Runnable: Sync {
+    fn name(&self) -> &'static str;
+    fn about(&self) -> &'static str;
+    fn algorithms(&self) -> &'static [&'static str];
+    fn serial(&self) -> Option<&'static str>;
+    fn params(&self) -> &'static [Param];
+
+    /// Runs `cfg` in the current rayon pool.
+    fn execute(&self, cfg: &RunConfig) -> Result<RunResult, InputError>;
+
+    /// Runs `cfg` in a fresh pinned pool of each of the `threads` sizes, and
+    /// measures the 1-thread and serial baselines. Fails if the input cannot
+    /// be read or the threads cannot be pinned.
+    fn sweep(&self, cfg: &RunConfig, threads: &[usize]) -> Result<Sweep, Box<dyn Error>>;
+
+    /// Validates the output file `ofname` against the input `ifname`, as
+    /// the `*Check` programs of PBBS do; `None` if there is no checker.
+    fn check_file(&self, ifname: &str, ofname: &str, ps: &Params) -> Result<Option<bool>, InputError>;
+}
+
+/// Times `alg` on `inp` as `cfg` says and returns the last round's output.
+fn measure<B: Benchmark>(
+    b: &B,
+    alg: &str,
+    inp: &B::Input,
+    cfg: &RunConfig
+) -> (RunStats, B::Output) {
+    let ps = &cfg.params;
+    let out = RefCell::new(b.output(inp, ps));
+    let stats = time_loop(
+        b.name(),
+        &cfg.time,
+        || { b.reset(alg, inp, ps, &mut out.borrow_mut()); },
+        || { b.run(alg, inp, ps, &mut out.borrow_mut()); },
+        || {}
+    );
+    (stats, out.into_inner())
+}
+
+impl<B: Benchmark> Runnable for B {
+    fn name(&self) -> &'static str { Benchmark::name(self) }
+    fn about(&self) -> &'static str { Benchmark::about(self) }
+    fn algorithms(&self) -> &'static [&'static str] {
+        Benchmark::algorithms(self)
+    }
+    fn serial(&self) -> Option<&'static str> { Benchmark::serial(self) }
+    fn params(&self) -> &'static [Param] { Benchmark::params(self) }
+
+    fn execute(&self, cfg: &RunConfig) -> Result<RunResult, InputError> {
+        let ps = &cfg.params;
+        let inp = self.read_input(cfg.ifname, ps)?;
+        let (stats, out) = measure(self, cfg.algorithm, &inp, cfg);
+
+        let check = if cfg.check { self.check(&inp, &out, ps) } else { None };
+        if !cfg.ofname.is_empty() { self.write(&out, cfg.ofname); }
+        Ok(RunResult { stats, check })
+    }
+
+    fn sweep(&self, cfg: &RunConfig, threads: &[usize]) -> Result<Sweep, Box<dyn Error>> {
+        let (alg, ps) = (cfg.algorithm, &cfg.params);
+        let inp = self.read_input(cfg.ifname, ps)?;
+
+        let mut runs = vec![];
+        let mut last = None;
+        for &p in threads {
+            let (stats, out) = pinned_pool(p, &cfg.pin)?.install(|| measure(self, alg, &inp, cfg));
+            let check = if cfg.check { self.check(&inp, &out, ps) } else { None };
+            runs.push((p, RunResult { stats, check }));
+            last = Some(out);
+        }
+        if let Some(out) = last.filter(|_| !cfg.ofname.is_empty()) {
+            self.write(&out, cfg.ofname);
+        }
+
+        let one = pinned_pool(1, &cfg.pin)?;
+        let t1 = match runs.iter().find(|r| r.0 == 1) {
+            Some(r) => r.1.stats.mean,
+            None => one.install(|| measure(self, alg, &inp, cfg)).0.mean,
+        };
+        let serial = Benchmark::serial(self).map(|s| {
+            (s, one.install(|| measure(self, s, &inp, cfg)).0)
+        });
+
+        let ratio = |a: Duration, b: Duration| a.as_secs_f64() / b.as_secs_f64();
+        let points = runs.into_iter().map(|(p, result)| {
+            let speedup = ratio(t1, result.stats.mean);
+            SweepPoint {
+                threads: p,
+                speedup,
+                efficiency: speedup / p as f64,
+                serial_speedup: serial.as_ref().map(|s| ratio(s.1.mean, result.stats.mean)),
+                result,
+            }
+        }).collect();
+        Ok(Sweep { serial, points })
+    }
+
+    fn check_file(&self, ifname: &str, ofname: &str, ps: &Params) -> Result<Option<bool>, InputError> {
+        let inp = self.read_input(ifname, ps)?;
+        let out = self.read_output(ofname, &inp, ps)?;
+        Ok(self.check(&inp, &out, ps))
+    }
+}
+
+/// All the benchmarks known to the `pbbs` runner.
+pub fn registry() -> Vec<&'static dyn Runnable> {
+    vec![
+        &bfs::bench::Bfs,
+        &bw_decode::bench::BwDecode,
+        &comparison_sort::bench::ComparisonSort,
+        &delaunay_refine::bench::DelaunayRefine,
+        &histogram::bench::Histogram,
+        &integer_sort::bench::IntegerSort,
+        &longest_repeated_sub_string::bench::LongestRepeatedSubString,
+        &maximal_independent_set::bench::MaximalIndependentSet,
+        &maximal_matching::bench::MaximalMatching,
+        &min_span_forest::bench::MinSpanForest,
+        &nearest_neighbors::bench::NearestNeighbors,
+        &remove_duplicates::bench::RemoveDuplicates,
+        &spanning_forest::bench::SpanningForest,
+        &suffix_array::bench::SuffixArray,
+        &word_counts::bench::WordCounts,
+    ]
+}
+
+/// Looks a benchmark up by its command line name.
+pub fn find(name: &str) -> Option<&'static dyn Runnable> {
+    registry().into_iter().find(|b| b.name() == name)
+}
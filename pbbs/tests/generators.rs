@@ -0,0 +1,223 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+
+use pbbs::common::graph_io::{read_graph_from_file, read_wgh_edge_array_from_file};
+use pbbs::common::io::{read_file_to_vec, BadLines};
+use pbbs::common::geometry::{counter_clock_wise, in_circle};
+use pbbs::common::geometry_io::read_points3d_from_file;
+use pbbs::generators::geometry::*;
+use pbbs::generators::graph::*;
+use pbbs::generators::seq::*;
+use pbbs::generators::write_sequence;
+
+
+#[test]
+fn deterministic() {
+    assert_eq!(random_ints(1000, 50, 1), random_ints(1000, 50, 1));
+    assert_ne!(random_ints(1000, 50, 1), random_ints(1000, 50, 2));
+    assert_eq!(expt_doubles(100, 3), expt_doubles(100, 3));
+    let t = Trigrams::learn(CORPUS.as_bytes()).unwrap();
+    assert_eq!(t.text(5000, 7), t.text(5000, 7));
+}
+
+#[test]
+fn ranges() {
+    assert!(random_ints(10_000, 17, 0).iter().all(|&x| x < 17));
+    assert!(random_doubles(10_000, 0).iter().all(|&x| (0.0..1.0).contains(&x)));
+    assert!(random_pairs(1000, 5, 0).iter().all(|&(a, b)| a < 5 && b < 5));
+    assert!(expt_ints(10_000, 100, 0).iter().all(|&x| x < 100));
+    assert_eq!(all_equal(3, 9), [9, 9, 9]);
+}
+
+#[test]
+fn expt_is_skewed() {
+    let n = 1 << 16;
+    let mut a = expt_ints(n, u64::MAX, 0);
+    a.sort();
+    let top = a.chunk_by(|x, y| x == y).map(|c| c.len()).max().unwrap();
+    a.dedup();
+    // uniform keys would be ~63% distinct and hardly ever repeat
+    assert!(a.len() < n / 2);
+    assert!(top > n / 32);
+}
+
+#[test]
+fn almost_sorted_is_a_permutation() {
+    let a = almost_sorted(10_000, 100, 0);
+    let mut s = a.clone();
+    s.sort();
+    assert_eq!(s, (0..10_000).collect::<Vec<u64>>());
+    let displaced = a.iter().enumerate().filter(|&(i, &x)| i as u64 != x).count();
+    assert!(displaced > 0 && displaced <= 200);
+}
+
+#[test]
+fn trigram_text() {
+    let t = Trigrams::learn(b"Abc, abd! ABE").unwrap();
+    let s = t.text(1000, 0);
+    assert_eq!(s.len(), 1000);
+    assert!(s.split(|&c| c == b' ').filter(|w| !w.is_empty())
+        .all(|w| w.len() == 3 && w.starts_with(b"ab")));
+    assert!(Trigrams::learn(b"123 ...").is_none());
+}
+
+#[test]
+fn sequence_file() {
+    let path = std::env::temp_dir().join(format!("pbbs_gen_{}", std::process::id()));
+    let a = random_ints(1000, 1 << 20, 0);
+    write_sequence(&a, "sequenceInt", &path).unwrap();
+    let b: Vec<u64> = read_file_to_vec(&path, Some("sequenceInt"), BadLines::Skip).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(a, b);
+}
+
+fn tmp(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("pbbs_gen_{name}_{}", std::process::id()))
+}
+
+#[test]
+fn rmat_graph() {
+    let es = rmat(10, 20_000, 0.5, 0.1, 0.1, 0);
+    assert!(es.iter().all(|&(u, v)| u < 1024 && v < 1024));
+    assert_eq!(es, rmat(10, 20_000, 0.5, 0.1, 0.1, 0));
+    // quadrant a gets half of the edges at every level
+    let low = es.iter().filter(|&&(u, v)| u < 512 && v < 512).count();
+    assert!((9_000..11_000).contains(&low));
+}
+
+#[test]
+fn rand_local_graph() {
+    let n = 100_000;
+    let es = rand_local(n, 5, 3, 0);
+    assert_eq!(es.len(), 5 * n);
+    assert!(es.iter().enumerate().all(|(k, &(u, v))| u as usize == k / 5 && u != v));
+    let near = es.iter().filter(|&&(u, v)| (v as usize + n - u as usize) % n < 1 << 8).count();
+    assert!(near > es.len() / 2);
+}
+
+#[test]
+fn grid_graph() {
+    let (n, es) = grid(1000, 3);
+    assert_eq!(n, 1000);
+    let es = clean(es, true);
+    assert_eq!(es.len(), 6 * n);
+    assert!(es.contains(&(9, 0)) && es.contains(&(0, 90)) && es.contains(&(0, 900)));
+}
+
+#[test]
+fn cleaning() {
+    let es = clean(vec![(1, 2), (2, 2), (1, 2), (0, 1)], true);
+    assert_eq!(es, [(0, 1), (1, 0), (1, 2), (2, 1)]);
+    assert_eq!(clean(vec![(1, 2), (1, 2)], false), [(1, 2)]);
+}
+
+#[test]
+fn graph_files() {
+    let es = clean(rand_local(1000, 4, 2, 1), true);
+
+    let path = tmp("adj");
+    write_graph(&es, 1000, GraphFormat::Adjacency, 0, 0, &path).unwrap();
+    let g = read_graph_from_file(path.to_str().unwrap(), BadLines::Skip).unwrap();
+    assert_eq!((g.n, g.m), (1000, es.len()));
+    assert_eq!(g.edges.iter().map(|&v| v as usize).sum::<usize>(),
+        es.iter().map(|e| e.1 as usize).sum::<usize>());
+    assert_eq!(g.offsets[1] as usize, es.iter().filter(|e| e.0 == 0).count());
+
+    write_graph(&es, 1000, GraphFormat::WeightedEdgeArray, 0, 3, &path).unwrap();
+    let wes = read_wgh_edge_array_from_file(path.to_str().unwrap(), BadLines::Skip).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(wes.m, es.len());
+    // both directions of an edge weigh the same
+    let w = |u, v| (0..wes.m).map(|i| wes[i]).find(|e| (e.u, e.v) == (u, v)).unwrap().w;
+    let (u, v) = es[0];
+    assert_eq!(w(u, v), w(v, u));
+    assert!((0..wes.m).all(|i| (0.0..1.0).contains(&wes[i].w)));
+}
+
+#[test]
+fn point_shapes() {
+    let len = |x: f64, y: f64, z: f64| (x * x + y * y + z * z).sqrt();
+    let p = points2d(1000, PointDist::InCube, 0);
+    assert!(p.iter().all(|p| p.x.abs() <= 1.0 && p.y.abs() <= 1.0));
+    let p = points3d(1000, PointDist::InSphere, 0);
+    assert!(p.iter().all(|p| len(p.x, p.y, p.z) <= 1.0));
+    let p = points3d(1000, PointDist::OnSphere, 0);
+    assert!(p.iter().all(|p| (len(p.x, p.y, p.z) - 1.0).abs() < 1e-9));
+    // half the mass of a Plummer sphere lies within a radius of ~1.3
+    let p = points3d(10_000, PointDist::Plummer, 0);
+    let inner = p.iter().filter(|p| len(p.x, p.y, p.z) < 1.3).count();
+    assert!((4000..6000).contains(&inner));
+
+    let path = tmp("points");
+    write_sequence(&p, "pbbs_sequencePoint3d", &path).unwrap();
+    let q = read_points3d_from_file::<f64>(path.to_str().unwrap(), BadLines::Skip).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(q.len(), p.len());
+    assert_eq!((q[7].x, q[7].z), (p[7].x, p[7].z));
+}
+
+#[test]
+fn delaunay_triangulation() {
+    let n = 500;
+    let p = points2d(n, PointDist::Kuzmin, 3);
+    let t = delaunay(&p);
+    let pt = |i: i32| p[i as usize];
+    assert!(t.iter().all(|t| counter_clock_wise(pt(t[0]), pt(t[1]), pt(t[2]))));
+    // no point lies inside the circumcircle of a triangle
+    for t in &t {
+        assert!(p.iter().all(|&q| !in_circle(pt(t[0]), pt(t[1]), pt(t[2]), q)));
+    }
+    // every edge borders two triangles, except for the h on the hull, and
+    // a triangulation of n points has 2n - 2 - h triangles
+    let mut es: Vec<_> = t.iter()
+        .flat_map(|t| (0..3).map(move |i| {
+            let (a, b) = (t[i], t[(i + 1) % 3]);
+            (a.min(b), a.max(b))
+        }))
+        .collect();
+    es.sort();
+    let hull = es.chunk_by(|a, b| a == b).filter(|c| c.len() == 1).count();
+    assert!(es.chunk_by(|a, b| a == b).all(|c| c.len() <= 2));
+    assert_eq!(t.len(), 2 * n - 2 - hull);
+}
+
+#[test]
+fn generated_mesh_refines() {
+    // dr has to finish on what `gen triangles` writes, including the skinny
+    // triangles along the hull that need more than its first vertex budget
+    let pbbs = |args: &[&str]| std::process::Command::new(env!("CARGO_BIN_EXE_pbbs"))
+        .args(args).output().unwrap();
+    for seed in 1..=3 {
+        let f = tmp(&format!("refine_{seed}"));
+        let f = f.to_str().unwrap();
+        assert!(pbbs(&["gen", "triangles", "2000", "-s", &seed.to_string(), f]).status.success());
+        let out = pbbs(&["run", "dr", f, "--warmup", "0", "-r", "1", "--check"]);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+        assert!(stdout.contains("check: OK"), "{stdout}");
+    }
+}
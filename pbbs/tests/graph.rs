@@ -0,0 +1,415 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use pbbs::common::graph::{Edge, EdgeArray, Graph, GraphBuf, WghEdge, WghEdgeArray};
+use pbbs::common::graph_bin::*;
+use pbbs::common::graph_import::*;
+use pbbs::common::graph_io::{read_graph_from_file, read_wgh_edge_array_from_file, read_wgh_graph_from_file};
+use pbbs::common::graph_utils::*;
+use pbbs::common::io::BadLines;
+use pbbs::benchmarks::spanning_forest::{serial_sf, incremental_sf};
+use pbbs::benchmarks::maximal_independent_set::{serial_mis, rusty_incremental_mis};
+use pbbs::benchmarks::bfs::{check::is_bfs_tree, det_bfs, dir_opt_bfs, nd_bfs};
+
+
+/// A symmetric ring over `n` vertices in the `AdjacencyGraph` layout.
+fn ring(n: usize) -> Graph {
+    let offsets: Vec<u32> = (0..=n).map(|i| (2 * i) as u32).collect();
+    let edges: Vec<u32> = (0..n)
+        .flat_map(|i| [((i + n - 1) % n) as u32, ((i + 1) % n) as u32])
+        .collect();
+    Graph::new(&offsets, &edges, n)
+}
+
+fn is_mis(g: &Graph, selected: &[u8]) -> bool {
+    (0..g.n).all(|v| {
+        let ngh = g.index(v).neighbors;
+        if selected[v] == 1 {
+            ngh.iter().all(|&u| selected[u as usize] != 1)
+        } else {
+            ngh.iter().any(|&u| selected[u as usize] == 1)
+        }
+    })
+}
+
+
+#[test]
+fn mis_on_ring() {
+    let g = ring(1000);
+    assert!(is_mis(&g, &serial_mis::maximal_independent_set(&g)));
+    assert!(is_mis(&g, &rusty_incremental_mis::maximal_independent_set(&g)));
+}
+
+
+#[test]
+fn sf_on_two_paths() {
+    // two disjoint paths over [0, 5000) and [5000, 10000), each closed
+    // into a cycle by one redundant edge
+    let n = 10000;
+    let mut es: Vec<Edge> = (0..n - 1)
+        .filter(|&i| i != n / 2 - 1)
+        .map(|i| Edge::new(i, i + 1))
+        .collect();
+    es.push(Edge::new(0, n / 2 - 1));
+    es.push(Edge::new(n / 2, n - 1));
+    let ea = EdgeArray::new(es, n as usize, n as usize);
+
+    assert_eq!(serial_sf::spanning_forest(&ea).len(), n as usize - 2);
+    assert_eq!(incremental_sf::spanning_forest(&ea).len(), n as usize - 2);
+}
+
+
+#[test]
+fn bfs_trees() {
+    let g = ring(1000);
+    for bfs in [nd_bfs::bfs, dir_opt_bfs::bfs, det_bfs::bfs] {
+        let parents = bfs(0, &g, false);
+        assert!(is_bfs_tree(&g, &parents, 0));
+        assert_eq!((parents[1], parents[999], parents[499]), (0, 0, 498));
+    }
+
+    // two random halves with no edges between them, dense enough for
+    // bottom-up steps
+    let n = 20000u32;
+    let ea = EdgeArray::new(
+        (0..200_000u64).map(|i| {
+            let h = i.wrapping_mul(0x9E3779B97F4A7C15);
+            let (u, v) = ((h >> 40) as u32 % (n / 2), (h >> 12) as u32 % (n / 2));
+            if i % 2 == 0 { Edge::new(u, v) } else { Edge::new(u + n / 2, v + n / 2) }
+        }).collect(),
+        n as usize, n as usize,
+    );
+    let g = symmetrize(&edges_to_graph(&ea));
+    for bfs in [nd_bfs::bfs, dir_opt_bfs::bfs, det_bfs::bfs] {
+        let mut parents = bfs(1, &g, false);
+        assert!(is_bfs_tree(&g, &parents, 1));
+        assert!(parents[n as usize / 2..].iter().all(|&p| p == -1));
+
+        // a vertex two levels down, hung from the source instead
+        let v = (0..n as usize / 2)
+            .find(|&v| parents[v] >= 0 && parents[parents[v] as usize] as usize != 1
+                && parents[v] as usize != 1)
+            .unwrap();
+        parents[v] = 1;
+        assert!(!is_bfs_tree(&g, &parents, 1));
+    }
+}
+
+
+#[test]
+fn deterministic_bfs() {
+    let n = 5000u32;
+    let ea = EdgeArray::new(
+        (0..50_000u64).map(|i| {
+            let h = i.wrapping_mul(0x9E3779B97F4A7C15);
+            Edge::new((h >> 40) as u32 % n, (h >> 12) as u32 % n)
+        }).collect(),
+        n as usize, n as usize,
+    );
+    let g = symmetrize(&edges_to_graph(&ea));
+    let parents: Vec<Vec<i32>> = [1, 2, 4, 8]
+        .into_iter()
+        .map(|t| {
+            let pool = rayon::ThreadPoolBuilder::new().num_threads(t).build().unwrap();
+            pool.install(|| det_bfs::bfs(0, &g, false))
+        })
+        .collect();
+    assert!(is_bfs_tree(&g, &parents[0], 0));
+    assert!(parents.windows(2).all(|w| w[0] == w[1]));
+
+    // the second level is found from the source's neighbors, in adjacency
+    // order, and each of its vertices hangs from the first one it can
+    let p = &parents[0];
+    let first: Vec<u32> = g.index(0).neighbors.iter().copied().filter(|&v| v != 0).collect();
+    for &u in &first {
+        for &w in g.index(u as usize).neighbors {
+            if w != 0 && p[w as usize] != 0 {
+                let expected = first.iter()
+                    .find(|&&x| g.index(x as usize).neighbors.contains(&w))
+                    .unwrap();
+                assert_eq!(p[w as usize], *expected as i32);
+            }
+        }
+    }
+}
+
+
+#[test]
+fn binary_graph() {
+    let dir = std::env::temp_dir();
+    let (text, bin) = (
+        dir.join(format!("pbbs_graph_{}", std::process::id())),
+        dir.join(format!("pbbs_graph_{}.bin", std::process::id())),
+    );
+    // a weighted triangle plus an isolated vertex
+    std::fs::write(&text, "WeightedAdjacencyGraph\n4\n6\n0\n2\n4\n6\n\
+        1\n2\n0\n2\n0\n1\n5\n6\n5\n7\n6\n7\n").unwrap();
+    convert_text_graph(&text, &bin).unwrap();
+    assert!(is_binary(&bin) && !is_binary(&text));
+
+    let g = MappedGraph::open(&bin).unwrap();
+    assert_eq!((g.n, g.m, g.weighted), (4, 6, true));
+    assert_eq!(g.offsets(), [0, 2, 4, 6, 6]);
+    assert_eq!(g.edges(), [1, 2, 0, 2, 0, 1]);
+    assert_eq!(g.weights(), Some(Weights::Int(&[5, 6, 5, 7, 6, 7])));
+
+    // the graph borrows the mapping rather than copying it
+    let g = read_graph_from_file(bin.to_str().unwrap(), BadLines::Skip).unwrap();
+    assert!(matches!(g.offsets, GraphBuf::Mapped { .. }) && matches!(g.edges, GraphBuf::Mapped { .. }));
+    assert_eq!(g.index(2).neighbors, [0, 1]);
+    assert_eq!(g.index(3).degree, 0);
+
+    let g = MappedGraph::open(&bin).unwrap();
+    assert_eq!(g.neighbors(1), [0, 2]);
+
+    // fractional weights are stored as floats rather than rounded
+    write_binary_graph(&bin, &[0, 1, 2], &[1, 0], Some(&[0.5, 2.0])).unwrap();
+    let g = MappedGraph::open(&bin).unwrap();
+    assert_eq!(g.weights(), Some(Weights::Float(&[0.5, 2.0])));
+    let (_, w) = read_wgh_graph_from_file(bin.to_str().unwrap(), BadLines::Skip).unwrap();
+    assert_eq!(w, [0.5, 2.0]);
+    convert_text_graph(&text, &bin).unwrap();
+
+    // a truncated or corrupt file is rejected rather than read out of bounds
+    let bytes = std::fs::read(&bin).unwrap();
+    std::fs::write(&bin, &bytes[..bytes.len() - 4]).unwrap();
+    assert!(MappedGraph::open(&bin).is_err());
+    let corrupt = |at: usize, v: &[u8]| {
+        let mut b = bytes.clone();
+        b[at..at + v.len()].copy_from_slice(v);
+        std::fs::write(&bin, b).unwrap();
+        MappedGraph::open(&bin).err().unwrap().to_string()
+    };
+    // the offsets start at byte 32 and the edges at 32 + 4 * 5
+    assert!(corrupt(32 + 4, &9u32.to_le_bytes()).contains("decrease"));
+    assert!(corrupt(32 + 4 * 4, &5u32.to_le_bytes()).contains("0 to m"));
+    assert!(corrupt(52 + 4, &4u32.to_le_bytes()).contains("not a vertex"));
+    assert!(corrupt(16, &u64::MAX.to_le_bytes()).contains("does not take"));
+    std::fs::write(&text, "AdjacencyGraph\n2\n1\n0\n").unwrap();
+    assert!(convert_text_graph(&text, &bin).is_err());
+    std::fs::remove_file(&text).unwrap();
+    std::fs::remove_file(&bin).unwrap();
+}
+
+
+fn import_str(name: &str, text: &str, format: ImportFormat, symmetric: bool) -> Imported {
+    let path = std::env::temp_dir().join(format!("pbbs_import_{}_{name}", std::process::id()));
+    std::fs::write(&path, text).unwrap();
+    assert_eq!(ImportFormat::guess(&path), Some(format));
+    let g = import(&path, format, symmetric);
+    std::fs::remove_file(&path).unwrap();
+    g.unwrap()
+}
+
+#[test]
+fn snap_import() {
+    let text = "# Nodes: 4 Edges: 5\n0\t1\n1\t2\n2\t2\n2\t0\n2\t3\n2\t3\n";
+    let g = import_str("g.txt", text, ImportFormat::Snap, false);
+    assert_eq!(g.n, 4);
+    // no self loop and no duplicate
+    assert_eq!(g.edges, [(0, 1), (1, 2), (2, 0), (2, 3)]);
+    assert!(g.weights.is_none());
+    assert_eq!(g.offsets(), [0, 1, 2, 4, 4]);
+
+    let g = import_str("s.txt", text, ImportFormat::Snap, true).to_graph();
+    assert_eq!(g.m, 8);
+    assert_eq!(g.index(2).neighbors, [0, 1, 3]);
+    assert_eq!(g.index(3).neighbors, [2]);
+}
+
+#[test]
+fn matrix_market_import() {
+    let text = "%%MatrixMarket matrix coordinate real symmetric\n% a comment\n\
+        4 4 3\n2 1 0.5\n3 2 1.5\n4 4 9\n";
+    let g = import_str("g.mtx", text, ImportFormat::MatrixMarket, false);
+    // 1-based and mirrored, without the diagonal entry
+    assert_eq!(g.n, 4);
+    assert_eq!(g.edges, [(0, 1), (1, 0), (1, 2), (2, 1)]);
+    assert_eq!(g.weights.as_deref(), Some(&[0.5, 0.5, 1.5, 1.5][..]));
+    let wes = g.to_wgh_edge_array();
+    assert_eq!((wes.n, wes.m, wes[2].w), (4, 4, 1.5));
+
+    let path = std::env::temp_dir().join(format!("pbbs_import_{}_array.mtx", std::process::id()));
+    std::fs::write(&path, "%%MatrixMarket matrix array real general\n2 2\n1\n2\n3\n4\n").unwrap();
+    assert!(import(&path, ImportFormat::MatrixMarket, false).is_err());
+    // mirroring these would need negated or conjugated weights
+    for kind in ["skew-symmetric", "hermitian"] {
+        std::fs::write(&path, format!("%%MatrixMarket matrix coordinate real {kind}\n2 2 1\n2 1 3\n")).unwrap();
+        let e = import(&path, ImportFormat::MatrixMarket, false).err().unwrap();
+        assert!(e.to_string().contains(kind));
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn guess_skips_pbbs_files() {
+    // a PBBS graph saved as .txt is not a SNAP edge list
+    let path = std::env::temp_dir().join(format!("pbbs_import_{}_adj.txt", std::process::id()));
+    std::fs::write(&path, "AdjacencyGraph\n2\n1\n0\n1\n1\n").unwrap();
+    assert_eq!(ImportFormat::guess(&path), None);
+    std::fs::write(&path, "WeightedEdgeArray\n0 1 2.5\n").unwrap();
+    assert_eq!(ImportFormat::guess(&path), None);
+    std::fs::write(&path, "0 1\n1 0\n").unwrap();
+    assert_eq!(ImportFormat::guess(&path), Some(ImportFormat::Snap));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn dimacs_import() {
+    let text = "c a comment\np sp 6 5\na 1 2 4\na 1 3 1\na 3 2 2\na 2 4 5\na 2 4 3\n";
+    let g = import_str("g.gr", text, ImportFormat::Dimacs, false);
+    // the declared vertex count holds even though vertices 5 and 6 have no
+    // arcs, and of the two arcs 2 -> 4 the lighter is kept
+    assert_eq!(g.n, 6);
+    assert_eq!(g.edges, [(0, 1), (0, 2), (1, 3), (2, 1)]);
+    assert_eq!(g.weights.as_deref(), Some(&[4.0, 1.0, 3.0, 2.0][..]));
+    let ea = g.to_edge_array();
+    assert_eq!((ea.num_rows, ea.non_zeros, ea[3].u, ea[3].v), (6, 4, 2, 1));
+}
+
+
+fn edges(g: &Graph) -> Vec<(u32, u32)> {
+    graph_to_edges(g).es.iter().map(|e| (e.u, e.v)).collect()
+}
+
+#[test]
+fn conversions() {
+    let es = [(2, 0), (0, 1), (2, 2), (0, 1), (1, 3), (0, 3)];
+    let ea = EdgeArray::new(es.iter().map(|&(u, v)| Edge::new(u, v)).collect(), 5, 5);
+    let g = edges_to_graph(&ea);
+    assert_eq!((g.n, g.m), (5, 6));
+    assert_eq!(g.offsets[..], [0, 3, 4, 6, 6, 6]);
+    assert_eq!(edges(&g), [(0, 1), (0, 1), (0, 3), (1, 3), (2, 0), (2, 2)]);
+
+    assert_eq!(edges(&dedup(&g)), [(0, 1), (0, 3), (1, 3), (2, 0), (2, 2)]);
+    assert_eq!(edges(&remove_self_loops(&g)), [(0, 1), (0, 1), (0, 3), (1, 3), (2, 0)]);
+    let s = symmetrize(&remove_self_loops(&g));
+    assert_eq!(s.offsets[..], [0, 3, 5, 6, 8, 8]);
+    assert_eq!(edges(&s), [(0, 1), (0, 2), (0, 3), (1, 0), (1, 3), (2, 0), (3, 0), (3, 1)]);
+
+    // by decreasing degree, ties by id
+    assert_eq!(relabeling(&s, Relabel::Degree, 0), [0, 1, 3, 2, 4]);
+    assert_eq!(relabeling(&s, Relabel::Bfs, 0), [0, 1, 2, 3, 4]);
+    let p = relabeling(&s, Relabel::Random, 7);
+    let mut sorted = p.clone();
+    sorted.sort();
+    assert_eq!(sorted, [0, 1, 2, 3, 4]);
+    let r = relabel(&s, &p);
+    let mut expected: Vec<_> = edges(&s).iter().map(|&(u, v)| (p[u as usize], p[v as usize])).collect();
+    expected.sort();
+    assert_eq!(edges(&r), expected);
+}
+
+#[test]
+fn weighted_conversions() {
+    let es = [(2, 0, 4.0), (0, 1, 3.0), (2, 2, 1.0), (0, 1, 2.0), (1, 3, 5.0)];
+    let ea = WghEdgeArray::new(es.iter().map(|&(u, v, w)| WghEdge::new(u, v, w)).collect(), 4);
+    let wes = |ea: &WghEdgeArray| -> Vec<(u32, u32, f32)> { ea.es.iter().map(|e| (e.u, e.v, e.w)).collect() };
+
+    let (g, w) = wgh_edges_to_graph(&ea);
+    assert_eq!(g.offsets[..], [0, 2, 3, 5, 5]);
+    assert_eq!(w, [2.0, 3.0, 5.0, 4.0, 1.0]);
+    assert_eq!(wes(&graph_to_wgh_edges(&g, &w)), [(0, 1, 2.0), (0, 1, 3.0), (1, 3, 5.0), (2, 0, 4.0), (2, 2, 1.0)]);
+
+    // the lightest of the duplicates, and reverse edges with the same weight
+    let d = wgh_dedup(&ea);
+    assert_eq!(wes(&d), [(0, 1, 2.0), (1, 3, 5.0), (2, 0, 4.0), (2, 2, 1.0)]);
+    let s = wgh_symmetrize(&wgh_remove_self_loops(&d));
+    assert_eq!(wes(&s), [(0, 1, 2.0), (0, 2, 4.0), (1, 0, 2.0), (1, 3, 5.0), (2, 0, 4.0), (3, 1, 5.0)]);
+    let r = wgh_relabel(&s, &[3, 2, 1, 0]);
+    assert_eq!(wes(&r), [(0, 2, 5.0), (1, 3, 4.0), (2, 0, 5.0), (2, 3, 2.0), (3, 1, 4.0), (3, 2, 2.0)]);
+}
+
+#[test]
+fn weighted_adjacency_graph() {
+    let path = std::env::temp_dir().join(format!("pbbs_wgh_adj_{}", std::process::id()));
+    let fname = path.to_str().unwrap();
+    std::fs::write(&path, "WeightedAdjacencyGraph\n3\n3\n0\n2\n2\n1\n2\n0\n7\n-1\n9\n").unwrap();
+    let (g, w) = read_wgh_graph_from_file(fname, BadLines::Skip).unwrap();
+    assert_eq!((g.n, g.m), (3, 3));
+    assert_eq!(g.index(0).neighbors, [1, 2]);
+    assert_eq!(w, [7.0, -1.0, 9.0]);
+    // one weight short
+    std::fs::write(&path, "WeightedAdjacencyGraph\n3\n3\n0\n2\n2\n1\n2\n0\n7\n-1\n").unwrap();
+    assert!(read_wgh_graph_from_file(fname, BadLines::Skip).is_err());
+    // an unweighted graph
+    std::fs::write(&path, "AdjacencyGraph\n2\n1\n0\n1\n1\n").unwrap();
+    assert!(read_wgh_graph_from_file(fname, BadLines::Skip).is_err());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn fractional_weights_survive_conversion() {
+    let dir = std::env::temp_dir();
+    let path = |name: &str| dir.join(format!("pbbs_frac_{name}_{}", std::process::id()));
+    let (wea, bin, back, wadj) = (path("g.wea"), path("g.bin"), path("back.wea"), path("g.wadj"));
+    let pbbs = |args: &[&std::path::Path]| std::process::Command::new(env!("CARGO_BIN_EXE_pbbs"))
+        .args(args).output().unwrap();
+    let arg = std::path::Path::new;
+
+    assert!(pbbs(&[arg("gen"), arg("rmat"), arg("64"), &wea, arg("-f"), arg("weighted-edge-array"),
+        arg("--symmetric"), arg("-s"), arg("3")]).status.success());
+    assert!(pbbs(&[arg("convert"), &wea, &bin]).status.success());
+    assert!(pbbs(&[arg("convert"), &bin, &back, arg("-f"), arg("weighted-edge-array")]).status.success());
+
+    let sorted = |p: &std::path::Path| {
+        let mut es: Vec<(u32, u32, f32)> = read_wgh_edge_array_from_file(p.to_str().unwrap(), BadLines::Skip)
+            .unwrap()
+            .es.iter().map(|e| (e.u, e.v, e.w)).collect();
+        es.sort_by_key(|e| (e.0, e.1));
+        es
+    };
+    let es = sorted(&wea);
+    assert!(es.iter().any(|e| e.2.fract() != 0.0));
+    assert_eq!(sorted(&back), es);
+    let (g, w) = read_wgh_graph_from_file(bin.to_str().unwrap(), BadLines::Skip).unwrap();
+    assert_eq!(g.m, es.len());
+    assert_eq!(w, es.iter().map(|e| e.2).collect::<Vec<_>>());
+
+    // WeightedAdjacencyGraph weights are integers
+    let out = pbbs(&[arg("convert"), &wea, &wadj, arg("-f"), arg("weighted-adjacency")]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("integer weights"));
+    for p in [wea, bin, back] { std::fs::remove_file(p).unwrap(); }
+    let _ = std::fs::remove_file(wadj);
+}
+
+#[test]
+fn conversions_at_scale() {
+    // enough edges for the parallel integer sort
+    let n = 3000;
+    let ea = EdgeArray::new(
+        (0..300_000u64).map(|i| {
+            let h = i.wrapping_mul(0x9E3779B97F4A7C15);
+            Edge::new((h >> 40) as u32 % n, (h >> 12) as u32 % n)
+        }).collect(),
+        n as usize, n as usize,
+    );
+    let g = symmetrize(&edges_to_graph(&ea));
+    assert!(edges(&g).windows(2).all(|w| w[0] < w[1]));
+    assert!((0..g.n).all(|u| g.index(u).neighbors.iter()
+        .all(|&v| g.index(v as usize).neighbors.binary_search(&(u as u32)).is_ok())));
+}
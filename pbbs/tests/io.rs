@@ -0,0 +1,124 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::path::PathBuf;
+
+use pbbs::common::geometry::Point2d;
+use pbbs::common::geometry_io::{read_points2d_from_file, read_triangles_from_file};
+use pbbs::common::graph_io::read_graph_from_file;
+use pbbs::common::io::{parse_line, parse_words, read_file_to_vec, split_records, BadLines};
+
+
+fn file(name: &str, text: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("pbbs_io_{name}_{}", std::process::id()));
+    std::fs::write(&path, text).unwrap();
+    path
+}
+
+
+#[test]
+fn headers_are_checked() {
+    let path = file("header", "sequenceDouble\n1.5\n");
+    let e = read_file_to_vec::<f64, _>(&path, Some("sequenceInt"), BadLines::Skip).unwrap_err();
+    assert_eq!(e.line, Some(1));
+    assert!(e.to_string().contains("sequenceInt"));
+    assert_eq!(read_file_to_vec::<f64, _>(&path, Some("sequenceDouble"), BadLines::Skip).unwrap(), [1.5]);
+
+    let e = read_points2d_from_file::<f64>(path.to_str().unwrap(), BadLines::Skip).err().unwrap();
+    assert_eq!(e.line, Some(1));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn inconsistent_files() {
+    // an offset past m
+    let path = file("graph", "AdjacencyGraph\n2\n1\n0\n5\n1\n");
+    let e = read_graph_from_file(path.to_str().unwrap(), BadLines::Skip).err().unwrap();
+    assert!(e.reason.contains("offset of vertex 1"), "{e}");
+    // one edge short
+    std::fs::write(&path, "AdjacencyGraph\n2\n2\n0\n1\n1\n").unwrap();
+    assert!(read_graph_from_file(path.to_str().unwrap(), BadLines::Skip).is_err());
+    // a triangle corner past the points
+    std::fs::write(&path, "pbbs_triangles\n3\n1\n0 0\n1 0\n0 1\n0 1 3\n").unwrap();
+    let e = read_triangles_from_file::<Point2d<f64>>(path.to_str().unwrap(), 0, BadLines::Skip).err().unwrap();
+    assert!(e.reason.contains("0 1 3"), "{e}");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn blank_lines() {
+    // the points end after n non-blank lines, not after n lines
+    let path = file("blank", "pbbs_triangles\n\n3\n1\n0 0\n\n1 0\n0 1\n\n0 1 2\n");
+    let t = read_triangles_from_file::<Point2d<f64>>(path.to_str().unwrap(), 0, BadLines::Skip).unwrap();
+    assert_eq!((t.p.len(), t.t.clone()), (3, vec![[0, 1, 2]]));
+    std::fs::write(&path, "AdjacencyGraph\n2\n\n1\n0\n1\n\n1\n").unwrap();
+    let g = read_graph_from_file(path.to_str().unwrap(), BadLines::Skip).unwrap();
+    assert_eq!((g.n, g.m), (2, 1));
+    std::fs::remove_file(&path).unwrap();
+
+    // several chunks, the split in a later one
+    let text: String = (0..200_000).map(|i| if i % 3 == 0 { "\n".into() } else { format!("{i}\n") }).collect();
+    let (head, body, lines) = split_records(text.as_bytes(), 100_000);
+    assert_eq!(lines, 150_000);
+    assert!(head.ends_with(b"149999\n") && body.starts_with(b"\n150001\n"));
+    assert_eq!(split_records(text.as_bytes(), 200_000).1, b"");
+}
+
+#[test]
+fn words() {
+    assert_eq!(parse_line::<u32>(b" 42\r"), Some(42));
+    assert_eq!(parse_line::<u32>(b"4294967296"), None);
+    assert_eq!(parse_line::<u8>(b"-1"), None);
+    assert_eq!(parse_line::<i64>(b"-9223372036854775808"), Some(i64::MIN));
+    assert_eq!(parse_line::<i32>(b"-+1"), None);
+    assert_eq!(parse_line::<(u32, f64)>(b"7\t-2.5e3"), Some((7, -2500.0)));
+    assert_eq!(parse_line::<[i32; 3]>(b"1 2 3"), Some([1, 2, 3]));
+    assert_eq!(parse_line::<[i32; 3]>(b"1 2 3 4"), None);
+    assert_eq!(parse_line::<Point2d<f64>>(b"0.5 1").map(|p| (p.x, p.y)), Some((0.5, 1.0)));
+}
+
+#[test]
+fn strict_mode() {
+    let path = file("strict", "sequenceInt\n1\n2\nthree\n4\n\n");
+    let v: Vec<u32> = read_file_to_vec(&path, Some("sequenceInt"), BadLines::Skip).unwrap();
+    assert_eq!(v, [1, 2, 4]);
+
+    let e = read_file_to_vec::<u32, _>(&path, Some("sequenceInt"), BadLines::Reject).unwrap_err();
+    assert_eq!(e.line, Some(4));
+    assert_eq!(e.to_string(), format!("{}:4: cannot parse 'three'", path.display()));
+    std::fs::remove_file(&path).unwrap();
+
+    // enough lines for several chunks, with bad ones in a few of them
+    let n = 200_000;
+    let text: String = (0..n)
+        .map(|i| if i % 30_011 == 7 { "bad\n".to_string() } else { format!("{i}\n") })
+        .collect();
+    let v: Vec<u32> = parse_words("mem", text.as_bytes(), 0, BadLines::Skip).unwrap();
+    let expected: Vec<u32> = (0..n).filter(|i| i % 30_011 != 7).collect();
+    assert_eq!(v, expected);
+    let e = parse_words::<u32, _>("mem", text.as_bytes(), 2, BadLines::Reject).unwrap_err();
+    assert_eq!(e.line, Some(3 + 7));
+}
@@ -0,0 +1,108 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+
+use pbbs::benchmarks::nearest_neighbors::{check::is_knn, cktree, kdtree, naive, point::KnnPoint};
+use pbbs::common::geometry::{Point2d, Point3d};
+use pbbs::generators::geometry::*;
+
+
+fn knn<P: KnnPoint>(ann: fn(&[P], usize, &mut Vec<Vec<usize>>), p: &[P], k: usize) -> Vec<Vec<usize>> {
+    let mut r = vec![vec![0; k]; p.len()];
+    ann(p, k, &mut r);
+    r
+}
+
+#[test]
+fn splits() {
+    let (a, b) = (Point3d::new(0.0, 0.0, 0.0), Point3d::new(1.0, -2.0, 4.0));
+    assert_eq!(a.get_split_point(&b), (2.0, 2));
+    assert_eq!(a.get_max_dim(&b), 4.0);
+    assert_eq!(a.get_distance(&b), 21f64.sqrt());
+    let c = a.centerv(&b);
+    assert_eq!((c.x, c.y, c.z), (0.5, -1.0, 2.0));
+
+    let (a, b) = (Point2d::new(0.0, 0.0), Point2d::new(3.0, 3.0));
+    assert_eq!(a.get_split_point(&b), (1.5, 0));
+}
+
+#[test]
+fn knn_2d_and_3d() {
+    let p2 = points2d(500, PointDist::InCube, 1);
+    let p3 = points3d(500, PointDist::InCube, 1);
+    let p3p = points3d(500, PointDist::Plummer, 1);
+    assert!(is_knn(&p2, &knn(naive::ann, &p2, 1), 1));
+    assert!(is_knn(&p3, &knn(naive::ann, &p3, 1), 1));
+    for k in [1, 4] {
+        assert!(is_knn(&p2, &knn(cktree::ann, &p2, k), k));
+        assert!(is_knn(&p3, &knn(cktree::ann, &p3, k), k));
+        assert!(is_knn(&p3p, &knn(cktree::ann, &p3p, k), k));
+        for p in [&p3, &p3p] {
+            assert!(is_knn(p, &knn(kdtree::ann, p, k), k));
+        }
+        assert!(is_knn(&p2, &knn(kdtree::ann, &p2, k), k));
+    }
+
+    // only a sample of the points is checked, so break all of them
+    let mut wrong = knn(naive::ann, &p3, 1);
+    wrong.iter_mut().for_each(|r| r[0] = (r[0] + 1) % p3.len());
+    assert!(!is_knn(&p3, &wrong, 1));
+    // the same neighbor twice
+    let mut twice = knn(kdtree::ann, &p2, 2);
+    twice.iter_mut().for_each(|r| r[0] = r[1]);
+    assert!(!is_knn(&p2, &twice, 2));
+}
+
+#[test]
+fn ties_in_any_order() {
+    // on a grid most points have several neighbors at the same distance
+    let p: Vec<_> = (0..50).map(|i| Point2d::new((i % 10) as f64, (i / 10) as f64)).collect();
+    assert!(is_knn(&p, &knn(naive::ann, &p, 1), 1));
+    for k in [1, 4, 6] {
+        assert!(is_knn(&p, &knn(kdtree::ann, &p, k), k));
+        let mut rev = knn(kdtree::ann, &p, k);
+        rev.iter_mut().for_each(|r| r.reverse());
+        // reversed, the nearest tie no longer comes last
+        assert_eq!(is_knn(&p, &rev, k), k == 1);
+    }
+}
+
+#[test]
+fn kdtree_corner_cases() {
+    // many copies of a few points: ties are broken by index
+    let p: Vec<_> = (0..2000).map(|i| Point3d::new((i % 3) as f64, 0.0, (i % 5) as f64)).collect();
+    for k in [1, 3, 20] {
+        assert!(is_knn(&p, &knn(kdtree::ann, &p, k), k));
+    }
+    // all the same point, still split down to the leaves
+    let p = vec![Point2d::new(1.0, 1.0); 100];
+    assert!(is_knn(&p, &knn(kdtree::ann, &p, 3), 3));
+    // fewer other points than k
+    let p = vec![Point2d::new(0.0, 0.0), Point2d::new(1.0, 0.0), Point2d::new(3.0, 0.0)];
+    assert_eq!(knn(kdtree::ann, &p, 2), vec![vec![2, 1], vec![2, 0], vec![0, 1]]);
+    assert_eq!(knn(kdtree::ann, &p, 3)[0], vec![2, 1, 0]);
+    assert!(knn(kdtree::ann, &p[..0], 1).is_empty());
+}
@@ -0,0 +1,78 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+
+use pbbs::runner::matrix::{Cell, Job, Matrix};
+
+
+fn job(benchmark: &str, algorithm: &str) -> Job {
+    Job {
+        benchmark: benchmark.to_string(),
+        algorithm: algorithm.to_string(),
+        input: "in".to_string(),
+        args: vec![],
+    }
+}
+
+fn cell(mean: f64) -> Cell {
+    Cell { features: vec![], mean, ci95: 0.0, check: None }
+}
+
+#[test]
+fn parse() {
+    let out = "mean:  1ms\nrounds: 1\n\
+        {\"benchmark\":\"mis\",\"features\":[\"mem_safe\"],\"mean\":0.5,\
+        \"ci95\":0.0,\"check\":true,\"phases\":[]}\ncheck: OK\n";
+    let c = Cell::parse(out).unwrap();
+    assert_eq!(c.features, ["mem_safe"]);
+    assert_eq!((c.mean, c.check), (0.5, Some(true)));
+    assert!(Cell::parse("mean:  1ms\n").is_err());
+}
+
+#[test]
+fn slowdowns() {
+    let m = Matrix {
+        profiles: vec!["default".to_string(), "unsafe".to_string()],
+        jobs: vec![job("mis", "a"), job("mis", "b"), job("sf", "a")],
+        cells: vec![
+            vec![cell(2.0), cell(1.0)],
+            vec![cell(8.0), cell(1.0)],
+            vec![cell(3.0), cell(2.0)],
+        ],
+    };
+    assert_eq!(m.slowdown(1, 0), 8.0);
+    assert_eq!(m.slowdown(2, 1), 1.0);
+
+    let geo = m.by_benchmark();
+    assert_eq!(geo.len(), 2);
+    assert_eq!(geo[0].0, "mis");
+    assert!((geo[0].1[0] - 4.0).abs() < 1e-9);
+    assert_eq!(geo[1].1, [1.5, 1.0]);
+
+    let table = m.to_string();
+    assert!(table.lines().nth(1).unwrap().contains("2.00x"));
+    assert!(table.contains("(geomean)"));
+}
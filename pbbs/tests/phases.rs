@@ -0,0 +1,91 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+
+// The phase collector is process wide, so this test has a binary of its own.
+
+use std::time::Duration;
+
+use parlay::{phases, set_verbosity, Timer, Verbosity};
+use pbbs::common::report::Record;
+use pbbs::common::time_loop::{time_loop, TimeConfig};
+
+pbbs::count_alloc!();
+
+
+#[test]
+fn phases_are_collected_per_round() {
+    set_verbosity(Verbosity::Quiet);
+    let cfg = TimeConfig { warmup: Duration::ZERO, ..TimeConfig::rounds(3) };
+    let mut round = 0;
+    let s = time_loop("test", &cfg, || {}, || {
+        let mut t = Timer::new("alg");
+        t.start();
+        std::hint::black_box(vec![1u8; 1 << 20]);
+        std::thread::sleep(Duration::from_millis(1));
+        t.next("first");
+        if round == 1 {
+            t.next("second");
+            t.next("second");
+        }
+        // never started, like most timers in the algorithms
+        let mut u = Timer::new("unstarted");
+        u.next("phase");
+        // a timer started within a phase must not hide what the phase peaked at
+        let mut outer = Timer::new("outer");
+        drop(std::hint::black_box(vec![1u8; 2 << 20]));
+        let mut inner = Timer::new("inner");
+        inner.next("phase");
+        drop(inner);
+        outer.next("phase");
+        round += 1;
+    }, || {});
+
+    assert_eq!(s.phases.len(), 5);
+    assert_eq!(s.phases[0].name, "alg:first");
+    assert_eq!(s.phases[1].name, "unstarted:phase");
+    assert_eq!(s.phases[3].name, "outer:phase");
+    assert_eq!(s.phases[4].name, "alg:second");
+    assert!(s.phases[0].samples.iter().all(|&d| d >= Duration::from_millis(1)));
+    assert_eq!(s.phases[4].samples.len(), 3);
+    assert_eq!(s.phases[4].samples[0], Duration::ZERO);
+    assert_eq!(s.phases[4].samples[2], Duration::ZERO);
+    assert!(!phases::is_enabled());
+
+    if cfg!(feature = "count_alloc") {
+        assert!(s.phases[0].alloc.iter().all(|a| a.allocated >= 1 << 20));
+        assert!(s.alloc.iter().all(|a| a.peak_live >= 2 << 20));
+        assert!(s.phases[3].alloc.iter().all(|a| a.peak_live >= 2 << 20));
+        assert!(s.phases[2].alloc.iter().all(|a| a.peak_live < 2 << 20));
+        assert_eq!(s.phases[4].alloc[0].allocated, 0);
+    } else {
+        assert!(s.alloc.is_empty() && s.phases[0].alloc.is_empty());
+    }
+
+    let r = Record::new("test", "alg", "", &s, None);
+    assert_eq!(r.phases[0].rounds.len(), 3);
+    assert!(r.to_csv().contains("alg:first="));
+}
@@ -0,0 +1,88 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+
+use std::fs;
+
+use pbbs::common::pool::{parse_cpu_list, pinned_pool, Pinning, PoolError, Topology};
+
+
+/// 2 sockets x 2 cores x 2 hyperthreads, numbered like linux usually does:
+/// cpus 0-3 are the first hyperthreads and 4-7 their siblings.
+fn dual_socket() -> Topology {
+    let root = std::env::temp_dir()
+        .join(format!("pbbs_pool_{}", std::process::id()));
+    fs::create_dir_all(&root).unwrap();
+    fs::write(root.join("online"), "0-7\n").unwrap();
+    for id in 0..8 {
+        let dir = root.join(format!("cpu{id}/topology"));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("physical_package_id"), format!("{}\n", id % 4 / 2)).unwrap();
+        fs::write(dir.join("core_id"), format!("{}\n", id % 2)).unwrap();
+    }
+    let t = Topology::from_sysfs(&root).unwrap();
+    fs::remove_dir_all(&root).unwrap();
+    t
+}
+
+#[test]
+fn policies() {
+    let t = dual_socket();
+    assert_eq!(Pinning::None.cpus(&t), None);
+    assert_eq!(Pinning::Compact.cpus(&t), Some(vec![0, 4, 1, 5, 2, 6, 3, 7]));
+    assert_eq!(Pinning::Scatter.cpus(&t), Some(vec![0, 2, 1, 3, 4, 6, 5, 7]));
+    assert_eq!(
+        Pinning::PhysicalCoresFirst.cpus(&t),
+        Some(vec![0, 1, 2, 3, 4, 5, 6, 7])
+    );
+    assert_eq!(Pinning::List(vec![3, 1]).cpus(&t), Some(vec![3, 1]));
+}
+
+#[test]
+fn parse() {
+    assert_eq!(parse_cpu_list("0-3,8,10-11"), Ok(vec![0, 1, 2, 3, 8, 10, 11]));
+    assert!(parse_cpu_list("3-1").is_err());
+    assert!(parse_cpu_list("").is_err());
+
+    for s in ["none", "compact", "scatter", "physical-cores-first", "0,2,4"] {
+        assert_eq!(s.parse::<Pinning>().unwrap().to_string(), s);
+    }
+    assert_eq!("1-2".parse(), Ok(Pinning::List(vec![1, 2])));
+    assert!("sockets".parse::<Pinning>().is_err());
+}
+
+#[test]
+fn pools() {
+    assert!(!Topology::detect().cpus.is_empty());
+    for pin in [Pinning::None, Pinning::default(), Pinning::List(vec![0])] {
+        let pool = pinned_pool(3, &pin).unwrap();
+        assert_eq!(pool.current_num_threads(), 3);
+        assert_eq!(pool.install(|| (0..100u64).sum::<u64>()), 4950);
+    }
+    // there is no such cpu
+    let e = pinned_pool(2, &Pinning::List(vec![1023])).err().unwrap();
+    assert!(matches!(e, PoolError::Pin { cpu: 1023, .. }), "{e}");
+}
@@ -0,0 +1,127 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+
+use std::time::Duration;
+
+use pbbs::common::perf::PerfCounts;
+use pbbs::common::report::{enabled_features, Record};
+use parlay::memory::AllocStats;
+use pbbs::common::time_loop::RunStats;
+
+
+fn stats() -> RunStats {
+    RunStats::new(
+        Duration::from_millis(1000),
+        vec![Duration::from_millis(1), Duration::from_millis(3)]
+    )
+}
+
+
+#[test]
+fn json_record() {
+    let r = Record::new("mis", "rustinc", "no/such/file", &stats(), Some(true));
+    let v: serde_json::Value = serde_json::from_str(&r.to_json()).unwrap();
+    assert_eq!(v["benchmark"], "mis");
+    assert_eq!(v["input_size"], 0);
+    assert_eq!(v["rounds"].as_array().unwrap().len(), 2);
+    assert_eq!(v["mean"], 0.002);
+    assert_eq!(v["min"], 0.001);
+    assert_eq!(v["check"], true);
+    assert_eq!(v["features"].as_array().unwrap().len(), enabled_features().len());
+}
+
+
+#[test]
+fn csv_record() {
+    let r = Record::new("wc", "parallel", "a,b", &stats(), None);
+    let row = r.to_csv();
+    assert!(row.starts_with("wc,parallel,\"a,b\",0,"));
+    assert!(row.ends_with(",0.001;0.003,,,,,,,,,,,,,"));
+    assert_eq!(
+        Record::CSV_HEADER.split(',').count(),
+        row.replace("\"a,b\"", "ab").split(',').count()
+    );
+}
+
+#[test]
+fn memory_record() {
+    let stats = RunStats {
+        peak_rss: vec![4096, 8192],
+        alloc: vec![
+            AllocStats { allocated: 10, peak_live: 5 },
+            AllocStats { allocated: 20, peak_live: 6 },
+        ],
+        ..stats()
+    };
+    let r = Record::new("knn", "cktree", "in", &stats, None);
+    assert!(r.to_csv().ends_with(",4096;8192,10;20,5;6,,,,,"));
+    let v: serde_json::Value = serde_json::from_str(&r.to_json()).unwrap();
+    assert_eq!(v["peak_rss"][1], 8192);
+    assert_eq!(v["allocated"][0], 10);
+    assert_eq!(v["peak_live"][1], 6);
+
+    let line = stats.to_string();
+    assert!(line.contains("peak rss: 0.0MiB"));
+    assert!(line.contains("allocated: 0.0MiB"));
+}
+
+#[test]
+fn perf_record() {
+    let counts = |cycles| PerfCounts {
+        cycles: Some(cycles),
+        context_switches: Some(1),
+        ..Default::default()
+    };
+    let stats = RunStats { perf: vec![counts(100), counts(300)], ..stats() };
+    let r = Record::new("bfs", "seq", "in", &stats, None);
+    assert!(r.to_csv().ends_with(",100;300,;,;,;,1;1"));
+    let v: serde_json::Value = serde_json::from_str(&r.to_json()).unwrap();
+    assert_eq!(v["perf"][1]["cycles"], 300);
+    assert!(v["perf"][0]["instructions"].is_null());
+    assert!(stats.to_string().ends_with("\nper round:  cycles: 200  context_switches: 1"));
+}
+
+#[test]
+fn legacy_binaries_report_the_check() {
+    let inp = std::env::temp_dir().join(format!("pbbs_report_sort_{}", std::process::id()));
+    std::fs::write(&inp, "sequenceInt\n3\n1\n2\n").unwrap();
+    let record = |extra: &[&str]| {
+        let out = std::process::Command::new(env!("CARGO_BIN_EXE_sort"))
+            .args(["--report", "json", "-r", "1", "--warmup", "0"])
+            .args(extra)
+            .arg(&inp)
+            .output()
+            .unwrap();
+        assert!(out.status.success());
+        let stdout = String::from_utf8(out.stdout).unwrap();
+        let line = stdout.lines().last().unwrap().to_string();
+        serde_json::from_str::<serde_json::Value>(&line).unwrap()
+    };
+    assert_eq!(record(&["-c"])["check"], true);
+    assert!(record(&[])["check"].is_null());
+    std::fs::remove_file(&inp).unwrap();
+}
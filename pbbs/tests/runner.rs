@@ -0,0 +1,233 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use pbbs::common::io::{read_file_to_vec, write_sequence, BadLines};
+use pbbs::common::pool::Pinning;
+use pbbs::common::time_loop::TimeConfig;
+use pbbs::runner::{find, parses, registry, Param, Params, RunConfig};
+
+
+const PARAMS: &[Param] = &[
+    Param { name: "k", default: Some("1"), help: "", valid: parses::<usize> },
+    Param { name: "buckets", default: None, help: "", valid: parses::<usize> },
+];
+
+fn strings(v: &[&str]) -> Vec<String> {
+    v.iter().map(|s| s.to_string()).collect()
+}
+
+
+#[test]
+fn params_defaults_and_overrides() {
+    let ps = Params::parse(PARAMS, &strings(&["buckets=16"])).unwrap();
+    assert_eq!(ps.get::<usize>("k"), 1);
+    assert_eq!(ps.get::<usize>("buckets"), 16);
+
+    let ps = Params::parse(PARAMS, &strings(&["buckets=4", "k=3"])).unwrap();
+    assert_eq!(ps.get::<usize>("k"), 3);
+}
+
+
+#[test]
+fn params_errors() {
+    assert!(Params::parse(PARAMS, &[]).is_err());
+    assert!(Params::parse(PARAMS, &strings(&["buckets"])).is_err());
+    assert!(Params::parse(PARAMS, &strings(&["buckets=1", "x=2"])).is_err());
+    // values are checked up front, defaults included
+    let e = Params::parse(PARAMS, &strings(&["buckets=many"])).err().unwrap();
+    assert!(e.contains("'many'") && e.contains("'buckets'"), "{e}");
+    assert!(Params::parse(PARAMS, &strings(&["buckets=1", "k=-1"])).is_err());
+    let knn = find("knn").unwrap();
+    assert!(Params::parse(knn.params(), &strings(&["dimension=3"])).is_ok());
+    assert!(Params::parse(knn.params(), &strings(&["dimension=4"])).is_err());
+}
+
+
+#[test]
+fn registry_is_consistent() {
+    let benches = registry();
+    let names: HashSet<_> = benches.iter().map(|b| b.name()).collect();
+    assert_eq!(names.len(), benches.len());
+    for b in benches {
+        assert!(!b.algorithms().is_empty());
+        assert_eq!(find(b.name()).unwrap().name(), b.name());
+    }
+    assert!(find("nope").is_none());
+}
+
+
+#[test]
+fn serial_variants() {
+    assert_eq!(find("mis").unwrap().serial(), Some("serial"));
+    assert_eq!(find("hist").unwrap().serial(), Some("sequential"));
+    assert_eq!(find("sort").unwrap().serial(), Some("std"));
+    assert_eq!(find("bfs").unwrap().serial(), None);
+}
+
+
+#[test]
+fn sweep_threads() {
+    // a ring over n vertices as an AdjacencyGraph
+    let n = 1000;
+    let mut text = format!("AdjacencyGraph\n{n}\n{}\n", 2 * n);
+    (0..n).for_each(|i| text += &format!("{}\n", 2 * i));
+    (0..n).for_each(|i| text += &format!("{}\n{}\n", (i + n - 1) % n, (i + 1) % n));
+    let path = std::env::temp_dir().join(format!("pbbs_ring_{}", std::process::id()));
+    std::fs::write(&path, text).unwrap();
+
+    let b = find("mis").unwrap();
+    let cfg = RunConfig {
+        algorithm: "rustinc",
+        ifname: path.to_str().unwrap(),
+        ofname: "",
+        time: TimeConfig { warmup: Duration::ZERO, ..TimeConfig::rounds(2) },
+        check: true,
+        params: Params::parse(b.params(), &[]).unwrap(),
+        pin: Pinning::default(),
+    };
+    let s = b.sweep(&cfg, &[1, 2]).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(s.serial.as_ref().unwrap().0, "serial");
+    assert_eq!(s.points.iter().map(|p| p.threads).collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(s.points[0].speedup, 1.0);
+    for p in &s.points {
+        assert_eq!(p.result.check, Some(true));
+        assert_eq!(p.result.stats.samples.len(), 2);
+        assert!((p.efficiency - p.speedup / p.threads as f64).abs() < 1e-12);
+        assert!(p.serial_speedup.unwrap() > 0.0);
+    }
+}
+
+
+#[test]
+fn unreadable_input() {
+    let b = find("sort").unwrap();
+    let cfg = RunConfig {
+        algorithm: b.algorithms()[0],
+        ifname: "/nonexistent/pbbs_input",
+        ofname: "",
+        time: TimeConfig::rounds(1),
+        check: false,
+        params: Params::parse(b.params(), &[]).unwrap(),
+        pin: Pinning::default(),
+    };
+    let e = b.execute(&cfg).err().unwrap();
+    assert_eq!(e.file.to_str(), Some("/nonexistent/pbbs_input"));
+    assert_eq!(e.line, None);
+}
+
+
+#[test]
+fn output_in_pbbs_format() {
+    let dir = std::env::temp_dir();
+    let (inp, out) = (
+        dir.join(format!("pbbs_seq_{}", std::process::id())),
+        dir.join(format!("pbbs_sorted_{}", std::process::id())),
+    );
+    write_sequence(&[3, 1, 2], "sequenceInt", &inp).unwrap();
+
+    let b = find("sort").unwrap();
+    let cfg = RunConfig {
+        algorithm: b.algorithms()[0],
+        ifname: inp.to_str().unwrap(),
+        ofname: out.to_str().unwrap(),
+        time: TimeConfig { warmup: Duration::ZERO, ..TimeConfig::rounds(1) },
+        check: false,
+        params: Params::parse(b.params(), &[]).unwrap(),
+        pin: Pinning::default(),
+    };
+    b.execute(&cfg).unwrap();
+    let sorted: Vec<i32> = read_file_to_vec(&out, Some("sequenceInt"), BadLines::Skip).unwrap();
+    std::fs::remove_file(&inp).unwrap();
+    std::fs::remove_file(&out).unwrap();
+    assert_eq!(sorted, [1, 2, 3]);
+}
+
+
+#[test]
+fn check_output_files() {
+    let dir = std::env::temp_dir();
+    let file = |name: &str, text: &str| {
+        let path = dir.join(format!("pbbs_check_{name}_{}", std::process::id()));
+        std::fs::write(&path, text).unwrap();
+        path.to_str().unwrap().to_string()
+    };
+    let b = find("sort").unwrap();
+    let ps = Params::parse(b.params(), &[]).unwrap();
+    let inp = file("in", "sequenceInt\n3\n1\n2\n");
+    let good = file("good", "sequenceInt\n1\n2\n3\n");
+    let bad = file("bad", "sequenceInt\n1\n3\n2\n");
+    assert_eq!(b.check_file(&inp, &good, &ps).unwrap(), Some(true));
+    assert_eq!(b.check_file(&inp, &bad, &ps).unwrap(), Some(false));
+    assert!(b.check_file(&inp, &inp.replace("in", "missing"), &ps).is_err());
+    // sorted, but not the input's keys
+    let zeros = file("zeros", "sequenceInt\n0\n0\n0\n");
+    assert_eq!(b.check_file(&inp, &zeros, &ps).unwrap(), Some(false));
+    let b = find("isort").unwrap();
+    let ips = Params::parse(b.params(), &[]).unwrap();
+    assert_eq!(b.check_file(&inp, &good, &ips).unwrap(), Some(true));
+    assert_eq!(b.check_file(&inp, &zeros, &ips).unwrap(), Some(false));
+
+    // a square split in two, and with a third triangle on the diagonal
+    let b = find("dr").unwrap();
+    let ps = Params::parse(b.params(), &[]).unwrap();
+    let square = "pbbs_triangles\n5\n2\n0 0\n1 0\n1 1\n0 1\n2 2\n0 1 2\n0 2 3\n";
+    let square = file("square", square);
+    let three = file("three", "pbbs_triangles\n5\n3\n0 0\n1 0\n1 1\n0 1\n2 2\n0 1 2\n0 2 3\n0 2 4\n");
+    assert_eq!(b.check_file(&square, &square, &ps).unwrap(), Some(true));
+    assert_eq!(b.check_file(&square, &three, &ps).unwrap(), Some(false));
+
+    // word counts in any order, with the sequenceStringIntPair header
+    let b = find("wc").unwrap();
+    let ps = Params::parse(b.params(), &[]).unwrap();
+    let text = file("text", "b a\nb\n");
+    let counts = file("counts", "sequenceStringIntPair\na 1\nb 2\n");
+    let wrong = file("wrong", "sequenceStringIntPair\nb 1\na 1\n");
+    let headless = file("headless", "a 1\nb 2\n");
+    assert_eq!(b.check_file(&text, &counts, &ps).unwrap(), Some(true));
+    assert_eq!(b.check_file(&text, &wrong, &ps).unwrap(), Some(false));
+    assert!(b.check_file(&text, &headless, &ps).is_err());
+
+    // points on a line: the middle ones have two nearest neighbors
+    let b = find("knn").unwrap();
+    let ps = Params::parse(b.params(), &[]).unwrap();
+    let line = file("line", "pbbs_sequencePoint2d\n0 0\n1 0\n2 0\n3 0\n");
+    let left = file("left", "sequenceInt\n1\n0\n1\n2\n");
+    let right = file("right", "sequenceInt\n1\n2\n3\n2\n");
+    let far = file("far", "sequenceInt\n2\n0\n1\n2\n");
+    assert_eq!(b.check_file(&line, &left, &ps).unwrap(), Some(true));
+    assert_eq!(b.check_file(&line, &right, &ps).unwrap(), Some(true));
+    assert_eq!(b.check_file(&line, &far, &ps).unwrap(), Some(false));
+
+    for f in [inp, good, bad, zeros, square, three, text, counts, wrong, headless, line, left, right, far] {
+        std::fs::remove_file(f).unwrap();
+    }
+}
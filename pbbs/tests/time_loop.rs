@@ -0,0 +1,96 @@
+
+// ============================================================================
+// This code is part of RPB.
+// ----------------------------------------------------------------------------
+// MIT License
+//
+// Copyright (c) 2023-present Javad Abdi, Mark C. Jeffrey
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+
+use std::time::Duration;
+
+use pbbs::common::time_loop::{time_loop, RunStats, TimeConfig};
+
+
+fn ms(v: &[u64]) -> Vec<Duration> {
+    v.iter().map(|&m| Duration::from_millis(m)).collect()
+}
+
+fn no_warmup(rounds: usize) -> TimeConfig {
+    TimeConfig { warmup: Duration::ZERO, ..TimeConfig::rounds(rounds) }
+}
+
+
+#[test]
+fn stats_summary() {
+    let s = RunStats::new(Duration::ZERO, ms(&[4, 1, 3, 2]));
+    assert_eq!(s.samples, ms(&[4, 1, 3, 2]));
+    assert_eq!(s.min, Duration::from_millis(1));
+    assert_eq!(s.max, Duration::from_millis(4));
+    assert_eq!(s.mean, Duration::from_micros(2500));
+    assert_eq!(s.median, Duration::from_micros(2500));
+    // sample stddev of 1..=4 is sqrt(5/3) ms; t(3) = 3.182
+    assert!((s.stddev.as_secs_f64() - (5.0f64 / 3.0).sqrt() / 1e3).abs() < 1e-9);
+    let ci = 3.182 * s.stddev.as_secs_f64() / 2.0;
+    assert!((s.ci95.as_secs_f64() - ci).abs() < 1e-9);
+
+    let one = RunStats::new(Duration::ZERO, ms(&[5]));
+    assert_eq!(one.median, Duration::from_millis(5));
+    assert_eq!(one.stddev, Duration::ZERO);
+}
+
+
+#[test]
+fn fixed_rounds() {
+    let mut runs = 0;
+    let s = time_loop("test", &no_warmup(4), || {}, || runs += 1, || {});
+    assert_eq!(s.samples.len(), 4);
+    assert_eq!(runs, 4);
+    assert_eq!(s.peak_rss.len(), 4);
+    // one count per round, if the counters are available at all
+    if cfg!(feature = "perf") {
+        assert!(s.perf.is_empty() || s.perf.len() == 4);
+    } else {
+        assert!(s.perf.is_empty());
+    }
+}
+
+
+#[test]
+fn min_time_and_max_rounds() {
+    let sleep = || std::thread::sleep(Duration::from_millis(2));
+    let cfg = TimeConfig { min_time: Duration::from_millis(20), ..no_warmup(1) };
+    let s = time_loop("test", &cfg, || {}, sleep, || {});
+    assert!(s.samples.iter().sum::<Duration>() >= cfg.min_time);
+
+    let cfg = TimeConfig { max_rounds: 3, ..cfg };
+    assert_eq!(time_loop("test", &cfg, || {}, sleep, || {}).samples.len(), 3);
+}
+
+
+#[test]
+fn ci_target() {
+    let sleep = || std::thread::sleep(Duration::from_millis(1));
+    let cfg = TimeConfig { ci: Some(50.0), ..no_warmup(1) };
+    let s = time_loop("test", &cfg, || {}, sleep, || {});
+    assert!(s.samples.len() >= 2);
+    assert!(s.ci95 <= s.mean / 2 || s.samples.len() == cfg.max_rounds);
+}